@@ -0,0 +1,61 @@
+//! Compares resolving line numbers of many positions of a large document by a linear
+//! scan over its lines for each position against the binary search over the line
+//! offsets that are cached once per parse, run with `cargo bench -p dotrain`
+
+use std::{hint::black_box, time::Instant};
+use dotrain::{line_number_at, line_offsets, RainDocument};
+
+/// the line number lookup as it was done before caching the line offsets
+fn linear_line_number(text: &str, pos: usize) -> usize {
+    let lines: Vec<_> = text.split_inclusive('\n').collect();
+    if pos >= text.len() {
+        lines.len()
+    } else {
+        let mut c = 0;
+        for (i, &s) in lines.iter().enumerate() {
+            c += s.len();
+            if pos <= c {
+                return i;
+            }
+        }
+        0
+    }
+}
+
+fn main() {
+    let mut text = "---\n".to_owned();
+    for i in 0..2000 {
+        text.push_str(&format!("#binding-{i}\n_: add(1 2 3),\n_: mul(4 5);\n"));
+    }
+    let rain_document = RainDocument::create(text.clone(), None, None, None);
+    let positions: Vec<usize> = (0..text.len()).step_by(97).collect();
+
+    let start = Instant::now();
+    let linear: Vec<usize> = positions
+        .iter()
+        .map(|&pos| linear_line_number(black_box(&text), pos))
+        .collect();
+    let linear_elapsed = start.elapsed();
+
+    let start = Instant::now();
+    let offsets = line_offsets(&text);
+    let cached: Vec<usize> = positions
+        .iter()
+        .map(|&pos| line_number_at(black_box(&offsets), text.len(), pos))
+        .collect();
+    let cached_elapsed = start.elapsed();
+
+    assert_eq!(linear, cached);
+    assert!(positions
+        .iter()
+        .zip(&cached)
+        .all(|(&pos, &line)| rain_document.line_number(pos) == line));
+
+    println!(
+        "{} lookups over {} lines: linear scan {:?}, cached line offsets {:?}",
+        positions.len(),
+        offsets.len(),
+        linear_elapsed,
+        cached_elapsed,
+    );
+}
@@ -3,7 +3,11 @@ use std::{
     fs::read_to_string,
     sync::{Arc, RwLock},
 };
-use super::{rainconfig::RainConfigStruct, super::parser::raindocument::RainDocument, Compose};
+use super::{
+    rainconfig::RainConfigStruct,
+    super::{error::ComposeError, parser::raindocument::RainDocument},
+    Compose,
+};
 
 /// Composes only the given .rain files based on provided options
 pub async fn compose_target(opts: Compose) -> anyhow::Result<String> {
@@ -36,5 +40,17 @@ pub async fn compose_target(opts: Compose) -> anyhow::Result<String> {
         .map(|e| e.as_str())
         .collect::<Vec<&str>>();
 
-    Ok(rain_document.compose(&entrypoints)?)
+    match rain_document.compose(&entrypoints) {
+        Ok(rainlang) => Ok(rainlang),
+        // render the problems as annotated snippets of the input text
+        Err(ComposeError::Problems(problems)) => {
+            let uri = opts.input.to_string_lossy();
+            Err(anyhow::anyhow!(problems
+                .iter()
+                .map(|p| p.render(rain_document.text(), &uri))
+                .collect::<Vec<_>>()
+                .join("\n\n")))
+        }
+        Err(e) => Err(e.into()),
+    }
 }
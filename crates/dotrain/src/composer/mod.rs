@@ -1,19 +1,22 @@
 use rain_metadata::Store;
 use std::{
     sync::{Arc, RwLock},
-    collections::VecDeque,
+    collections::{BTreeMap, BTreeSet, VecDeque},
 };
 use magic_string::{MagicString, OverwriteOptions, GenerateDecodedMapOptions};
 use crate::types::ast::PragmaStatement;
 
 use super::{
     error::{ComposeError, ErrorCode},
-    parser::{RainlangDocument, RainDocument, exclusive_parse, Rebind},
+    parser::{
+        RainlangDocument, RainDocument, ParseOptions, exclusive_parse, search_binding_ref, to_u256,
+        Rebind,
+    },
     types::{
-        patterns::{WORD_PATTERN, NAMESPACE_SEGMENT_PATTERN},
+        patterns::{BINARY_PATTERN, WORD_PATTERN, NAMESPACE_SEGMENT_PATTERN},
         ast::{
             Offsets, Problem, Node, Namespace, NamespaceItem, NamespaceLeaf, Binding, BindingItem,
-            Import,
+            Import, FrozenDocument,
         },
     },
 };
@@ -66,11 +69,15 @@ impl<'a> ComposeTarget<'a> {
     }
 
     fn build_name_comment(&self, index: usize) -> String {
-        let header = "/* ".to_string() + &index.to_string() + ". ";
+        "/* ".to_string() + &index.to_string() + ". " + &self.qualified_name() + " */ \n"
+    }
+
+    /// the full name of this target, i.e. its name prefixed by its namespace path if any
+    fn qualified_name(&self) -> String {
         if self.namespace_path.is_empty() {
-            header + self.element.name + " */ \n"
+            self.element.name.to_owned()
         } else {
-            header + &self.namespace_path + "." + self.element.name + " */ \n"
+            self.namespace_path.clone() + "." + self.element.name
         }
     }
 }
@@ -95,6 +102,212 @@ impl RainDocument {
         Ok(rainlang_string)
     }
 
+    /// the ordered list of the bindings that composing the given entrypoints produces sources
+    /// for, i.e. the order the expressions are expected in when deployed, which is the given
+    /// entrypoints followed by their dependencies in the order they are first reached, a source's
+    /// index in the list is what its quotes are replaced with, bindings are identified by their
+    /// full namespace paths (as in the composed sources' name comments), errors the same way as
+    /// composing if any of them cannot be resolved or if there are circular dependencies
+    pub fn deployment_order(&self, entrypoints: &[&str]) -> Result<Vec<String>, ComposeError> {
+        Ok(self
+            .build_targets_sourcemap(entrypoints)?
+            .iter()
+            .map(|v| v.target.qualified_name())
+            .collect())
+    }
+
+    /// validates that none of the given entrypoints and none of their dependencies (resolved
+    /// recursively the same way as composing does) reference an elided binding, reports an
+    /// ElidedBinding problem at each of such references, or at the import's hash if the reference
+    /// is in an imported binding, since composing them would fail, unresolvable entrypoints and
+    /// dependencies are ignored
+    pub fn validate_elided_references(&self, entrypoints: &[&str]) -> Vec<Problem> {
+        let mut problems = vec![];
+        let mut targets = VecDeque::new();
+        let flat_namespace = self.flat_namespace();
+        for entrypoint in entrypoints {
+            match search_namespace(entrypoint, &self.namespace, "") {
+                Ok(target) => targets.push_back(target),
+                Err(_) => {
+                    if let Some(leaf) = flat_namespace.get(entrypoint.trim_start_matches('.')) {
+                        if let BindingItem::Elided(e) = &leaf.element.item {
+                            problems.push(ErrorCode::ElidedBinding.to_problem(
+                                vec![entrypoint, &e.msg],
+                                self.leaf_position(leaf, leaf.element.name_position),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut visited = vec![];
+        while let Some((namespace, leaf, binding, namespace_path)) = targets.pop_front() {
+            let key = (namespace_path.clone(), &binding.name, leaf.import_index);
+            if visited.contains(&key) {
+                continue;
+            }
+            visited.push(key);
+            let rainlang_doc = RainlangDocument::create(
+                binding.content.clone(),
+                namespace,
+                self.known_words.as_ref(),
+                &self.options,
+            );
+            for problem in &rainlang_doc.problems {
+                if problem.code == ErrorCode::ElidedBinding {
+                    let problem = Problem {
+                        msg: problem.msg.clone(),
+                        code: problem.code,
+                        position: self
+                            .leaf_position(leaf, binding.map_to_document(problem.position)),
+                    };
+                    if !problems.contains(&problem) {
+                        problems.push(problem);
+                    }
+                }
+            }
+            for dep in &rainlang_doc.dependencies {
+                if let Ok(target) = search_namespace(dep, namespace, &namespace_path) {
+                    targets.push_back(target);
+                }
+            }
+        }
+        problems
+    }
+
+    /// checks whether the given bindings are independent of each other, i.e. neither of them
+    /// transitively quotes the other (dependencies are resolved recursively the same way as
+    /// composing does) so they can be evaluated in parallel, a binding is not independent of
+    /// itself, errors if any of the names is not a binding of this instance's namespace
+    pub fn are_independent(&self, a: &str, b: &str) -> Result<bool, ComposeError> {
+        let flat_namespace = self.flat_namespace();
+        for name in [a, b] {
+            if !flat_namespace.contains_key(name.trim_start_matches('.')) {
+                return Err(ComposeError::Reject(format!(
+                    "undefined identifier: {}",
+                    name
+                )));
+            }
+        }
+        let a_deps = self.quoted_dependencies(a);
+        let b_deps = self.quoted_dependencies(b);
+        let depends_on = |deps: &[(String, String, isize)], other: &[(String, String, isize)]| {
+            other.first().is_some_and(|v| deps.contains(v))
+        };
+        Ok(!depends_on(&a_deps, &b_deps) && !depends_on(&b_deps, &a_deps))
+    }
+
+    /// the given binding (quotes resolved to the quoted binding) followed by all of its quoted
+    /// dependencies resolved recursively, identified by their namespace paths, names and import
+    /// indexes, empty if the binding cannot be resolved as an expression, unresolvable
+    /// dependencies are ignored
+    fn quoted_dependencies(&self, name: &str) -> Vec<(String, String, isize)> {
+        let mut visited = vec![];
+        let mut targets = VecDeque::new();
+        if let Ok(target) = search_namespace(name, &self.namespace, "") {
+            targets.push_back(target);
+        }
+        while let Some((namespace, leaf, binding, namespace_path)) = targets.pop_front() {
+            let key = (
+                namespace_path.clone(),
+                binding.name.clone(),
+                leaf.import_index,
+            );
+            if visited.contains(&key) {
+                continue;
+            }
+            visited.push(key);
+            let rainlang_doc = RainlangDocument::create(
+                binding.content.clone(),
+                namespace,
+                self.known_words.as_ref(),
+                &self.options,
+            );
+            for dep in &rainlang_doc.dependencies {
+                if let Ok(target) = search_namespace(dep, namespace, &namespace_path) {
+                    targets.push_back(target);
+                }
+            }
+        }
+        visited
+    }
+
+    /// The quote dependencies between this instance's bindings (the quotes of its own bindings
+    /// resolved recursively the same way as composing does) as a Graphviz DOT digraph, quote
+    /// bindings are resolved to the bindings they quote, quoted elided bindings are dashed nodes
+    /// and the edges that are part of a dependency cycle are colored red, imported bindings are
+    /// named by their namespace paths
+    pub fn dependency_graph_dot(&self) -> String {
+        // node names mapped to whether they are elided bindings
+        let mut nodes: BTreeMap<String, bool> = BTreeMap::new();
+        let mut edges: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut targets = VecDeque::new();
+        for binding in &self.bindings {
+            match &binding.item {
+                BindingItem::Elided(_) => {
+                    nodes.insert(binding.name.clone(), true);
+                }
+                BindingItem::Exp(_) => {
+                    if let Ok(target) = search_namespace(&binding.name, &self.namespace, "") {
+                        targets.push_back(target);
+                    }
+                }
+                _ => {}
+            }
+        }
+        while let Some((namespace, _leaf, binding, namespace_path)) = targets.pop_front() {
+            let name = graph_node_name(&namespace_path, &binding.name);
+            if nodes.insert(name.clone(), false).is_some() {
+                continue;
+            }
+            let BindingItem::Exp(exp) = &binding.item else {
+                continue;
+            };
+            for dep in &exp.dependencies {
+                if let Ok(target) = search_namespace(dep, namespace, &namespace_path) {
+                    let dep_name = graph_node_name(&target.3, &target.2.name);
+                    targets.push_back(target);
+                    edges.entry(name.clone()).or_default().insert(dep_name);
+                }
+            }
+            // quoted elided bindings are not dependencies, since they cannot be composed
+            for (quote, _) in exp.quotes() {
+                if let Some(Binding {
+                    item: BindingItem::Elided(_),
+                    ..
+                }) = search_binding_ref(&quote, namespace)
+                {
+                    let dep_name = graph_node_name(&namespace_path, quote.trim_start_matches('.'));
+                    nodes.insert(dep_name.clone(), true);
+                    edges.entry(name.clone()).or_default().insert(dep_name);
+                }
+            }
+        }
+
+        // an edge is part of a cycle if both of its ends are in the same strongly connected component
+        let components = strongly_connected_components(&edges);
+        let mut dot = "digraph dependencies {\n".to_owned();
+        for (name, is_elided) in &nodes {
+            if *is_elided {
+                dot.push_str(&format!("    \"{}\" [style=dashed];\n", name));
+            } else {
+                dot.push_str(&format!("    \"{}\";\n", name));
+            }
+        }
+        for (name, deps) in &edges {
+            for dep in deps {
+                if components.get(name.as_str()) == components.get(dep.as_str()) {
+                    dot.push_str(&format!("    \"{}\" -> \"{}\" [color=red];\n", name, dep));
+                } else {
+                    dot.push_str(&format!("    \"{}\" -> \"{}\";\n", name, dep));
+                }
+            }
+        }
+        dot.push('}');
+        dot
+    }
+
     /// composes a given text as RainDocument into rainlang with remote meta search disabled for parsing
     pub fn compose_text(
         text: &str,
@@ -118,6 +331,39 @@ impl RainDocument {
     }
 }
 
+impl FrozenDocument {
+    /// Builds a meta Store that only contains this snapshot's metas
+    pub fn store(&self) -> Store {
+        let mut store = Store::default();
+        for (hash, meta) in &self.metas {
+            if let Ok(hash) = alloy_primitives::hex::decode(hash) {
+                store.update_with(&hash, meta);
+            }
+        }
+        store
+    }
+
+    /// Composes this snapshot into rainlang the same way its RainDocument is composed, without
+    /// any meta search, the document itself is not parsed again but the expressions of the
+    /// composed bindings are, with the same parse options the document was parsed with, errors
+    /// if the snapshot's parse options are invalid
+    pub fn compose(&self, entrypoints: &[&str]) -> Result<String, ComposeError> {
+        let options = ParseOptions::try_from(&self.options)
+            .map_err(|e| ComposeError::Reject(e.to_string()))?;
+        let mut rain_document = RainDocument::new(
+            self.text.clone(),
+            Some(Arc::new(RwLock::new(self.store()))),
+            0,
+            self.known_words.clone(),
+        );
+        rain_document.namespace = self.namespace.clone();
+        rain_document.bindings = self.bindings.clone();
+        rain_document.imports = self.imports.clone();
+        rain_document.options = options;
+        rain_document.compose(entrypoints)
+    }
+}
+
 impl RainDocument {
     /// builds composing targets sourcemaps
     pub(crate) fn build_targets_sourcemap(
@@ -127,8 +373,14 @@ impl RainDocument {
         if entrypoints.is_empty() {
             return Err(ComposeError::Reject("no entrypoints specified".to_owned()));
         }
-        if !self.problems.is_empty() {
-            return Err(ComposeError::Problems(self.problems.clone()));
+        if self.problems.iter().any(|p| !p.code.is_warning()) {
+            return Err(ComposeError::Problems(
+                self.problems
+                    .iter()
+                    .filter(|p| !p.code.is_warning())
+                    .cloned()
+                    .collect(),
+            ));
         }
 
         let mut nodes: Vec<ComposeTarget> = vec![];
@@ -137,18 +389,34 @@ impl RainDocument {
         for entrypoint in entrypoints {
             match search_namespace(entrypoint, &self.namespace, "") {
                 Ok((parent_namespace, leaf, binding, namespace_path)) => {
-                    if !binding.problems.is_empty() {
+                    let errors = binding
+                        .problems
+                        .iter()
+                        .filter(|p| !p.code.is_warning())
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if !errors.is_empty() {
                         return Err(ComposeError::from_problems(
-                            &binding.problems,
+                            &errors,
                             leaf.import_index,
                             &self.imports,
                         ));
                     }
-                    let rainlang_doc =
-                        RainlangDocument::create(binding.content.clone(), parent_namespace, None);
-                    if !rainlang_doc.problems.is_empty() {
+                    let rainlang_doc = RainlangDocument::create(
+                        binding.content.clone(),
+                        parent_namespace,
+                        self.known_words.as_ref(),
+                        &self.options,
+                    );
+                    let errors = rainlang_doc
+                        .problems
+                        .iter()
+                        .filter(|p| !p.code.is_warning())
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if !errors.is_empty() {
                         return Err(ComposeError::from_problems(
-                            &rainlang_doc.problems,
+                            &errors,
                             leaf.import_index,
                             &self.imports,
                         ));
@@ -252,6 +520,15 @@ impl RainDocument {
         Ok(sourcemaps)
     }
 
+    /// the given position if the leaf is local, else its import's hash position
+    fn leaf_position(&self, leaf: &NamespaceLeaf, position: Offsets) -> Offsets {
+        if leaf.import_index == -1 {
+            position
+        } else {
+            self.imports[leaf.import_index as usize].hash_position
+        }
+    }
+
     /// resolves dependencies recuresively
     /// this means resolving deps of deps recursively as long as any of them have nested deps
     /// gathers all the deps into 'nodes' array for building the sourcemap and returns deps indexes
@@ -260,6 +537,7 @@ impl RainDocument {
     /// indexes, for example [[], [2, 3], [], []],  will indicate that composing- node[0], node[2] and node[3]
     /// have no deps, node[1] has 2 deps with index 2 and 3 in order, so when first dependency is reached
     /// when building the sourcemap for node[1], it will be replaced with '2' and the next one with '3'
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
     fn resolve_deps<'a>(
         &'a self,
         nodes: &mut Vec<ComposeTarget<'a>>,
@@ -272,11 +550,20 @@ impl RainDocument {
             for node in nodes[ignore_offset..].iter() {
                 let mut this_node_deps_indexes = VecDeque::new();
                 for dep in &node.element.item.dependencies {
+                    #[cfg(feature = "tracing")]
+                    let _span =
+                        tracing::debug_span!("resolve_dependency", binding = %dep).entered();
                     match search_namespace(dep, node.namespace, &node.namespace_path) {
                         Ok((parent_node, leaf, binding, namespace_path)) => {
-                            if !binding.problems.is_empty() {
+                            let errors = binding
+                                .problems
+                                .iter()
+                                .filter(|p| !p.code.is_warning())
+                                .cloned()
+                                .collect::<Vec<_>>();
+                            if !errors.is_empty() {
                                 return Err(ComposeError::from_problems(
-                                    &binding.problems,
+                                    &errors,
                                     leaf.import_index,
                                     &self.imports,
                                 ));
@@ -284,11 +571,18 @@ impl RainDocument {
                             let rainlang_doc = RainlangDocument::create(
                                 binding.content.clone(),
                                 parent_node,
-                                None,
+                                self.known_words.as_ref(),
+                                &self.options,
                             );
-                            if !rainlang_doc.problems.is_empty() {
+                            let errors = rainlang_doc
+                                .problems
+                                .iter()
+                                .filter(|p| !p.code.is_warning())
+                                .cloned()
+                                .collect::<Vec<_>>();
+                            if !errors.is_empty() {
                                 return Err(ComposeError::from_problems(
-                                    &rainlang_doc.problems,
+                                    &errors,
                                     leaf.import_index,
                                     &self.imports,
                                 ));
@@ -412,6 +706,79 @@ fn search_namespace<'a>(
     }
 }
 
+/// the name of a binding's node in the dependency graph, i.e. its namespace path joined
+/// with its name without the leading dot
+fn graph_node_name(namespace_path: &str, name: &str) -> String {
+    if namespace_path.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", namespace_path.trim_start_matches('.'), name)
+    }
+}
+
+/// the strongly connected components of the given directed graph (Tarjan's algorithm), i.e.
+/// each node mapped to the index of the component it belongs to, nodes without any edges are left out
+fn strongly_connected_components(
+    edges: &BTreeMap<String, BTreeSet<String>>,
+) -> BTreeMap<&str, usize> {
+    struct Tarjan<'a> {
+        edges: &'a BTreeMap<String, BTreeSet<String>>,
+        indexes: BTreeMap<&'a str, usize>,
+        low_links: BTreeMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: BTreeSet<&'a str>,
+        components: BTreeMap<&'a str, usize>,
+        component_count: usize,
+    }
+    impl<'a> Tarjan<'a> {
+        fn visit(&mut self, node: &'a str) {
+            let index = self.indexes.len();
+            self.indexes.insert(node, index);
+            self.low_links.insert(node, index);
+            self.stack.push(node);
+            self.on_stack.insert(node);
+            let edges = self.edges;
+            for dep in edges.get(node).into_iter().flatten() {
+                let dep = dep.as_str();
+                if !self.indexes.contains_key(dep) {
+                    self.visit(dep);
+                    let low_link = self.low_links[node].min(self.low_links[dep]);
+                    self.low_links.insert(node, low_link);
+                } else if self.on_stack.contains(dep) {
+                    let low_link = self.low_links[node].min(self.indexes[dep]);
+                    self.low_links.insert(node, low_link);
+                }
+            }
+            if self.low_links[node] == index {
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(member);
+                    self.components.insert(member, self.component_count);
+                    if member == node {
+                        break;
+                    }
+                }
+                self.component_count += 1;
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        edges,
+        indexes: BTreeMap::new(),
+        low_links: BTreeMap::new(),
+        stack: vec![],
+        on_stack: BTreeSet::new(),
+        components: BTreeMap::new(),
+        component_count: 0,
+    };
+    for node in edges.keys() {
+        if !tarjan.indexes.contains_key(node.as_str()) {
+            tarjan.visit(node);
+        }
+    }
+    tarjan.components
+}
+
 /// builds sourcemaps for a given array of AST Nodes recursively
 fn build_sourcemap<'a>(
     nodes: impl Iterator<Item = &'a Node>,
@@ -433,6 +800,17 @@ fn build_sourcemap<'a>(
                 }
             }
             Node::Opcode(opcode) => {
+                // opcodes referenced by an alias are composed as their canonical word
+                if let Some(canonical) = &opcode.opcode.canonical {
+                    generator
+                        .overwrite(
+                            opcode.opcode.position[0] as i64,
+                            opcode.opcode.position[1] as i64,
+                            canonical,
+                            OverwriteOptions::default(),
+                        )
+                        .or(Err("could not build sourcemap".to_owned()))?;
+                }
                 let args_details = if let Some(operand_args) = &opcode.operand_args {
                     operand_args
                         .args
@@ -446,6 +824,26 @@ fn build_sourcemap<'a>(
                 } else {
                     vec![]
                 };
+                // binary operand args are not a rainlang literal form, so they are
+                // composed as their decimal value
+                if let Some(operand_args) = &opcode.operand_args {
+                    for arg in &operand_args.args {
+                        if let (None, Some(value)) = (&arg.binding_id, &arg.value) {
+                            if BINARY_PATTERN.is_match(value) {
+                                let decimal = to_u256(value)
+                                    .or(Err("could not build sourcemap".to_owned()))?;
+                                generator
+                                    .overwrite(
+                                        arg.position[0] as i64,
+                                        arg.position[1] as i64,
+                                        &decimal.to_string(),
+                                        OverwriteOptions::default(),
+                                    )
+                                    .or(Err("could not build sourcemap".to_owned()))?;
+                            }
+                        }
+                    }
+                }
                 if !args_details.is_empty() {
                     if deps_indexes.is_empty() && args_details.iter().any(|v| v.1.starts_with('\''))
                     {
@@ -1137,13 +1535,13 @@ _: opcode-1(12.34e6 123.123);"#;
         matter
 ---
 #exp-binding
-_some-lhs-word: opcode-1(12 123.456e123),
+_some-lhs-word: opcode-1(12 123.456e12),
 _some-other-lhs-word: 1e18,
 _ _: opcode-1(_some-lhs-word _some-other-lhs-word) _some-other-lhs-word;
 "#;
         let rainlang_text = RainDocument::compose_text(dotrain_text, &["exp-binding"], None, None)?;
         let expected_rainlang = r#"/* 0. exp-binding */ 
-_some-lhs-word: opcode-1(12 123.456e123),
+_some-lhs-word: opcode-1(12 123.456e12),
 _some-other-lhs-word: 1e18,
 _ _: opcode-1(_some-lhs-word _some-other-lhs-word) _some-other-lhs-word;"#;
         assert_eq!(rainlang_text, expected_rainlang);
@@ -1469,17 +1867,31 @@ _: opcode-1(0xabcd 456);
         Ok(())
     }
 
+    #[test]
+    fn test_compose_numeric_operand_args() -> anyhow::Result<()> {
+        let dotrain_text = r"---
+#exp
+_: opcode-1<0b101 1e3 0x0a>(1);
+";
+        let rain_document = RainDocument::create(dotrain_text.to_owned(), None, None, None);
+        let result = rain_document.compose(&["exp"])?;
+        assert_eq!(result, "/* 0. exp */ \n_: opcode-1<5 1e3 0x0a>(1);");
+
+        Ok(())
+    }
+
     proptest! {
         #![proptest_config(Config {
             cases: 999,
             ..Config::default()
         })]
+        // values are kept within uint256 range
         #[test]
         fn test_fuzz_num_literals_compose(
-            a in [0f64..f64::MAX],
-            b in [0f64..f64::MAX],
-            c in [0f64..f64::MAX],
-            d in [0f64..f64::MAX],
+            a in [0f64..1e76],
+            b in [0f64..1e76],
+            c in [0f64..1e76],
+            d in [0f64..1e76],
         ) {
             let e1 = format!("{:e}", c[0]);
             let e2 = format!("{:e}", d[0]);
@@ -1503,4 +1915,420 @@ _: opcode-1<{} literal-a>(literal-b {});", a[0], b[0], e1, e2);
             assert_eq!(rainlang_text, expected_rainlang);
         }
     }
+
+    #[test]
+    fn test_validate_elided_references() -> anyhow::Result<()> {
+        let mut store = rain_metadata::Store::new();
+        let (hash_bytes, _) = store.set_dotrain(
+            "---\n#value ! lib elided\n#exp\n_: value;",
+            "lib.rain",
+            false,
+        )?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let dotrain_text = format!(
+            r"---
+@lib {hash}
+#elided ! not available
+#dep
+_: elided;
+#exp
+_: opcode-1<'dep>(elided);
+#other
+_: elided;
+"
+        );
+        let position = |pattern: &str, offset: usize| {
+            let start = dotrain_text.find(pattern).unwrap() + offset;
+            [start, start + 6]
+        };
+        let hash_start = dotrain_text.find(&hash).unwrap();
+        let expected = vec![
+            ErrorCode::ElidedBinding
+                .to_problem(vec!["elided", "not available"], position("elided !", 0)),
+            ErrorCode::ElidedBinding
+                .to_problem(vec!["elided", "not available"], position("(elided)", 1)),
+            ErrorCode::ElidedBinding.to_problem(
+                vec!["value", "lib elided"],
+                [hash_start, hash_start + hash.len()],
+            ),
+            ErrorCode::ElidedBinding.to_problem(
+                vec!["elided", "not available"],
+                position("#dep\n_: elided", 8),
+            ),
+        ];
+        let rain_document =
+            RainDocument::create(dotrain_text.clone(), Some(meta_store), None, None);
+        let result =
+            rain_document.validate_elided_references(&["elided", "exp", "lib.exp", "missing"]);
+        assert_eq!(result, expected);
+
+        // bindings that are not reachable from the entrypoints are not reported
+        let result = rain_document.validate_elided_references(&["lib.value"]);
+        assert_eq!(
+            result,
+            vec![ErrorCode::ElidedBinding.to_problem(
+                vec!["lib.value", "lib elided"],
+                [hash_start, hash_start + hash.len()],
+            )]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_keyword_literals() -> anyhow::Result<()> {
+        let options = crate::ParseOptions::default().with_keyword_literals(
+            &std::collections::HashMap::from([
+                ("true".to_owned(), "1".to_owned()),
+                ("false".to_owned(), "0".to_owned()),
+            ]),
+        )?;
+        let rain_document = RainDocument::builder("---\n#exp\n_ _: true false;".to_owned())
+            .with_options(options)
+            .build();
+        let result = rain_document.compose(&["exp"])?;
+        assert_eq!(result, "/* 0. exp */ \n_ _: 1 0;");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_opcode_aliases() -> anyhow::Result<()> {
+        let options = crate::ParseOptions::default().with_opcode_aliases(
+            &std::collections::HashMap::from([("plus".to_owned(), "add".to_owned())]),
+        )?;
+        let words = rain_metadata::types::authoring::v1::AuthoringMeta(vec![
+            rain_metadata::types::authoring::v1::AuthoringMetaItem {
+                word: "add".to_owned(),
+                operand_parser_offset: 0,
+                description: String::new(),
+            },
+        ]);
+        let rain_document = RainDocument::builder("---\n#exp\n_: plus(1 plus(2 3));".to_owned())
+            .with_words(Some(words))
+            .with_options(options)
+            .build();
+        let result = rain_document.compose(&["exp"])?;
+        assert_eq!(result, "/* 0. exp */ \n_: add(1 add(2 3));");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_are_independent() -> anyhow::Result<()> {
+        let dotrain_text = r"---
+#c 1
+#q 'leaf
+#leaf
+_: 1;
+#mid
+_: call<'leaf>();
+#top
+_: call<'mid>();
+#other
+_: call<'q>();
+#alone
+_: 2;
+";
+        let rain_document = RainDocument::create(dotrain_text.to_owned(), None, None, None);
+
+        // transitive quotes in either order
+        assert!(!rain_document.are_independent("top", "leaf")?);
+        assert!(!rain_document.are_independent("leaf", "top")?);
+        assert!(!rain_document.are_independent("mid", "leaf")?);
+        // quoting through a quote binding
+        assert!(!rain_document.are_independent("other", "leaf")?);
+        assert!(!rain_document.are_independent("q", "leaf")?);
+        // a binding is not independent of itself
+        assert!(!rain_document.are_independent("top", "top")?);
+
+        assert!(rain_document.are_independent("top", "other")?);
+        assert!(rain_document.are_independent("alone", "leaf")?);
+        assert!(rain_document.are_independent("c", "top")?);
+
+        assert_eq!(
+            rain_document.are_independent("top", "unknown"),
+            Err(ComposeError::Reject(
+                "undefined identifier: unknown".to_owned()
+            ))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_with_tab_lints() -> anyhow::Result<()> {
+        let options = crate::ParseOptions::default().with_lint_tabs(true);
+        let rain_document = RainDocument::builder(
+            "---\n#c 1\n#dep\n_:\t2;\n#exp\n_ _:\tc call<'dep>();".to_owned(),
+        )
+        .with_options(options.clone())
+        .build();
+        assert!(!rain_document.all_problems().is_empty());
+        assert!(rain_document
+            .all_problems()
+            .iter()
+            .all(|v| v.code == ErrorCode::TabCharacter));
+        assert_eq!(
+            rain_document.compose(&["exp"]),
+            Ok("/* 0. exp */ \n_ _:\t1 call<1>();\n\n/* 1. dep */ \n_:\t2;".to_owned())
+        );
+
+        // other problems still reject composing
+        let rain_document = RainDocument::builder("---\n#exp\n_:\tundefined-binding;".to_owned())
+            .with_options(options)
+            .build();
+        assert!(rain_document.compose(&["exp"]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_with_warnings() -> anyhow::Result<()> {
+        let words = rain_metadata::types::authoring::v1::AuthoringMeta(vec![
+            rain_metadata::types::authoring::v1::AuthoringMetaItem {
+                word: "no-args".to_owned(),
+                operand_parser_offset: 0,
+                description: String::new(),
+            },
+        ]);
+        let rain_document = RainDocument::builder("---\n#exp\n_: no-args<1>();".to_owned())
+            .with_words(Some(words))
+            .build();
+        assert_eq!(
+            rain_document.bindings[0].problems[0].code,
+            ErrorCode::UnexpectedOperandArgs
+        );
+        assert_eq!(
+            rain_document.compose(&["exp"]),
+            Ok("/* 0. exp */ \n_: no-args<1>();".to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_colliding_opcode_name() -> anyhow::Result<()> {
+        let words = rain_metadata::types::authoring::v1::AuthoringMeta(vec![
+            rain_metadata::types::authoring::v1::AuthoringMetaItem {
+                word: "add".to_owned(),
+                operand_parser_offset: 0,
+                description: String::new(),
+            },
+        ]);
+        let rain_document = RainDocument::builder("---\n#add\n_: 1;".to_owned())
+            .with_words(Some(words))
+            .build();
+        assert_eq!(
+            rain_document.bindings[0].problems,
+            vec![ErrorCode::CollidingOpcodeName.to_problem(vec!["add"], [5, 8])]
+        );
+        assert_eq!(
+            rain_document.compose(&["add"]),
+            Ok("/* 0. add */ \n_: 1;".to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_freeze() -> anyhow::Result<()> {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FrozenDocument>();
+
+        let mut store = rain_metadata::Store::new();
+        let (hash_bytes, _) =
+            store.set_dotrain("---\n#value 5\n#exp\n_: value;", "lib.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(&hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let dotrain_text = format!("---\n@lib {hash}\n#v ! elided\n#exp\n_ _: v lib.value;");
+        let rebinds = Some(vec![Rebind("v".to_owned(), "7".to_owned())]);
+        let rain_document =
+            RainDocument::create(dotrain_text.clone(), Some(meta_store), None, rebinds);
+        let frozen = rain_document.freeze().map_err(|e| anyhow::anyhow!("{e}"))?;
+        assert_eq!(frozen.metas.len(), 1);
+        assert!(frozen.metas.contains_key(&hash));
+
+        // survives serialization and composes without the original store
+        let frozen: FrozenDocument = serde_json::from_str(&serde_json::to_string(&frozen)?)?;
+        assert_eq!(frozen.compose(&["exp"]), rain_document.compose(&["exp"]));
+        assert_eq!(frozen.compose(&["exp"])?, "/* 0. exp */ \n_ _: 7 5;");
+        assert!(frozen.store().get_meta(&hash_bytes).is_some());
+
+        // not consumable
+        let rain_document = RainDocument::create(dotrain_text, None, None, None);
+        assert!(matches!(
+            rain_document.freeze(),
+            Err(ComposeError::Problems(problems))
+                if problems.iter().any(|v| v.code == ErrorCode::UndefinedImport)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_freeze_with_options() -> anyhow::Result<()> {
+        let options = crate::ParseOptions::default()
+            .with_keyword_literals(&std::collections::HashMap::from([(
+                "true".to_owned(),
+                "1".to_owned(),
+            )]))?
+            .with_opcode_aliases(&std::collections::HashMap::from([(
+                "plus".to_owned(),
+                "add".to_owned(),
+            )]))?;
+        let words = rain_metadata::types::authoring::v1::AuthoringMeta(vec![
+            rain_metadata::types::authoring::v1::AuthoringMetaItem {
+                word: "add".to_owned(),
+                operand_parser_offset: 0,
+                description: String::new(),
+            },
+        ]);
+        let rain_document = RainDocument::builder("---\n#exp\n_: plus(true 2);".to_owned())
+            .with_words(Some(words))
+            .with_options(options.clone())
+            .build();
+        let frozen = rain_document.freeze().map_err(|e| anyhow::anyhow!("{e}"))?;
+        assert_eq!(crate::ParseOptions::try_from(&frozen.options)?, options);
+
+        // composes with the options it was parsed with after serialization
+        let frozen: FrozenDocument = serde_json::from_str(&serde_json::to_string(&frozen)?)?;
+        assert_eq!(frozen.compose(&["exp"]), rain_document.compose(&["exp"]));
+        assert_eq!(frozen.compose(&["exp"])?, "/* 0. exp */ \n_: add(1 2);");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deployment_order() -> anyhow::Result<()> {
+        let mut store = rain_metadata::Store::new();
+        let (hash_bytes, _) = store.set_dotrain(
+            "---\n#value 5\n#dep\n_: value;\n#exp\n_: call<'dep>();",
+            "lib.rain",
+            false,
+        )?;
+        let hash = alloy_primitives::hex::encode_prefixed(&hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let dotrain_text = format!(
+            "---\n@lib {hash}\n#a\n_ _: call<'b>() call<'lib.exp>();\n#b\n_: call<'c>();\n#c\n_: 1;\n#d\n_: call<'c>();"
+        );
+        let rain_document = RainDocument::create(dotrain_text, Some(meta_store), None, None);
+        assert_eq!(
+            rain_document.deployment_order(&["a", "d"])?,
+            vec!["a", "d", "b", ".lib.exp", "c", ".lib.dep"]
+        );
+        assert_eq!(rain_document.deployment_order(&["c"])?, vec!["c"]);
+        assert_eq!(
+            rain_document.deployment_order(&[".lib.exp"])?,
+            vec![".lib.exp", ".lib.dep"]
+        );
+        assert!(matches!(
+            rain_document.deployment_order(&[]),
+            Err(ComposeError::Reject(_))
+        ));
+        assert!(matches!(
+            rain_document.deployment_order(&["e"]),
+            Err(ComposeError::Reject(_))
+        ));
+
+        let rain_document = RainDocument::create(
+            "---\n#a\n_: call<'b>();\n#b\n_: call<'a>();".to_owned(),
+            None,
+            None,
+            None,
+        );
+        assert!(matches!(
+            rain_document.deployment_order(&["a"]),
+            Err(ComposeError::Problems(problems))
+                if problems[0].code == ErrorCode::CircularDependency
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let edges: BTreeMap<String, BTreeSet<String>> = [
+            ("a", vec!["b"]),
+            ("b", vec!["a", "c"]),
+            ("c", vec!["c", "e"]),
+            ("d", vec!["a"]),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v.into_iter().map(|v| v.to_owned()).collect()))
+        .collect();
+        let components = strongly_connected_components(&edges);
+        assert_eq!(components.len(), 5);
+        assert_eq!(components["a"], components["b"]);
+        let distinct: BTreeSet<usize> =
+            ["a", "c", "d", "e"].iter().map(|v| components[v]).collect();
+        assert_eq!(distinct.len(), 4);
+    }
+
+    #[test]
+    fn test_dependency_graph_dot() -> anyhow::Result<()> {
+        let dotrain_text = r"---
+#c 1
+#el ! some elided binding
+#q 'leaf
+#leaf
+_: 1;
+#mid
+_: call<'leaf>();
+#top
+_: call<'mid>();
+#other
+_: call<'q>();
+#uses-el
+_: call<'el>();
+#cyc-a
+_: call<'cyc-b>();
+#cyc-b
+_: call<'cyc-a>();
+";
+        let rain_document = RainDocument::create(dotrain_text.to_owned(), None, None, None);
+        let expected = r#"digraph dependencies {
+    "cyc-a";
+    "cyc-b";
+    "el" [style=dashed];
+    "leaf";
+    "mid";
+    "other";
+    "top";
+    "uses-el";
+    "cyc-a" -> "cyc-b" [color=red];
+    "cyc-b" -> "cyc-a" [color=red];
+    "mid" -> "leaf";
+    "other" -> "leaf";
+    "top" -> "mid";
+    "uses-el" -> "el";
+}"#;
+        assert_eq!(rain_document.dependency_graph_dot(), expected);
+
+        // imported bindings are named by their namespace paths
+        let mut store = Store::new();
+        let (hash_bytes, _) =
+            store.set_dotrain("---\n#a\n_: call<'b>();\n#b\n_: 1;", "lib.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(&hash_bytes);
+        let rain_document = RainDocument::create(
+            format!("---\n@lib {hash}\n#uses-lib\n_: call<'lib.a>();"),
+            Some(Arc::new(RwLock::new(store))),
+            None,
+            None,
+        );
+        let expected = r#"digraph dependencies {
+    "lib.a";
+    "lib.b";
+    "uses-lib";
+    "lib.a" -> "lib.b";
+    "uses-lib" -> "lib.a";
+}"#;
+        assert_eq!(rain_document.dependency_graph_dot(), expected);
+
+        Ok(())
+    }
 }
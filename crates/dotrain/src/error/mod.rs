@@ -1,4 +1,5 @@
 use super::types::ast::{Offsets, Problem};
+use serde::{Serialize, Deserialize};
 use serde_repr::{Serialize_repr, Deserialize_repr};
 
 #[cfg(feature = "js-api")]
@@ -7,7 +8,7 @@ use tsify::Tsify;
 use wasm_bindgen::prelude::*;
 
 /// All Error codes of RainlangDocument/RainDocument problem and LSP Diagnostics
-#[derive(Debug, Clone, PartialEq, Copy, Serialize_repr, Deserialize_repr)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Serialize_repr, Deserialize_repr)]
 #[cfg_attr(feature = "js-api", wasm_bindgen)]
 #[repr(i32)]
 pub enum ErrorCode {
@@ -27,12 +28,24 @@ pub enum ErrorCode {
     CollidingNamespaceNodes = 13,
     DepsResolvingFailed = 14,
     CorruptMeta = 15,
+    DeepNesting = 16,
+    UnreachableLine = 17,
+    CollidingOpcodeName = 18,
+    TabCharacter = 19,
+    UnusedAlias = 20,
+    ZeroOutputs = 21,
+    CollidingBindingTypes = 22,
+    UnknownPragma = 23,
+    UnexpectedOperandArgs = 24,
 
     UndefinedWord = 0x101,
+    UndefinedOpcode = 0x102,
     UndefinedImport = 0x103,
     UndefinedQuote = 0x104,
     UndefinedNamespaceMember = 0x105,
     UndefinedIdentifier = 0x106,
+    UndefinedAuthoringMeta = 0x107,
+    UndefinedPragma = 0x108,
 
     InvalidWordPattern = 0x201,
     InvalidExpression = 0x202,
@@ -47,6 +60,8 @@ pub enum ErrorCode {
     InvalidOperandArg = 0x211,
     InvalidSuppliedRebindings = 0x212,
     InvalidReferenceAll = 0x213,
+    InvalidSelfImport = 0x214,
+    InvalidVersion = 0x215,
 
     UnexpectedToken = 0x301,
     UnexpectedClosingParen = 0x302,
@@ -69,10 +84,12 @@ pub enum ErrorCode {
     ExpectedHexLiteral = 0x407,
     ExpectedSemi = 0x408,
     ExpectedLiteral = 0x409,
+    ExpectedOperandArgs = 0x40A,
 
     MismatchRHS = 0x501,
     MismatchLHS = 0x502,
     MismatchOperandArgs = 0x503,
+    MismatchImportHash = 0x504,
 
     OutOfRangeInputs = 0x601,
     OutOfRangeOperandArgs = 0x602,
@@ -82,51 +99,206 @@ pub enum ErrorCode {
     DuplicateIdentifier = 0x702,
     DuplicateImportStatement = 0x703,
     DuplicateImport = 0x704,
+    DuplicateImportHash = 0x705,
+}
+
+/// Describes an error code by its numeric value, name and message template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ErrorCodeDescription {
+    pub code: i32,
+    pub name: String,
+    pub template: String,
 }
 
 impl ErrorCode {
+    /// Max number of message items that the messages of the codes are formatted with
+    const MAX_MSG_ITEMS: usize = 3;
+
+    const ALL: &[ErrorCode] = &[
+        Self::IllegalChar,
+        Self::RuntimeError,
+        Self::CircularDependency,
+        Self::NoFrontMatterSplitter,
+        Self::DeepImport,
+        Self::DeepNamespace,
+        Self::DeepQuote,
+        Self::ElidedBinding,
+        Self::NoneTopLevelImport,
+        Self::NativeParserError,
+        Self::InconsumableMeta,
+        Self::OccupiedNamespace,
+        Self::OddLenHex,
+        Self::CollidingNamespaceNodes,
+        Self::DepsResolvingFailed,
+        Self::CorruptMeta,
+        Self::DeepNesting,
+        Self::UnreachableLine,
+        Self::CollidingOpcodeName,
+        Self::TabCharacter,
+        Self::UnusedAlias,
+        Self::ZeroOutputs,
+        Self::CollidingBindingTypes,
+        Self::UnknownPragma,
+        Self::UnexpectedOperandArgs,
+        Self::UndefinedWord,
+        Self::UndefinedOpcode,
+        Self::UndefinedImport,
+        Self::UndefinedQuote,
+        Self::UndefinedNamespaceMember,
+        Self::UndefinedIdentifier,
+        Self::UndefinedAuthoringMeta,
+        Self::UndefinedPragma,
+        Self::InvalidWordPattern,
+        Self::InvalidExpression,
+        Self::InvalidNamespaceReference,
+        Self::InvalidEmptyLine,
+        Self::InvalidHash,
+        Self::InvalidReferenceLiteral,
+        Self::InvalidRainDocument,
+        Self::InvalidImport,
+        Self::InvalidEmptyBinding,
+        Self::InvalidLiteralQuote,
+        Self::InvalidOperandArg,
+        Self::InvalidSuppliedRebindings,
+        Self::InvalidReferenceAll,
+        Self::InvalidSelfImport,
+        Self::InvalidVersion,
+        Self::UnexpectedToken,
+        Self::UnexpectedClosingParen,
+        Self::UnexpectedNamespacePath,
+        Self::UnexpectedRebinding,
+        Self::UnexpectedClosingAngleParen,
+        Self::UnexpectedEndOfComment,
+        Self::UnexpectedComment,
+        Self::UnexpectedPragma,
+        Self::UnexpectedRename,
+        Self::UnexpectedStringLiteralEnd,
+        Self::UnexpectedSubParserEnd,
+        Self::ExpectedOpcode,
+        Self::ExpectedRename,
+        Self::ExpectedElisionOrRebinding,
+        Self::ExpectedClosingParen,
+        Self::ExpectedOpeningParen,
+        Self::ExpectedClosingAngleBracket,
+        Self::ExpectedHexLiteral,
+        Self::ExpectedSemi,
+        Self::ExpectedLiteral,
+        Self::ExpectedOperandArgs,
+        Self::MismatchRHS,
+        Self::MismatchLHS,
+        Self::MismatchOperandArgs,
+        Self::MismatchImportHash,
+        Self::OutOfRangeInputs,
+        Self::OutOfRangeOperandArgs,
+        Self::OutOfRangeValue,
+        Self::DuplicateAlias,
+        Self::DuplicateIdentifier,
+        Self::DuplicateImportStatement,
+        Self::DuplicateImport,
+        Self::DuplicateImportHash,
+    ];
+
+    /// Returns all error codes
+    pub fn all() -> &'static [ErrorCode] {
+        Self::ALL
+    }
+
     pub fn to_i32(self) -> i32 {
         self as i32
     }
 
+    /// Describes this error code, the message template has a "{}" placeholder for
+    /// each of the items that are passed to [ErrorCode::to_problem()]
+    pub fn describe(self) -> ErrorCodeDescription {
+        ErrorCodeDescription {
+            code: self.to_i32(),
+            name: format!("{:?}", self),
+            template: self.to_problem(vec!["{}"; Self::MAX_MSG_ITEMS], [0, 0]).msg,
+        }
+    }
+
+    /// Determines if this code is of a warning nature, i.e. it does not invalidate the
+    /// document and does not prevent it from being composed
+    pub fn is_warning(&self) -> bool {
+        matches!(
+            self,
+            Self::DuplicateImportHash
+                | Self::DeepNesting
+                | Self::UnreachableLine
+                | Self::CollidingOpcodeName
+                | Self::TabCharacter
+                | Self::UnusedAlias
+                | Self::ZeroOutputs
+                | Self::UnknownPragma
+                | Self::UnexpectedOperandArgs
+        )
+    }
+
+    /// Determines if this code is of a hint nature, i.e. a warning that only suggests a
+    /// possible improvement and is expected to be presented with the lowest severity
+    pub fn is_hint(&self) -> bool {
+        matches!(self, Self::UnusedAlias)
+    }
+
+    /// Builds a problem of this code at the given position with its message formatted from
+    /// the given items, the missing items are formatted as empty
     pub fn to_problem(self, msg_items: Vec<&str>, position: Offsets) -> Problem {
+        let item = |i: usize| msg_items.get(i).copied().unwrap_or_default();
         let msg = match self {
-            Self::IllegalChar => format!("illegal character: {}", msg_items[0]),
-            Self::RuntimeError => msg_items[0].to_owned(),
+            Self::IllegalChar => format!("illegal character: {}", item(0)),
+            Self::RuntimeError => item(0).to_owned(),
             Self::CircularDependency => "circular dependency".to_owned(),
             Self::DeepImport => "import too deep".to_owned(),
             Self::DeepNamespace => "namespace path too deep".to_owned(),
             Self::DeepQuote => "quote too deep".to_owned(),
-            Self::ElidedBinding => format!("elided binding '{}': {}", msg_items[0], msg_items[1]),
+            Self::ElidedBinding => format!("elided binding '{}': {}", item(0), item(1)),
             Self::InconsumableMeta => "import contains inconsumable meta".to_owned(),
-            Self::OccupiedNamespace => "cannot import into an occupied namespace".to_owned(),
+            Self::OccupiedNamespace => match msg_items.get(..2) {
+                Some([name, detail]) => format!("occupied namespace: {}, {}", name, detail),
+                _ => "cannot import into an occupied namespace".to_owned(),
+            },
             Self::CollidingNamespaceNodes => "namespace nodes colliding".to_owned(),
             Self::OddLenHex => "odd length hex literal".to_owned(),
             Self::NoneTopLevelImport => "imports can only be stated at top level".to_owned(),
-            Self::NativeParserError => msg_items[0].to_owned(),
+            Self::NativeParserError => item(0).to_owned(),
             Self::DepsResolvingFailed => "failed to resolve dependencies".to_owned(),
             Self::NoFrontMatterSplitter => "cannot find front matter splitter".to_owned(),
             Self::CorruptMeta => "corrupt meta".to_owned(),
+            Self::DeepNesting => format!("expression nesting is deeper than {} levels", item(0)),
+            Self::UnreachableLine => "unreachable line, expression outputs are already satisfied by previous lines".to_owned(),
+            Self::CollidingOpcodeName => format!("binding name collides with opcode: {}, references to it are ambiguous", item(0)),
+            Self::TabCharacter => "tab character, use spaces instead".to_owned(),
+            Self::UnusedAlias => format!("unused alias: {}", item(0)),
+            Self::ZeroOutputs => "expression source produces zero outputs".to_owned(),
+            Self::CollidingBindingTypes => format!("colliding binding types of {}: {} and {}", item(0), item(1), item(2)),
+            Self::UnknownPragma => format!("unknown pragma: {}, ignored", item(0)),
+            Self::UnexpectedOperandArgs => format!("unexpected operand args, {} takes none", item(0)),
 
-            Self::UndefinedWord => format!("undefined word: {}", msg_items[0]),
-            Self::UndefinedImport => format!("cannot find any settlement for import: {}", msg_items[0]),
-            Self::UndefinedQuote => format!("undefined quote: {}", msg_items[0]),
-            Self::UndefinedIdentifier => format!("undefined identifier: {}", msg_items[0]),
-            Self::UndefinedNamespaceMember => format!("namespace has no member: {}", msg_items[0]),
+            Self::UndefinedWord => format!("undefined word: {}", item(0)),
+            Self::UndefinedOpcode => format!("undefined opcode: {}, alias of: {}", item(0), item(1)),
+            Self::UndefinedImport => format!("cannot find any settlement for import: {}", item(0)),
+            Self::UndefinedQuote => format!("undefined quote: {}", item(0)),
+            Self::UndefinedIdentifier => format!("undefined identifier: {}", item(0)),
+            Self::UndefinedAuthoringMeta => format!("cannot find any authoring meta for: {}", item(0)),
+            Self::UndefinedPragma => format!("undefined pragma: {}", item(0)),
+            Self::UndefinedNamespaceMember => format!("namespace has no member: {}", item(0)),
 
-            Self::InvalidWordPattern => format!("invalid word pattern: {}", msg_items[0]),
+            Self::InvalidWordPattern => format!("invalid word pattern: {}", item(0)),
             Self::InvalidExpression => "invalid expression line".to_owned(),
             Self::InvalidHash => "invalid hash, must be 32 bytes".to_owned(),
             Self::InvalidImport => "expected a valid name or hash".to_owned(),
             Self::InvalidEmptyBinding => "invalid empty expression".to_owned(),
             Self::InvalidEmptyLine => "invalid empty expression line".to_owned(),
-            Self::InvalidLiteralQuote => format!("invalid quote: {}, cannot quote literals", msg_items[0]),
-            Self::InvalidOperandArg => format!("invalid argument pattern: {}", msg_items[0]),
-            Self::InvalidReferenceLiteral => format!("invalid reference to binding: {}, only literal bindings can be referenced", msg_items[0]),
+            Self::InvalidLiteralQuote => format!("invalid quote: {}, cannot quote literals", item(0)),
+            Self::InvalidOperandArg => format!("invalid argument pattern: {}", item(0)),
+            Self::InvalidReferenceLiteral => format!("invalid reference to binding: {}, only literal bindings can be referenced", item(0)),
             Self::InvalidRainDocument => "imported rain document contains top level errors".to_owned(),
-            Self::InvalidNamespaceReference => format!("expected a node, {} is a namespace", msg_items[0]),
-            Self::InvalidSuppliedRebindings => format!("invalid supplied rebinding: {}", msg_items[0]),
-            Self::InvalidReferenceAll => format!("invalid reference to binding: {}, only literal or quote bindings can be referenced", msg_items[0]),
+            Self::InvalidNamespaceReference => format!("expected a node, {} is a namespace", item(0)),
+            Self::InvalidSuppliedRebindings => format!("invalid supplied rebinding: {}", item(0)),
+            Self::InvalidReferenceAll => format!("invalid reference to binding: {}, only literal or quote bindings can be referenced", item(0)),
+            Self::InvalidSelfImport => "invalid import, resolves to the importing document itself".to_owned(),
+            Self::InvalidVersion => "invalid version, expected major[.minor[.patch]]".to_owned(),
 
             Self::UnexpectedToken => "unexpected token".to_owned(),
             Self::UnexpectedClosingParen => "unexpected \")\"".to_owned(),
@@ -136,7 +308,7 @@ impl ErrorCode {
             Self::UnexpectedEndOfComment => "unexpected end of comment".to_owned(),
             Self::UnexpectedComment => "unexpected comment".to_owned(),
             Self::UnexpectedPragma => "unexpected pragma, expected only one statement at top".to_owned(),
-            Self::UnexpectedRename => format!("unexpected rename, name '{}' already taken", msg_items[0]),
+            Self::UnexpectedRename => format!("unexpected rename, name '{}' already taken", item(0)),
             Self::UnexpectedStringLiteralEnd => "unexpected end of string literal".to_owned(),
             Self::UnexpectedSubParserEnd => "unexpected end of sub parser syntax".to_owned(),
 
@@ -149,19 +321,31 @@ impl ErrorCode {
             Self::ExpectedHexLiteral => "expected to be followed by a hex literal".to_owned(),
             Self::ExpectedRename => "expected to be renamed".to_owned(),
             Self::ExpectedLiteral => "expected to be followed by a literal".to_owned(),
+            Self::ExpectedOperandArgs => format!("expected operand args: <{}>", item(0)),
 
-            Self::MismatchRHS => String::new(),
-            Self::MismatchLHS => String::new(),
-            Self::MismatchOperandArgs => String::new(),
+            Self::MismatchRHS => format!("expected {} values on RHS, found {}", item(0), item(1)),
+            Self::MismatchLHS => format!("expected {} values on LHS, found {}", item(0), item(1)),
+            Self::MismatchOperandArgs => match msg_items.first() {
+                Some(args) => format!("mismatching operand args, expected: <{}>", args),
+                None => "mismatching operand args".to_owned(),
+            },
+            Self::MismatchImportHash => format!("mismatching import hash, declared: {}, actual: {}", item(0), item(1)),
 
-            Self::OutOfRangeInputs => String::new(),
-            Self::OutOfRangeOperandArgs => String::new(),
-            Self::OutOfRangeValue => "value out of range".to_owned(),
+            Self::OutOfRangeInputs => format!("expected {} inputs, found {}", item(0), item(1)),
+            Self::OutOfRangeOperandArgs => match msg_items.get(..2) {
+                Some([expected, found]) => format!("expected {} operand args, found {}", expected, found),
+                _ => "operand args out of range".to_owned(),
+            },
+            Self::OutOfRangeValue => match msg_items.first() {
+                Some(detail) => format!("value out of range, {}", detail),
+                None => "value out of range".to_owned(),
+            },
 
-            Self::DuplicateAlias => format!("duplicate alias: {}", msg_items[0]),
+            Self::DuplicateAlias => format!("duplicate alias: {}", item(0)),
             Self::DuplicateIdentifier => "duplicate identifier".to_owned(),
             Self::DuplicateImportStatement => "duplicate import statement".to_owned(),
             Self::DuplicateImport => "duplicate import".to_owned(),
+            Self::DuplicateImportHash => format!("same meta is already imported as: {}", item(0)),
         };
         Problem {
             msg,
@@ -189,6 +373,11 @@ pub enum Error {
     SerdeJsonError(serde_json::Error),
     ParseIntError(std::num::ParseIntError),
     UintParseError(alloy_primitives::ruint::ParseError),
+    InvalidWordPatternRegex(regex::Error),
+    InvalidCommentStyle(String),
+    InvalidOpcodeAlias(String),
+    DocumentTooLarge(usize),
+    MetaError(rain_metadata::Error),
 }
 
 impl std::fmt::Display for Error {
@@ -204,6 +393,13 @@ impl std::fmt::Display for Error {
             Error::SerdeJsonError(v) => write!(f, "{}", v),
             Error::UintParseError(v) => write!(f, "{}", v),
             Error::ParseIntError(v) => write!(f, "{}", v),
+            Error::InvalidWordPatternRegex(v) => write!(f, "invalid word pattern: {}", v),
+            Error::InvalidCommentStyle(v) => write!(f, "invalid comment style: {}", v),
+            Error::InvalidOpcodeAlias(v) => write!(f, "invalid opcode alias: {}", v),
+            Error::DocumentTooLarge(v) => {
+                write!(f, "document exceeds the maximum size of {} bytes", v)
+            }
+            Error::MetaError(v) => write!(f, "{}", v),
         }
     }
 }
@@ -228,6 +424,12 @@ impl From<alloy_primitives::ruint::ParseError> for Error {
     }
 }
 
+impl From<rain_metadata::Error> for Error {
+    fn from(value: rain_metadata::Error) -> Self {
+        Error::MetaError(value)
+    }
+}
+
 impl From<std::num::ParseIntError> for Error {
     fn from(value: std::num::ParseIntError) -> Self {
         Error::ParseIntError(value)
@@ -259,3 +461,205 @@ impl From<ComposeError> for JsValue {
         serde_wasm_bindgen::to_value(&value).unwrap_or(JsValue::NULL)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_catalog() -> anyhow::Result<()> {
+        for code in ErrorCode::all() {
+            assert_eq!(ErrorCode::try_from(code.to_i32())?, *code);
+        }
+        let mut codes: Vec<i32> = ErrorCode::all().iter().map(|v| v.to_i32()).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), ErrorCode::all().len());
+
+        let result = ErrorCode::UndefinedWord.describe();
+        let expected = ErrorCodeDescription {
+            code: 0x101,
+            name: "UndefinedWord".to_owned(),
+            template: "undefined word: {}".to_owned(),
+        };
+        assert_eq!(result, expected);
+
+        let result = ErrorCode::ElidedBinding.describe();
+        let expected = ErrorCodeDescription {
+            code: 7,
+            name: "ElidedBinding".to_owned(),
+            template: "elided binding '{}': {}".to_owned(),
+        };
+        assert_eq!(result, expected);
+
+        // every code has a message
+        assert!(ErrorCode::all()
+            .iter()
+            .all(|v| !v.describe().template.is_empty()));
+        assert_eq!(
+            ErrorCode::MismatchOperandArgs
+                .to_problem(vec![], [0, 0])
+                .msg,
+            "mismatching operand args"
+        );
+        assert_eq!(
+            ErrorCode::OutOfRangeOperandArgs
+                .to_problem(vec!["2", "3"], [0, 0])
+                .msg,
+            "expected 2 operand args, found 3"
+        );
+
+        // the templates take no more items than the placeholders that they are described with
+        for code in ErrorCode::all() {
+            assert_eq!(
+                code.to_problem(vec!["{}"; ErrorCode::MAX_MSG_ITEMS + 1], [0, 0])
+                    .msg,
+                code.describe().template
+            );
+        }
+        assert_eq!(
+            ErrorCode::ElidedBinding.to_problem(vec!["a"], [0, 0]).msg,
+            "elided binding 'a': "
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_all_error_codes() {
+        // an exhaustive match, so a new code fails to compile until it is listed here, and
+        // then this test fails until it is listed in ErrorCode::all() as well
+        let index = |code: ErrorCode| match code {
+            ErrorCode::IllegalChar => 0,
+            ErrorCode::RuntimeError => 1,
+            ErrorCode::CircularDependency => 2,
+            ErrorCode::NoFrontMatterSplitter => 3,
+            ErrorCode::DeepImport => 4,
+            ErrorCode::DeepNamespace => 5,
+            ErrorCode::DeepQuote => 6,
+            ErrorCode::ElidedBinding => 7,
+            ErrorCode::NoneTopLevelImport => 8,
+            ErrorCode::NativeParserError => 9,
+            ErrorCode::InconsumableMeta => 10,
+            ErrorCode::OccupiedNamespace => 11,
+            ErrorCode::OddLenHex => 12,
+            ErrorCode::CollidingNamespaceNodes => 13,
+            ErrorCode::DepsResolvingFailed => 14,
+            ErrorCode::CorruptMeta => 15,
+            ErrorCode::DeepNesting => 16,
+            ErrorCode::UnreachableLine => 17,
+            ErrorCode::CollidingOpcodeName => 18,
+            ErrorCode::TabCharacter => 19,
+            ErrorCode::UnusedAlias => 20,
+            ErrorCode::ZeroOutputs => 21,
+            ErrorCode::CollidingBindingTypes => 22,
+            ErrorCode::UnknownPragma => 23,
+            ErrorCode::UnexpectedOperandArgs => 24,
+            ErrorCode::UndefinedWord => 25,
+            ErrorCode::UndefinedOpcode => 26,
+            ErrorCode::UndefinedImport => 27,
+            ErrorCode::UndefinedQuote => 28,
+            ErrorCode::UndefinedNamespaceMember => 29,
+            ErrorCode::UndefinedIdentifier => 30,
+            ErrorCode::UndefinedAuthoringMeta => 31,
+            ErrorCode::UndefinedPragma => 32,
+            ErrorCode::InvalidWordPattern => 33,
+            ErrorCode::InvalidExpression => 34,
+            ErrorCode::InvalidNamespaceReference => 35,
+            ErrorCode::InvalidEmptyLine => 36,
+            ErrorCode::InvalidHash => 37,
+            ErrorCode::InvalidReferenceLiteral => 38,
+            ErrorCode::InvalidRainDocument => 39,
+            ErrorCode::InvalidImport => 40,
+            ErrorCode::InvalidEmptyBinding => 41,
+            ErrorCode::InvalidLiteralQuote => 42,
+            ErrorCode::InvalidOperandArg => 43,
+            ErrorCode::InvalidSuppliedRebindings => 44,
+            ErrorCode::InvalidReferenceAll => 45,
+            ErrorCode::InvalidSelfImport => 46,
+            ErrorCode::InvalidVersion => 47,
+            ErrorCode::UnexpectedToken => 48,
+            ErrorCode::UnexpectedClosingParen => 49,
+            ErrorCode::UnexpectedNamespacePath => 50,
+            ErrorCode::UnexpectedRebinding => 51,
+            ErrorCode::UnexpectedClosingAngleParen => 52,
+            ErrorCode::UnexpectedEndOfComment => 53,
+            ErrorCode::UnexpectedComment => 54,
+            ErrorCode::UnexpectedPragma => 55,
+            ErrorCode::UnexpectedRename => 56,
+            ErrorCode::UnexpectedStringLiteralEnd => 57,
+            ErrorCode::UnexpectedSubParserEnd => 58,
+            ErrorCode::ExpectedOpcode => 59,
+            ErrorCode::ExpectedRename => 60,
+            ErrorCode::ExpectedElisionOrRebinding => 61,
+            ErrorCode::ExpectedClosingParen => 62,
+            ErrorCode::ExpectedOpeningParen => 63,
+            ErrorCode::ExpectedClosingAngleBracket => 64,
+            ErrorCode::ExpectedHexLiteral => 65,
+            ErrorCode::ExpectedSemi => 66,
+            ErrorCode::ExpectedLiteral => 67,
+            ErrorCode::ExpectedOperandArgs => 68,
+            ErrorCode::MismatchRHS => 69,
+            ErrorCode::MismatchLHS => 70,
+            ErrorCode::MismatchOperandArgs => 71,
+            ErrorCode::MismatchImportHash => 72,
+            ErrorCode::OutOfRangeInputs => 73,
+            ErrorCode::OutOfRangeOperandArgs => 74,
+            ErrorCode::OutOfRangeValue => 75,
+            ErrorCode::DuplicateAlias => 76,
+            ErrorCode::DuplicateIdentifier => 77,
+            ErrorCode::DuplicateImportStatement => 78,
+            ErrorCode::DuplicateImport => 79,
+            ErrorCode::DuplicateImportHash => 80,
+        };
+        let mut indexes: Vec<usize> = ErrorCode::all().iter().map(|v| index(*v)).collect();
+        indexes.sort();
+        assert_eq!(indexes, (0..81).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_problem_render() -> anyhow::Result<()> {
+        let text = "---\n#exp\n_: add(1 2)\n_: 3;";
+        let start = text.find("add").unwrap();
+        let problem = ErrorCode::UndefinedWord.to_problem(vec!["add"], [start, start + 3]);
+        let result = problem.render(text, "file:///example.rain");
+        let expected = "error[UndefinedWord]: undefined word: add
+ --> file:///example.rain:3:4
+  |
+3 | _: add(1 2)
+  |    ^^^";
+        assert_eq!(result, expected);
+
+        // empty span at the end of the text and warnings
+        let problem = ErrorCode::DeepNesting.to_problem(vec!["1"], [text.len(), text.len()]);
+        let result = problem.render(text, "a.rain");
+        let expected = "warning[DeepNesting]: expression nesting is deeper than 1 levels
+ --> a.rain:4:6
+  |
+4 | _: 3;
+  |      ^";
+        assert_eq!(result, expected);
+
+        // multi-line span truncated to its first and last lines
+        let text = "#exp\n\t_: add(\n1\n2\n3\n4\n5);\n#other 1";
+        let start = text.find("add").unwrap();
+        let end = text.find(';').unwrap();
+        let problem = ErrorCode::InvalidExpression.to_problem(vec![], [start, end]);
+        let result = problem.render(text, "a.rain");
+        let expected = "error[InvalidExpression]: invalid expression line
+ --> a.rain:2:5
+  |
+2 | \t_: add(
+  | \t   ^^^^
+3 | 1
+  | ^
+...
+6 | 4
+  | ^
+7 | 5);
+  | ^^";
+        assert_eq!(result, expected);
+
+        Ok(())
+    }
+}
@@ -5,7 +5,13 @@
 //! library, for the sole purpose of providing an easy, typed and native Javascript/Typescript API
 //! for generated wasm bindings
 
-use wasm_bindgen::prelude::*;
+use wasm_bindgen::{
+    prelude::*,
+    convert::*,
+    describe::{WasmDescribeVector, inform, VECTOR, WasmDescribe},
+};
+use serde_wasm_bindgen::{to_value, from_value};
+use super::error::{ErrorCode, ErrorCodeDescription};
 
 mod store;
 mod raindocument;
@@ -63,3 +69,51 @@ pub fn arrayify(hex: &str) -> Vec<u8> {
 pub fn hexlify(data: &[u8]) -> String {
     alloy_primitives::hex::encode_prefixed(data)
 }
+
+/// Describes all error codes by their numeric value, name and message template
+#[wasm_bindgen(js_name = "errorCodeCatalog")]
+pub fn error_code_catalog() -> Vec<ErrorCodeDescription> {
+    ErrorCode::all().iter().map(|v| v.describe()).collect()
+}
+
+/// Computes a deterministic, position independent id of the given node from its structural content
+#[wasm_bindgen(js_name = "nodeId")]
+pub fn node_id(node: crate::types::ast::Node) -> u64 {
+    crate::parser::node_id(&node)
+}
+
+/// Computes a deterministic, position independent id of the given binding from its name and content
+#[wasm_bindgen(js_name = "bindingId")]
+pub fn binding_id(binding: crate::types::ast::Binding) -> u64 {
+    crate::parser::binding_id(&binding)
+}
+
+impl VectorIntoWasmAbi for ErrorCodeDescription {
+    type Abi = <Box<[JsValue]> as IntoWasmAbi>::Abi;
+    fn vector_into_abi(vector: Box<[Self]>) -> Self::Abi {
+        js_value_vector_into_abi(vector)
+    }
+}
+impl From<ErrorCodeDescription> for JsValue {
+    fn from(value: ErrorCodeDescription) -> Self {
+        to_value(&value).unwrap_throw()
+    }
+}
+impl TryFromJsValue for ErrorCodeDescription {
+    type Error = serde_wasm_bindgen::Error;
+    fn try_from_js_value(value: JsValue) -> Result<Self, Self::Error> {
+        from_value(value)
+    }
+}
+impl VectorFromWasmAbi for ErrorCodeDescription {
+    type Abi = <Box<[JsValue]> as IntoWasmAbi>::Abi;
+    unsafe fn vector_from_abi(js: Self::Abi) -> Box<[Self]> {
+        js_value_vector_from_abi(js)
+    }
+}
+impl WasmDescribeVector for ErrorCodeDescription {
+    fn describe_vector() {
+        inform(VECTOR);
+        ErrorCodeDescription::describe();
+    }
+}
@@ -3,9 +3,15 @@ use super::{
     store::MetaStore,
     Namespace, IRainDocument, IAuthoringMeta,
     super::{
-        parser::raindocument::{RainDocument, Rebind},
+        parser::{
+            line_offsets,
+            raindocument::{RainDocument, Rebind},
+        },
         error::ComposeError,
-        types::ast::{Problem, Import, Comment, Binding},
+        types::ast::{
+            Problem, Import, Comment, Binding, DocumentDiff, DependencyEntry, FrozenDocument,
+            Literal,
+        },
     },
 };
 use serde_wasm_bindgen::{Error, to_value, from_value};
@@ -40,6 +46,17 @@ impl RainDocument {
         RainDocument::create(text.to_string(), Some(meta_store.0.clone()), None, rebinds)
     }
 
+    /// Creates an instance of the text that lives at the given uri (if any) and parses only its
+    /// syntax, skipping all meta and import resolutions
+    #[wasm_bindgen(js_name = "parseSyntaxOnly")]
+    pub fn js_parse_syntax_only(text: &str, uri: Option<String>) -> RainDocument {
+        let mut builder = RainDocument::builder(text.to_string());
+        if let Some(uri) = uri {
+            builder = builder.with_uri(uri);
+        }
+        builder.build_syntax_only()
+    }
+
     #[wasm_bindgen(js_name = "getFrontMatter")]
     pub fn js_get_front_matter(text: &str) -> Option<String> {
         RainDocument::get_front_matter(text).map(|v| v.to_owned())
@@ -49,7 +66,8 @@ impl RainDocument {
     pub fn from_interface(value: &IRainDocument, meta_store: &MetaStore) -> RainDocument {
         let mut rd =
             serde_wasm_bindgen::from_value::<RainDocument>(value.obj.clone()).unwrap_throw();
-        rd.meta_store = meta_store.0.clone();
+        rd.meta_store = Some(meta_store.0.clone());
+        rd.line_offsets = line_offsets(&rd.text);
         rd
     }
 
@@ -102,12 +120,50 @@ impl RainDocument {
         self.comments.clone()
     }
 
+    /// This instance's comments that are not within any binding's span
+    #[wasm_bindgen(js_name = "leadingComments")]
+    pub fn js_leading_comments(&self) -> Vec<Comment> {
+        self.leading_comments().into_iter().cloned().collect()
+    }
+
+    /// Checks if the given offset is inside any of this instance's comments
+    #[wasm_bindgen(js_name = "isInComment")]
+    pub fn js_is_in_comment(&self, offset: usize) -> bool {
+        self.is_in_comment(offset)
+    }
+
+    /// The position and the declared namespace name of each of this instance's imports
+    #[wasm_bindgen(js_name = "importAliases")]
+    pub fn js_import_aliases(&self) -> JsValue {
+        to_value(&self.import_aliases()).unwrap_or(JsValue::NULL)
+    }
+
+    /// Encodes this instance's text as a cbor encoded DotrainV1 RainMetaDocument
+    #[wasm_bindgen(js_name = "toDotrainMeta")]
+    pub fn js_to_dotrain_meta(&self) -> Result<Vec<u8>, JsError> {
+        self.to_dotrain_meta()
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Verifies the meta bytes of this instance's resolved imports against their declared hashes
+    #[wasm_bindgen(js_name = "verifyImportIntegrity")]
+    pub fn js_verify_import_integrity(&self) -> Vec<Problem> {
+        self.verify_import_integrity()
+    }
+
     /// This instance's imports
     #[wasm_bindgen(getter, js_name = "imports")]
     pub fn js_imports(&self) -> Vec<Import> {
         self.imports.clone()
     }
 
+    /// The parsed document of this instance's import at the given index, if the import is
+    /// resolved and is a dotrain
+    #[wasm_bindgen(js_name = "importedDocument")]
+    pub fn js_imported_document(&self, import_index: usize) -> Option<RainDocument> {
+        self.imported_document(import_index).cloned()
+    }
+
     /// This instance's bindings
     #[wasm_bindgen(getter, js_name = "bindings")]
     pub fn js_bindings(&self) -> Vec<Binding> {
@@ -125,7 +181,7 @@ impl RainDocument {
     /// This instance's MetaStore
     #[wasm_bindgen(getter, js_name = "metaStore")]
     pub fn js_store(&self) -> MetaStore {
-        MetaStore(self.meta_store.clone())
+        MetaStore(self.store())
     }
 
     /// This instance's AuthoringMeta
@@ -142,6 +198,12 @@ impl RainDocument {
         self.error.clone()
     }
 
+    /// The codes of all problems of this instance, each code once
+    #[wasm_bindgen(js_name = "problemCodes")]
+    pub fn js_problem_codes(&self) -> JsValue {
+        to_value(&self.problem_codes()).unwrap_or(JsValue::NULL)
+    }
+
     /// This instance's all problems (bindings + top)
     #[wasm_bindgen(getter, js_name = "allProblems")]
     pub fn js_all_problems(&self) -> Vec<Problem> {
@@ -160,6 +222,145 @@ impl RainDocument {
             .collect()
     }
 
+    /// The meta bytes (cbor encoded RainMetaDocument) that the import at the given index was resolved to
+    #[wasm_bindgen(js_name = "importMetaBytes")]
+    pub fn js_import_meta_bytes(&self, import_index: usize) -> Option<Vec<u8>> {
+        self.import_meta_bytes(import_index).map(|v| v.to_vec())
+    }
+
+    /// The problems of each import's configuration, in the same order as the imports
+    #[wasm_bindgen(js_name = "importConfigurationProblems")]
+    pub fn js_import_configuration_problems(&self) -> JsValue {
+        to_value(&self.import_configuration_problems()).unwrap_or(JsValue::NULL)
+    }
+
+    /// The dependency manifest of this instance's imports and their nested imports
+    #[wasm_bindgen(js_name = "dependencyManifest")]
+    pub fn js_dependency_manifest(&self) -> Vec<DependencyEntry> {
+        self.dependency_manifest()
+    }
+
+    /// This instance's namespace flattened into the dotted paths of its leaves
+    #[wasm_bindgen(js_name = "flatNamespace")]
+    pub fn js_flat_namespace(&self) -> JsValue {
+        to_value(&self.flat_namespace()).unwrap_or(JsValue::NULL)
+    }
+
+    /// The constant bindings of this instance's namespace with their values resolved to
+    /// decimal strings of U256
+    #[wasm_bindgen(js_name = "constants")]
+    pub fn js_constants(&self) -> JsValue {
+        let constants: Vec<(String, String)> = self
+            .constants()
+            .into_iter()
+            .map(|(path, value)| (path, value.to_string()))
+            .collect();
+        to_value(&constants).unwrap_or(JsValue::NULL)
+    }
+
+    /// The constant bindings of this instance's namespace whose values fail to resolve to U256
+    /// with the resolution errors' messages
+    #[wasm_bindgen(js_name = "invalidConstants")]
+    pub fn js_invalid_constants(&self) -> JsValue {
+        let invalid: Vec<(String, String)> = self
+            .invalid_constants()
+            .into_iter()
+            .map(|(path, err)| (path, err.to_string()))
+            .collect();
+        to_value(&invalid).unwrap_or(JsValue::NULL)
+    }
+
+    /// Previews the rebindings of the import at the given index without applying them
+    #[wasm_bindgen(js_name = "previewRebindings")]
+    pub fn js_preview_rebindings(&self, import_index: usize) -> JsValue {
+        to_value(&self.preview_rebindings(import_index)).unwrap_or(JsValue::NULL)
+    }
+
+    /// The text of the given span of this instance's text, undefined if the span is out of
+    /// range, reversed or does not fall on char boundaries
+    #[wasm_bindgen(js_name = "spanText")]
+    pub fn js_span_text(&self, start: usize, end: usize) -> Option<String> {
+        self.span_text([start, end]).map(|v| v.to_owned())
+    }
+
+    /// The binding whose span contains the given offset
+    #[wasm_bindgen(js_name = "bindingAt")]
+    pub fn js_binding_at(&self, offset: usize) -> Option<Binding> {
+        self.binding_at(offset).cloned()
+    }
+
+    /// The index of the import that the given dotted path of this instance's namespace came from
+    #[wasm_bindgen(js_name = "originOf")]
+    pub fn js_origin_of(&self, path: &str) -> Option<usize> {
+        self.origin_of(path)
+    }
+
+    /// Counts the occurrences of each opcode used across all expression bindings of this instance
+    #[wasm_bindgen(js_name = "usedOpcodes")]
+    pub fn js_used_opcodes(&self) -> JsValue {
+        to_value(&self.used_opcodes()).unwrap_or(JsValue::NULL)
+    }
+
+    /// The opcodes of all expression bindings with their positions and resolved operands in source order
+    #[wasm_bindgen(js_name = "resolvedOperands")]
+    pub fn js_resolved_operands(&self) -> JsValue {
+        to_value(&self.resolved_operands()).unwrap_or(JsValue::NULL)
+    }
+
+    /// The literals of all expression bindings of this instance with their resolved values,
+    /// null for the literals that cannot be resolved
+    #[wasm_bindgen(js_name = "literals")]
+    pub fn js_literals(&self) -> JsValue {
+        let literals: Vec<(&Literal, Option<String>)> = self
+            .literals()
+            .into_iter()
+            .map(|(literal, value)| (literal, value.ok().map(|v| v.to_string())))
+            .collect();
+        to_value(&literals).unwrap_or(JsValue::NULL)
+    }
+
+    /// Compares the structure of this instance with the given one while ignoring all positions
+    #[wasm_bindgen(js_name = "astEqIgnoringPositions")]
+    pub fn js_ast_eq_ignoring_positions(&self, other: &RainDocument) -> bool {
+        self.ast_eq_ignoring_positions(other)
+    }
+
+    /// Reports the semantic differences of the given RainDocument compared to this instance
+    #[wasm_bindgen(js_name = "diff")]
+    pub fn js_diff(&self, other: &RainDocument) -> DocumentDiff {
+        self.diff(other)
+    }
+
+    /// Builds a minimal dotrain text containing only the given entrypoints and their dependencies
+    #[wasm_bindgen(js_name = "minimize")]
+    pub fn js_minimize(&self, keep: Vec<String>) -> String {
+        self.minimize(&keep.iter().map(|v| v.as_str()).collect::<Vec<&str>>())
+    }
+
+    /// Reports each reference to an elided binding in the given entrypoints and their dependencies
+    #[wasm_bindgen(js_name = "validateElidedReferences")]
+    pub fn js_validate_elided_references(&self, entrypoints: Vec<String>) -> Vec<Problem> {
+        self.validate_elided_references(
+            &entrypoints
+                .iter()
+                .map(|v| v.as_str())
+                .collect::<Vec<&str>>(),
+        )
+    }
+
+    /// Checks whether the given bindings are independent of each other, i.e. neither of them
+    /// transitively quotes the other
+    #[wasm_bindgen(js_name = "areIndependent")]
+    pub fn js_are_independent(&self, a: &str, b: &str) -> Result<bool, ComposeError> {
+        self.are_independent(a, b)
+    }
+
+    /// The quote dependencies between this instance's bindings as a Graphviz DOT digraph
+    #[wasm_bindgen(js_name = "dependencyGraphDot")]
+    pub fn js_dependency_graph_dot(&self) -> String {
+        self.dependency_graph_dot()
+    }
+
     /// Parses this instance's text with remote meta search enabled
     #[wasm_bindgen(js_name = "parseAsync")]
     pub async fn js_parse_async(&mut self, rebinds: Option<Vec<Rebind>>) {
@@ -183,6 +384,26 @@ impl RainDocument {
         )
     }
 
+    /// Takes a fully resolved snapshot of this instance, errors if it is not consumable
+    #[wasm_bindgen(js_name = "freeze")]
+    pub fn js_freeze(&self) -> Result<FrozenDocument, ComposeError> {
+        self.freeze()
+    }
+
+    /// The ordered list of the bindings that composing the given entrypoints produces sources for
+    #[wasm_bindgen(js_name = "deploymentOrder")]
+    pub fn js_deployment_order(
+        &self,
+        entrypoints: Vec<String>,
+    ) -> Result<Vec<String>, ComposeError> {
+        self.deployment_order(
+            &entrypoints
+                .iter()
+                .map(|v| v.as_str())
+                .collect::<Vec<&str>>(),
+        )
+    }
+
     /// Composes a text as RainDocument with remote meta search enabled for parsing
     #[wasm_bindgen(js_name = "composeTextAsync")]
     pub async fn js_compose_text_async(
@@ -252,3 +473,33 @@ impl WasmDescribeVector for Rebind {
         Rebind::describe();
     }
 }
+
+impl VectorIntoWasmAbi for DependencyEntry {
+    type Abi = <Box<[JsValue]> as IntoWasmAbi>::Abi;
+    fn vector_into_abi(vector: Box<[Self]>) -> Self::Abi {
+        js_value_vector_into_abi(vector)
+    }
+}
+impl From<DependencyEntry> for JsValue {
+    fn from(value: DependencyEntry) -> Self {
+        to_value(&value).unwrap_throw()
+    }
+}
+impl TryFromJsValue for DependencyEntry {
+    type Error = Error;
+    fn try_from_js_value(value: JsValue) -> Result<Self, Self::Error> {
+        from_value(value)
+    }
+}
+impl VectorFromWasmAbi for DependencyEntry {
+    type Abi = <Box<[JsValue]> as IntoWasmAbi>::Abi;
+    unsafe fn vector_from_abi(js: Self::Abi) -> Box<[Self]> {
+        js_value_vector_from_abi(js)
+    }
+}
+impl WasmDescribeVector for DependencyEntry {
+    fn describe_vector() {
+        inform(VECTOR);
+        DependencyEntry::describe();
+    }
+}
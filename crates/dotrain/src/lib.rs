@@ -20,6 +20,7 @@
 //! Includes 3 features:
 //! - `cli`  A [mod@clap] based module (CLI app) for functionalities of this library, this features is required for building/installing the **binary**
 //! - `js-api`  includes wrappers around main structs and functionalities to provide an API through [mod@wasm_bindgen]
+//! - `tracing`  emits `tracing` spans around the parse phases (import resolution recording the import hash, per binding parsing recording the binding name and composer dependency resolution), to be consumed by any `tracing` subscriber
 
 pub mod types;
 pub mod error;
@@ -1,14 +1,24 @@
+use std::hash::{Hash, Hasher};
 use regex::{Match, Regex};
 use super::error::{Error, ErrorCode};
+use alloy_primitives::{
+    U256,
+    ruint::{BaseConvertError, ParseError},
+};
 use rain_metadata::{RainMetaDocumentV1Item, KnownMagic};
 use super::types::{
     ast::*,
-    patterns::{NAMESPACE_SEGMENT_PATTERN, WORD_PATTERN},
+    patterns::{
+        BINARY_PATTERN, E_PATTERN, HEX_PATTERN, INT_PATTERN, NAMESPACE_SEGMENT_PATTERN,
+        WORD_PATTERN,
+    },
 };
 
+pub(crate) mod options;
 pub(crate) mod raindocument;
 pub(crate) mod rainlangdocument;
 
+pub use self::options::*;
 pub use self::raindocument::*;
 pub use self::rainlangdocument::*;
 
@@ -32,39 +42,22 @@ pub fn exclusive_parse(
     offset: usize,
     include_empty_ends: bool,
 ) -> Vec<ParsedItem> {
+    // each item spans from the end of the previous match up until the start of the next
+    // one, matches are ordered and non-overlapping, so the resulting spans are always
+    // in order, non-overlapping and within the text bounds even for zero-width matches
     let matches: Vec<Match> = pattern.find_iter(text).collect();
-    let strings: Vec<_> = pattern.split(text).collect();
     let mut result: Vec<ParsedItem> = vec![];
-    let count = strings.len();
-    for (i, &s) in strings.iter().enumerate() {
-        if i == 0 {
-            if !s.is_empty() || include_empty_ends {
-                result.push(ParsedItem(
-                    s.to_owned(),
-                    [
-                        offset,
-                        match matches.len() {
-                            0 => text.len() + offset,
-                            _ => matches[0].start() + offset,
-                        },
-                    ],
-                ))
-            }
-        } else if i == count - 1 {
-            if !s.is_empty() || include_empty_ends {
-                result.push(ParsedItem(
-                    s.to_owned(),
-                    [
-                        matches[matches.len() - 1].end() + offset,
-                        text.len() + offset,
-                    ],
-                ))
-            }
-        } else {
-            result.push(ParsedItem(
-                s.to_owned(),
-                [matches[i - 1].end() + offset, matches[i].start() + offset],
-            ))
+    let count = matches.len() + 1;
+    let mut start = 0;
+    for i in 0..count {
+        let end = matches.get(i).map_or(text.len(), |m| m.start());
+        let s = &text[start..end];
+        let is_end = i == 0 || i == count - 1;
+        if !is_end || !s.is_empty() || include_empty_ends {
+            result.push(ParsedItem(s.to_owned(), [start + offset, end + offset]));
+        }
+        if let Some(m) = matches.get(i) {
+            start = m.end();
         }
     }
     result
@@ -118,19 +111,203 @@ pub fn tracked_trim(s: &str) -> (&str, usize, usize) {
 
 /// Calculates the line number of the given position in the given text
 pub fn line_number(text: &str, pos: usize) -> usize {
-    let lines: Vec<_> = text.split_inclusive('\n').collect();
-    let lines_count = lines.len();
-    if pos >= text.len() {
-        lines_count
+    line_number_at(&line_offsets(text), text.len(), pos)
+}
+
+/// Calculates the offsets of the starts of the lines of the given text, used for
+/// resolving line numbers of many positions of the same text by binary search
+pub fn line_offsets(text: &str) -> Vec<usize> {
+    let mut offsets = vec![];
+    let mut acc = 0;
+    text.split_inclusive('\n').for_each(|v| {
+        offsets.push(acc);
+        acc += v.len();
+    });
+    offsets
+}
+
+/// Calculates the line number of the given position from the given line offsets of a
+/// text with the given length, see [line_offsets]
+pub fn line_number_at(line_offsets: &[usize], text_len: usize, pos: usize) -> usize {
+    if pos >= text_len {
+        line_offsets.len()
     } else {
-        let mut _c = 0;
-        for (i, &s) in lines.iter().enumerate() {
-            _c += s.len();
-            if pos <= _c {
-                return i;
+        // a position at the very end of a line (i.e. the start of the next line)
+        // belongs to that line
+        line_offsets
+            .get(1..)
+            .map_or(0, |v| v.partition_point(|&offset| offset < pos))
+    }
+}
+
+/// Computes a deterministic id of the given node from its structural content, i.e. its
+/// kind, name/value, operand args, lhs aliases and inputs, independent of its position,
+/// so it stays the same across edits that only move or reformat the node
+pub fn node_id(node: &Node) -> u64 {
+    let mut hasher = StableHasher::default();
+    hash_node(node, &mut hasher);
+    hasher.finish()
+}
+
+/// Computes a deterministic id of the given binding from its name and its structural
+/// content independent of positions, see [node_id]
+pub fn binding_id(binding: &Binding) -> u64 {
+    let mut hasher = StableHasher::default();
+    binding.name.hash(&mut hasher);
+    match &binding.item {
+        BindingItem::Elided(v) => {
+            0u8.hash(&mut hasher);
+            v.msg.hash(&mut hasher);
+        }
+        BindingItem::Literal(v) => {
+            1u8.hash(&mut hasher);
+            v.value.hash(&mut hasher);
+        }
+        BindingItem::Quote(v) => {
+            2u8.hash(&mut hasher);
+            v.quote.hash(&mut hasher);
+        }
+        BindingItem::Exp(v) => {
+            3u8.hash(&mut hasher);
+            v.ast.len().hash(&mut hasher);
+            for src in &v.ast {
+                src.lines.len().hash(&mut hasher);
+                for line in &src.lines {
+                    hash_aliases(&line.aliases, &mut hasher);
+                    line.nodes.len().hash(&mut hasher);
+                    line.nodes.iter().for_each(|n| hash_node(n, &mut hasher));
+                }
             }
         }
-        0
+    }
+    hasher.finish()
+}
+
+/// FNV-1a hasher, unlike std DefaultHasher its output is stable across builds and releases
+struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        StableHasher(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+    fn write_usize(&mut self, i: usize) {
+        // fixed width so the result does not depend on the target's pointer width
+        self.write(&(i as u64).to_le_bytes());
+    }
+}
+
+fn hash_aliases(aliases: &[Alias], hasher: &mut StableHasher) {
+    aliases.len().hash(hasher);
+    aliases.iter().for_each(|v| v.name.hash(hasher));
+}
+
+fn hash_node(node: &Node, hasher: &mut StableHasher) {
+    match node {
+        Node::Literal(v) => {
+            0u8.hash(hasher);
+            v.value.hash(hasher);
+            v.id.hash(hasher);
+            hash_aliases(v.lhs_alias.as_deref().unwrap_or_default(), hasher);
+        }
+        Node::Alias(v) => {
+            1u8.hash(hasher);
+            v.name.hash(hasher);
+            hash_aliases(v.lhs_alias.as_deref().unwrap_or_default(), hasher);
+        }
+        Node::Opcode(v) => {
+            2u8.hash(hasher);
+            v.opcode.name.hash(hasher);
+            hash_aliases(v.lhs_alias.as_deref().unwrap_or_default(), hasher);
+            match &v.operand_args {
+                Some(operand_args) => {
+                    operand_args.args.len().hash(hasher);
+                    operand_args
+                        .args
+                        .iter()
+                        .for_each(|arg| arg.value.hash(hasher));
+                }
+                None => usize::MAX.hash(hasher),
+            }
+            v.inputs.len().hash(hasher);
+            v.inputs.iter().for_each(|n| hash_node(n, hasher));
+        }
+    }
+}
+
+/// Converts a rain numeric literal (hex, binary, integer or e-notation) to U256, errors with
+/// [Error::UintParseError] if the value overflows U256 and with [Error::InvalidNumbericValue]
+/// if it is not a numeric literal or does not resolve to an integer
+pub fn to_u256(value: &str) -> Result<U256, Error> {
+    let overflow =
+        || Error::UintParseError(ParseError::BaseConvertError(BaseConvertError::Overflow));
+    if HEX_PATTERN.is_match(value) {
+        return Ok(U256::from_str_radix(&value[2..], 16)?);
+    }
+    if BINARY_PATTERN.is_match(value) {
+        return Ok(U256::from_str_radix(&value[2..], 2)?);
+    }
+    let (mantissa, exponent) = if E_PATTERN.is_match(value) {
+        let (mantissa, exponent) = value.split_once('e').unwrap();
+        // the exponent digits are already validated, so failing to parse means it is too
+        // large, which overflows if positive, and if negative it cannot resolve to an integer
+        // since the mantissa is not zero
+        let exponent = exponent.parse::<i64>().map_err(|_| {
+            if exponent.starts_with('-') {
+                Error::InvalidNumbericValue
+            } else {
+                overflow()
+            }
+        })?;
+        (mantissa, exponent)
+    } else if INT_PATTERN.is_match(value) {
+        (value, 0)
+    } else {
+        return Err(Error::InvalidNumbericValue);
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let mut digits = integer.to_owned() + fraction;
+    let mut exponent = exponent - fraction.len() as i64;
+    while exponent < 0 {
+        match digits.pop() {
+            Some('0') => exponent += 1,
+            None => break,
+            Some(_) => return Err(Error::InvalidNumbericValue),
+        }
+    }
+    if digits.is_empty() {
+        return Ok(U256::ZERO);
+    }
+    let base = U256::from_str_radix(&digits, 10)?;
+    if base.is_zero() {
+        return Ok(base);
+    }
+    U256::from(10)
+        .checked_pow(U256::from(exponent))
+        .and_then(|v| v.checked_mul(base))
+        .ok_or_else(overflow)
+}
+
+/// builds the out of range problem detail of the given numeric literal if it overflows U256
+pub(crate) fn u256_overflow_detail(value: &str) -> Option<String> {
+    if let Err(Error::UintParseError(_)) = to_u256(value) {
+        Some(format!(
+            "{} is not within uint256 range [0, 2^256 - 1]",
+            value
+        ))
+    } else {
+        None
     }
 }
 
@@ -256,6 +433,7 @@ pub fn parse_cli_key_val(
 #[cfg(test)]
 mod tests {
     use crate::parser::*;
+    use proptest::{proptest, prop_oneof, strategy::Just, test_runner::Config};
 
     #[test]
     fn test_parse_cli_key_val() -> anyhow::Result<()> {
@@ -340,6 +518,56 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_to_u256() -> anyhow::Result<()> {
+        assert_eq!(to_u256("0")?, U256::ZERO);
+        assert_eq!(to_u256("0x0a")?, U256::from(10));
+        assert_eq!(to_u256("0b1010")?, U256::from(10));
+        assert_eq!(to_u256("1234")?, U256::from(1234));
+        assert_eq!(to_u256("12.00")?, U256::from(12));
+        assert_eq!(to_u256("1e18")?, U256::from(10).pow(U256::from(18)));
+        assert_eq!(to_u256("1.5e3")?, U256::from(1500));
+        assert_eq!(to_u256("1500e-2")?, U256::from(15));
+        assert_eq!(to_u256(&format!("0x{}", "f".repeat(64)))?, U256::MAX);
+        assert_eq!(to_u256(&U256::MAX.to_string())?, U256::MAX);
+
+        assert!(matches!(to_u256("1.5"), Err(Error::InvalidNumbericValue)));
+        assert!(matches!(to_u256("15e-2"), Err(Error::InvalidNumbericValue)));
+        // negative exponents that do not resolve to an integer, no matter how large
+        assert!(matches!(to_u256("1e-3"), Err(Error::InvalidNumbericValue)));
+        assert!(matches!(
+            to_u256("1e-99999999999999999999"),
+            Err(Error::InvalidNumbericValue)
+        ));
+        assert_eq!(u256_overflow_detail("1e-99999999999999999999"), None);
+        // incomplete e notations
+        assert!(matches!(to_u256("1e"), Err(Error::InvalidNumbericValue)));
+        assert!(matches!(to_u256("e5"), Err(Error::InvalidNumbericValue)));
+        assert!(matches!(to_u256("abcd"), Err(Error::InvalidNumbericValue)));
+
+        for overflowed in [
+            "1e100".to_owned(),
+            "1e99999999999999999999".to_owned(),
+            format!("0x1{}", "0".repeat(64)),
+            (U256::MAX.to_string() + "0"),
+        ] {
+            assert!(matches!(
+                to_u256(&overflowed),
+                Err(Error::UintParseError(_))
+            ));
+            assert_eq!(
+                u256_overflow_detail(&overflowed),
+                Some(format!(
+                    "{} is not within uint256 range [0, 2^256 - 1]",
+                    overflowed
+                ))
+            );
+        }
+        assert_eq!(u256_overflow_detail("1e18"), None);
+
+        Ok(())
+    }
+
     #[test]
     fn test_line_number() -> anyhow::Result<()> {
         let text = r"abcd
@@ -349,6 +577,96 @@ mod tests {
         ";
         let result = line_number(text, 38);
         assert_eq!(result, 3);
+
+        // same as a linear scan over the lines for every position
+        let linear = |pos: usize| {
+            let mut c = 0;
+            for (i, s) in text.split_inclusive('\n').enumerate() {
+                c += s.len();
+                if pos <= c {
+                    return i;
+                }
+            }
+            0
+        };
+        let offsets = line_offsets(text);
+        for pos in 0..text.len() {
+            assert_eq!(line_number_at(&offsets, text.len(), pos), linear(pos));
+        }
+        assert_eq!(line_number(text, text.len()), offsets.len());
+        assert_eq!(line_number("", 0), 0);
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(Config {
+            cases: 999,
+            ..Config::default()
+        })]
+        #[test]
+        fn test_fuzz_exclusive_parse(
+            text in "\\PC*",
+            pattern in prop_oneof![
+                Just(r"\s+"),
+                Just(r"\s*"),
+                Just(r"\b"),
+                Just(r"^"),
+                Just(r"$"),
+                Just(r"a*"),
+                Just(r"[@#;,]"),
+                Just(r"\."),
+                Just(r".?"),
+            ],
+            offset in 0usize..1000,
+            include_empty_ends: bool,
+        ) {
+            let pattern = Regex::new(pattern).unwrap();
+            let parsed_items = exclusive_parse(&text, &pattern, offset, include_empty_ends);
+            let mut last_end = offset;
+            for item in &parsed_items {
+                assert!(last_end <= item.1[0]);
+                assert!(item.1[0] <= item.1[1]);
+                assert!(item.1[1] <= text.len() + offset);
+                assert_eq!(&text[item.1[0] - offset..item.1[1] - offset], item.0);
+                last_end = item.1[1];
+            }
+        }
+    }
+
+    #[test]
+    fn test_node_and_binding_ids() -> anyhow::Result<()> {
+        let ids = |text: &str| {
+            let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+            rain_document
+                .bindings()
+                .iter()
+                .map(|b| {
+                    let nodes = match &b.item {
+                        BindingItem::Exp(e) => e.ast()[0].lines[0]
+                            .nodes
+                            .iter()
+                            .map(node_id)
+                            .collect::<Vec<_>>(),
+                        _ => vec![],
+                    };
+                    (binding_id(b), nodes)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let original = ids("---\n#c 1\n#exp _: add(c 2);");
+        let reformatted = ids("---\n\n\n#c   1\n#exp\n  _:   add(\n c\n  2\n);");
+        assert_eq!(original, reformatted);
+        assert_eq!(original[1].1.len(), 1);
+
+        let changed = ids("---\n#c 1\n#exp _: add(c 3);");
+        assert_eq!(original[0], changed[0]);
+        assert_ne!(original[1].0, changed[1].0);
+        assert_ne!(original[1].1, changed[1].1);
+
+        let renamed = ids("---\n#d 1\n#exp _: add(c 2);");
+        assert_ne!(original[0].0, renamed[0].0);
+
         Ok(())
     }
 }
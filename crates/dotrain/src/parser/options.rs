@@ -0,0 +1,357 @@
+use regex::Regex;
+use alloy_primitives::U256;
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use super::{
+    to_u256,
+    super::{
+        error::Error,
+        types::patterns::{
+            CommentStyle, UnknownPragmaPolicy, WordPattern, COMMENT_PATTERN, WORD_PATTERN,
+        },
+    },
+};
+
+#[cfg(feature = "js-api")]
+use tsify::Tsify;
+
+/// Dialect specific settings of parsing RainDocuments and RainlangDocuments, i.e. the
+/// identifier [WordPattern], the keyword literals (such as `true` and `false`) and opcode
+/// aliases if any, the comment styles which default to `/* */` block comments, whether
+/// expressions are parsed leniently, whether tab characters in expressions are reported, the
+/// maximum size of documents and how unknown pragmas are handled
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    word_pattern: WordPattern,
+    keyword_literals: HashMap<String, U256>,
+    opcode_aliases: HashMap<String, String>,
+    comment_styles: Vec<CommentStyle>,
+    comment_regex: Regex,
+    lenient: bool,
+    lint_tabs: bool,
+    max_document_bytes: Option<usize>,
+    unknown_pragmas: UnknownPragmaPolicy,
+}
+
+impl ParseOptions {
+    /// Sets the given word pattern as the identifier pattern that binding names and lhs aliases
+    /// are validated with
+    pub fn with_word_pattern(mut self, word_pattern: WordPattern) -> ParseOptions {
+        self.word_pattern = word_pattern;
+        self
+    }
+
+    /// Sets the given keywords (mapped to their numeric values) as the keyword literals,
+    /// keyword literals are parsed as literals with their resolved values instead of being
+    /// resolved as identifiers, errors if any of the values is not a valid U256 numeric value
+    pub fn with_keyword_literals(
+        mut self,
+        keyword_literals: &HashMap<String, String>,
+    ) -> Result<ParseOptions, Error> {
+        self.keyword_literals = keyword_literals
+            .iter()
+            .map(|(keyword, value)| Ok((keyword.clone(), to_u256(value)?)))
+            .collect::<Result<_, Error>>()?;
+        Ok(self)
+    }
+
+    /// Sets the given aliases (mapped to their canonical opcode words) as the opcode aliases,
+    /// an opcode referenced by an alias resolves to its canonical word in the authoring meta,
+    /// these override the aliases that the authoring meta itself declares (the words whose
+    /// description starts with `alias of:` followed by the canonical word), errors if any of
+    /// the aliases or canonical words is not a valid word
+    pub fn with_opcode_aliases(
+        mut self,
+        opcode_aliases: &HashMap<String, String>,
+    ) -> Result<ParseOptions, Error> {
+        if let Some((alias, word)) = opcode_aliases
+            .iter()
+            .find(|(alias, word)| !WORD_PATTERN.is_match(alias) || !WORD_PATTERN.is_match(word))
+        {
+            return Err(Error::InvalidOpcodeAlias(format!("{} -> {}", alias, word)));
+        }
+        self.opcode_aliases = opcode_aliases.clone();
+        Ok(self)
+    }
+
+    /// Sets the given comment styles as the comment syntaxes replacing the default `/* */`
+    /// block comments, the styles are matched in the given order, errors if no style is given
+    /// or if any of the delimiters is empty
+    pub fn with_comment_styles(
+        mut self,
+        comment_styles: Vec<CommentStyle>,
+    ) -> Result<ParseOptions, Error> {
+        if comment_styles.is_empty() {
+            return Err(Error::InvalidCommentStyle(
+                "expected at least one comment style".to_owned(),
+            ));
+        }
+        if comment_styles.iter().any(|v| match v {
+            CommentStyle::Block { open, close } => open.is_empty() || close.is_empty(),
+            CommentStyle::Line { prefix } => prefix.is_empty(),
+        }) {
+            return Err(Error::InvalidCommentStyle(
+                "comment delimiters cannot be empty".to_owned(),
+            ));
+        }
+        let pattern = comment_styles
+            .iter()
+            .map(|v| v.pattern())
+            .collect::<Vec<_>>()
+            .join("|");
+        self.comment_regex =
+            Regex::new(&pattern).map_err(|e| Error::InvalidCommentStyle(e.to_string()))?;
+        self.comment_styles = comment_styles;
+        Ok(self)
+    }
+
+    /// Sets whether expressions are parsed leniently, i.e. a trailing empty line (such as
+    /// one left by a trailing `,`) or a trailing empty source (such as one left by a
+    /// repeated `;`) of an expression is dropped instead of being reported as
+    /// [ErrorCode::InvalidEmptyLine](crate::error::ErrorCode::InvalidEmptyLine) or
+    /// [ErrorCode::InvalidEmptyBinding](crate::error::ErrorCode::InvalidEmptyBinding),
+    /// strict (the default) keeps reporting them
+    pub fn with_lenient(mut self, lenient: bool) -> ParseOptions {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Sets whether tab characters in expressions are reported as
+    /// [ErrorCode::TabCharacter](crate::error::ErrorCode::TabCharacter) warnings, since
+    /// they throw off column calculations of clients, disabled by default
+    pub fn with_lint_tabs(mut self, lint_tabs: bool) -> ParseOptions {
+        self.lint_tabs = lint_tabs;
+        self
+    }
+
+    /// Sets how the pragma statements with unknown keywords at top of documents are handled,
+    /// i.e. reported as errors (the default), reported as warnings or ignored, see [UnknownPragmaPolicy]
+    pub fn with_unknown_pragmas(mut self, policy: UnknownPragmaPolicy) -> ParseOptions {
+        self.unknown_pragmas = policy;
+        self
+    }
+
+    /// Sets the maximum size (in bytes) of documents, a RainDocument exceeding it is not parsed
+    /// and only gets a [ErrorCode::RuntimeError](crate::error::ErrorCode::RuntimeError) problem,
+    /// unlimited by default
+    pub fn with_max_document_bytes(mut self, max_document_bytes: Option<usize>) -> ParseOptions {
+        self.max_document_bytes = max_document_bytes;
+        self
+    }
+
+    /// The identifier pattern of these options
+    pub fn word_pattern(&self) -> &WordPattern {
+        &self.word_pattern
+    }
+
+    /// The resolved value of the given keyword literal if it is one
+    pub fn keyword_literal(&self, text: &str) -> Option<&U256> {
+        self.keyword_literals.get(text)
+    }
+
+    /// The canonical opcode word of the given alias if it is one
+    pub fn opcode_alias(&self, alias: &str) -> Option<&str> {
+        self.opcode_aliases.get(alias).map(|v| v.as_str())
+    }
+
+    /// Whether expressions are parsed leniently, see [ParseOptions::with_lenient]
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Whether tab characters in expressions are reported, see [ParseOptions::with_lint_tabs]
+    pub fn lints_tabs(&self) -> bool {
+        self.lint_tabs
+    }
+
+    /// How unknown pragmas are handled, see [ParseOptions::with_unknown_pragmas]
+    pub fn unknown_pragmas(&self) -> UnknownPragmaPolicy {
+        self.unknown_pragmas
+    }
+
+    /// The maximum size (in bytes) of documents, see [ParseOptions::with_max_document_bytes]
+    pub fn max_document_bytes(&self) -> Option<usize> {
+        self.max_document_bytes
+    }
+
+    /// The comment styles of these options
+    pub fn comment_styles(&self) -> &[CommentStyle] {
+        &self.comment_styles
+    }
+
+    /// The regex that matches the comments of any of the comment styles
+    pub fn comment_pattern(&self) -> &Regex {
+        &self.comment_regex
+    }
+
+    /// Checks if the given comment (matched by [ParseOptions::comment_pattern]) is a block
+    /// comment that is not ended by its close delimiter
+    pub fn is_unended_comment(&self, comment: &str) -> bool {
+        !self.comment_styles.iter().any(|v| match v {
+            CommentStyle::Block { open, close } => {
+                comment.len() >= open.len() + close.len()
+                    && comment.starts_with(open.as_str())
+                    && comment.ends_with(close.as_str())
+            }
+            CommentStyle::Line { prefix } => comment.starts_with(prefix.as_str()),
+        })
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            word_pattern: WordPattern::default(),
+            keyword_literals: HashMap::new(),
+            opcode_aliases: HashMap::new(),
+            comment_styles: vec![CommentStyle::Block {
+                open: "/*".to_owned(),
+                close: "*/".to_owned(),
+            }],
+            comment_regex: COMMENT_PATTERN.clone(),
+            lenient: false,
+            lint_tabs: false,
+            max_document_bytes: None,
+            unknown_pragmas: UnknownPragmaPolicy::default(),
+        }
+    }
+}
+
+impl PartialEq for ParseOptions {
+    fn eq(&self, other: &Self) -> bool {
+        self.word_pattern == other.word_pattern
+            && self.keyword_literals == other.keyword_literals
+            && self.opcode_aliases == other.opcode_aliases
+            && self.comment_styles == other.comment_styles
+            && self.lenient == other.lenient
+            && self.lint_tabs == other.lint_tabs
+            && self.max_document_bytes == other.max_document_bytes
+            && self.unknown_pragmas == other.unknown_pragmas
+    }
+}
+
+/// Serializable form of [ParseOptions], i.e. its word pattern as the source of its compiled
+/// regex and its keyword literals as their decimal values, used for shipping the options a
+/// document was parsed with alongside it, see [FrozenDocument](crate::types::ast::FrozenDocument)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ParseOptionsSnapshot {
+    pub word_pattern: String,
+    pub keyword_literals: HashMap<String, String>,
+    pub opcode_aliases: HashMap<String, String>,
+    pub comment_styles: Vec<CommentStyle>,
+    pub lenient: bool,
+    pub lint_tabs: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "js-api", tsify(optional))]
+    pub max_document_bytes: Option<usize>,
+    pub unknown_pragmas: UnknownPragmaPolicy,
+}
+
+impl Default for ParseOptionsSnapshot {
+    fn default() -> Self {
+        ParseOptionsSnapshot::from(&ParseOptions::default())
+    }
+}
+
+impl From<&ParseOptions> for ParseOptionsSnapshot {
+    fn from(value: &ParseOptions) -> Self {
+        ParseOptionsSnapshot {
+            word_pattern: value.word_pattern.as_regex().as_str().to_owned(),
+            keyword_literals: value
+                .keyword_literals
+                .iter()
+                .map(|(keyword, value)| (keyword.clone(), value.to_string()))
+                .collect(),
+            opcode_aliases: value.opcode_aliases.clone(),
+            comment_styles: value.comment_styles.clone(),
+            lenient: value.lenient,
+            lint_tabs: value.lint_tabs,
+            max_document_bytes: value.max_document_bytes,
+            unknown_pragmas: value.unknown_pragmas,
+        }
+    }
+}
+
+impl TryFrom<&ParseOptionsSnapshot> for ParseOptions {
+    type Error = Error;
+    fn try_from(value: &ParseOptionsSnapshot) -> Result<Self, Self::Error> {
+        // the word pattern source is already anchored, so it is compiled as is
+        let word_pattern = WordPattern::from_regex(
+            Regex::new(&value.word_pattern).map_err(Error::InvalidWordPatternRegex)?,
+        );
+        Ok(ParseOptions::default()
+            .with_word_pattern(word_pattern)
+            .with_keyword_literals(&value.keyword_literals)?
+            .with_opcode_aliases(&value.opcode_aliases)?
+            .with_comment_styles(value.comment_styles.clone())?
+            .with_lenient(value.lenient)
+            .with_lint_tabs(value.lint_tabs)
+            .with_max_document_bytes(value.max_document_bytes)
+            .with_unknown_pragmas(value.unknown_pragmas))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_styles() -> anyhow::Result<()> {
+        let options = ParseOptions::default().with_comment_styles(vec![
+            CommentStyle::Line {
+                prefix: "#!".to_owned(),
+            },
+            CommentStyle::Block {
+                open: "(*".to_owned(),
+                close: "*)".to_owned(),
+            },
+        ])?;
+        let comments: Vec<_> = options
+            .comment_pattern()
+            .find_iter("a #! line\nb (* block *) c (*)")
+            .map(|v| v.as_str())
+            .collect();
+        assert_eq!(comments, vec!["#! line", "(* block *)", "(*)"]);
+        assert!(!options.is_unended_comment("#! line"));
+        assert!(!options.is_unended_comment("(* block *)"));
+        assert!(options.is_unended_comment("(*)"));
+        assert_ne!(options, ParseOptions::default());
+
+        assert!(ParseOptions::default().with_comment_styles(vec![]).is_err());
+        assert!(ParseOptions::default()
+            .with_comment_styles(vec![CommentStyle::Line {
+                prefix: "".to_owned()
+            }])
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot() -> anyhow::Result<()> {
+        let options = ParseOptions::default()
+            .with_word_pattern(WordPattern::new("[a-z][0-9a-z_]*")?)
+            .with_keyword_literals(&HashMap::from([("true".to_owned(), "0x01".to_owned())]))?
+            .with_opcode_aliases(&HashMap::from([("plus".to_owned(), "add".to_owned())]))?
+            .with_comment_styles(vec![CommentStyle::Line {
+                prefix: "#!".to_owned(),
+            }])?
+            .with_lenient(true)
+            .with_lint_tabs(true)
+            .with_max_document_bytes(Some(1024))
+            .with_unknown_pragmas(UnknownPragmaPolicy::Warn);
+        let snapshot = ParseOptionsSnapshot::from(&options);
+        assert_eq!(snapshot.keyword_literals["true"], "1");
+        let snapshot: ParseOptionsSnapshot =
+            serde_json::from_str(&serde_json::to_string(&snapshot)?)?;
+        assert_eq!(ParseOptions::try_from(&snapshot)?, options);
+        assert_eq!(
+            ParseOptions::try_from(&ParseOptionsSnapshot::default())?,
+            ParseOptions::default()
+        );
+
+        Ok(())
+    }
+}
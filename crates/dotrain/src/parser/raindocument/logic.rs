@@ -1,5 +1,5 @@
 use async_recursion::async_recursion;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use futures::future::join_all;
 use rain_metadata::{types::dotrain::v1::DotrainMeta, KnownMagic, RainMetaDocumentV1Item, search};
 use super::*;
@@ -7,27 +7,28 @@ use super::super::{
     super::error::{Error, ErrorCode},
     deep_read_quote, exclusive_parse, fill_in, inclusive_parse, is_consumable,
     rainlangdocument::RainlangDocument,
-    tracked_trim,
+    to_u256, tracked_trim, u256_overflow_detail,
 };
 
 impl RainDocument {
     /// the main method that takes out and processes each section of a RainDocument
     /// text (comments, imports, etc) one after the other, builds the parse tree, builds
     /// the namespace and checks for dependency issues and resolves the global words
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(import_depth = self.import_depth))
+    )]
     #[cfg_attr(target_family = "wasm", async_recursion(?Send))]
     #[cfg_attr(not(target_family = "wasm"), async_recursion)]
     pub(super) async fn _parse(
         &mut self,
         remote_search: bool,
+        resolve_imports: bool,
         opts_rebinds: Option<Vec<Rebind>>,
     ) -> Result<(), Error> {
-        self.imports.clear();
-        self.problems.clear();
-        self.comments.clear();
-        self.bindings.clear();
-        self.namespace.clear();
-        self.known_words = None;
-        self.front_matter_offset = 0;
+        self.reset_state();
+        // the given words are the known words unless the text pins a word set by its pragma
+        self.known_words = self.words.clone();
 
         let mut document = self.text.clone();
         let mut namespace: Namespace = HashMap::new();
@@ -53,19 +54,30 @@ impl RainDocument {
         };
 
         // parse comments
-        for parsed_comment in inclusive_parse(&document, &COMMENT_PATTERN, 0).iter() {
-            // if a comment is not ended
-            if !parsed_comment.0.ends_with("*/") {
+        for parsed_comment in inclusive_parse(&document, self.options.comment_pattern(), 0).iter() {
+            let mut comment = parsed_comment.0.as_str();
+            // if a comment is not ended, it is cut before the next line that starts a
+            // binding, so the rest of the bindings are still parsed
+            if self.options.is_unended_comment(comment) {
+                comment = &comment[..Self::unended_comment_len(comment)];
                 self.problems
-                    .push(ErrorCode::UnexpectedEndOfComment.to_problem(vec![], parsed_comment.1));
+                    .push(ErrorCode::UnexpectedEndOfComment.to_problem(
+                        vec![],
+                        [parsed_comment.1[0], parsed_comment.1[0] + comment.len()],
+                    ));
             }
+            let position = [parsed_comment.1[0], parsed_comment.1[0] + comment.len()];
             self.comments.push(Comment {
-                comment: parsed_comment.0.clone(),
-                position: parsed_comment.1,
+                comment: comment.to_owned(),
+                position,
             });
-            fill_in(&mut document, parsed_comment.1)?;
+            fill_in(&mut document, position)?;
         }
 
+        // parse the pragmas and take them out of the text, the authoring meta that the word set
+        // pragma pins replaces the known words before anything else is parsed
+        self.process_pragmas(&mut document)?;
+
         // since exclusive_parse() is being used with 'include_empty_ends' arg set to true,
         // the first item of the parsed items should be ignored since it only contains the
         // text before the first match
@@ -94,24 +106,44 @@ impl RainDocument {
         // alltogether with care for read/write lock on the CAS
         ignore_first = true;
         if self.import_depth < 32 {
-            let mut futures = vec![];
-            for s in &import_statements {
-                if ignore_first {
-                    ignore_first = false;
-                    continue;
+            let parsed_imports = if resolve_imports {
+                let mut futures = vec![];
+                for s in &import_statements {
+                    if ignore_first {
+                        ignore_first = false;
+                        continue;
+                    }
+                    futures.push(self.process_import(s, remote_search));
                 }
-                futures.push(self.process_import(s, remote_search));
-            }
-            let mut parsed_imports = join_all(futures).await;
+                join_all(futures).await
+            } else {
+                // only parse the statements and leave the imports unresolved
+                import_statements[1..]
+                    .iter()
+                    .map(|s| self.process_import_statement(s).0)
+                    .collect()
+            };
 
             // since the parsing import statements is async, it is needed to check for
             // duplicate imports after all imports have been done parsing and then add
             // their found problems to the top problems list
-            for imp in &mut parsed_imports {
-                // check for duplicate imports
-                if !imp.hash.is_empty() && self.imports.iter().any(|i| i.hash == imp.hash) {
-                    self.problems
-                        .push(ErrorCode::DuplicateImport.to_problem(vec![], imp.hash_position));
+            for (i, imp) in parsed_imports.iter().enumerate() {
+                // check for duplicate imports, importing the same resolved meta under
+                // a different name is not an error but is reported as a warning
+                if !imp.hash.is_empty() {
+                    if let Some(original) = parsed_imports[..i].iter().find(|v| v.hash == imp.hash)
+                    {
+                        if original.name == imp.name {
+                            self.problems.push(
+                                ErrorCode::DuplicateImport.to_problem(vec![], imp.hash_position),
+                            );
+                        } else if original.sequence.is_some() && imp.sequence.is_some() {
+                            self.problems.push(
+                                ErrorCode::DuplicateImportHash
+                                    .to_problem(vec![&original.name], imp.position),
+                            );
+                        }
+                    }
                 }
                 // add found problems of each import to top problems list
                 self.problems.extend(imp.problems.clone());
@@ -128,11 +160,22 @@ impl RainDocument {
         }
 
         // merge all the built and ready imported items namespaces into the main namespace
-        let mut imports_namespaces = self.build_imports_namespaces(&namespace);
+        let (mut imports_namespaces, reexports) = self.build_imports_namespaces(&namespace);
         while let Some((name, hash_position, _ns)) = imports_namespaces.pop_front() {
             self.merge_namespace(name, hash_position, _ns, &mut namespace);
         }
 
+        // re-export the imports' members that are configured to be re-exported
+        // directly under the main namespace
+        for (key, position, item) in reexports {
+            if let std::collections::hash_map::Entry::Vacant(entry) = namespace.entry(key) {
+                entry.insert(item);
+            } else {
+                self.problems
+                    .push(ErrorCode::OccupiedNamespace.to_problem(vec![], position));
+            }
+        }
+
         // parsing bindings
         let parsed_bindings = exclusive_parse(&document, &BINDING_PATTERN, 0, true);
         ignore_first = true;
@@ -188,23 +231,27 @@ impl RainDocument {
                 // parse the rainlang binding to ast and repopulate the
                 // binding.item and corresponding namespace with it
                 if matches!(binding.item, BindingItem::Exp(_)) {
+                    #[cfg(feature = "tracing")]
+                    let _span =
+                        tracing::debug_span!("parse_rainlang", binding = %binding.name).entered();
                     let rainlang_doc = RainlangDocument::create(
                         binding.content.clone(),
                         &self.namespace,
                         self.known_words.as_ref(),
+                        &self.options,
                     );
                     // add the rainlang problems to the binding problems by applying
                     // the initial offset difference to their positions
-                    binding
+                    let problems: Vec<Problem> = rainlang_doc
                         .problems
-                        .extend(rainlang_doc.problems.iter().map(|p| Problem {
+                        .iter()
+                        .map(|p| Problem {
                             msg: p.msg.clone(),
-                            position: [
-                                p.position[0] + binding.content_position[0],
-                                p.position[1] + binding.content_position[0],
-                            ],
+                            position: binding.map_to_document(p.position),
                             code: p.code,
-                        }));
+                        })
+                        .collect();
+                    binding.problems.extend(problems);
                     // assign to the binding.item and namespace
                     binding.item = BindingItem::Exp(rainlang_doc);
                     self.namespace.insert(
@@ -217,6 +264,10 @@ impl RainDocument {
                     );
                 }
             }
+
+            // done after the namespace is built, so these warnings only appear on this
+            // instance's bindings and do not prevent composing
+            Self::check_opcode_name_collisions(&mut self.bindings, self.known_words.as_ref());
         }
 
         Ok(())
@@ -260,7 +311,8 @@ impl RainDocument {
         } else {
             let items = exclusive_parse(text, &WS_PATTERN, 0, false);
             if items.len() == 1 && NUMERIC_PATTERN.is_match(&items[0].0) {
-                Some((items[0].0.clone(), 2, false))
+                let has_err = u256_overflow_detail(&items[0].0).is_some();
+                Some((items[0].0.clone(), 2, has_err))
             } else {
                 None
             }
@@ -338,6 +390,7 @@ impl RainDocument {
                 if WORD_PATTERN.is_match(&first_piece.0) {
                     if LITERAL_PATTERN.is_match(&complementary_piece.0)
                         || complementary_piece.0 == "!"
+                        || complementary_piece.0 == "^"
                         || QUOTE_PATTERN.is_match(&complementary_piece.0)
                     {
                         if imp_conf.groups.iter().any(|v| {
@@ -421,6 +474,10 @@ impl RainDocument {
     }
 
     /// processes an import statement
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(hash = tracing::field::Empty))
+    )]
     #[cfg_attr(target_family = "wasm", async_recursion(?Send))]
     #[cfg_attr(not(target_family = "wasm"), async_recursion)]
     pub(super) async fn process_import(
@@ -428,6 +485,69 @@ impl RainDocument {
         statement: &ParsedItem,
         remote_search: bool,
     ) -> Import {
+        let (mut result, is_valid) = self.process_import_statement(statement);
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("hash", result.hash.as_str());
+
+        // do not continue if import statement is not valid
+        if !is_valid {
+            return result;
+        }
+
+        let hash_bytes = alloy_primitives::hex::decode(&result.hash).unwrap();
+        if self.is_self_import(&hash_bytes) {
+            result
+                .problems
+                .push(ErrorCode::InvalidSelfImport.to_problem(vec![], result.hash_position));
+            return result;
+        }
+        let subgraphs = self
+            .meta_store
+            .as_ref()
+            .map(|v| v.read().unwrap().subgraphs().clone())
+            .unwrap_or_default();
+
+        // read the corresponding hash from CAS
+        let opt_meta_seq = self
+            .fetch_import_contents(&subgraphs, &hash_bytes, &mut result, remote_search)
+            .await;
+
+        // continue based on if the result was a deployer or a meta
+        if let Some((meta_bytes, meta_items, source)) = opt_meta_seq {
+            self.process_meta_import(meta_bytes, meta_items, source, &mut result, remote_search)
+                .await;
+        } else if result
+            .problems
+            .iter()
+            .all(|p| p.code != ErrorCode::CorruptMeta && p.code != ErrorCode::InconsumableMeta)
+        {
+            result.problems.push(
+                ErrorCode::UndefinedImport.to_problem(vec![&result.hash], result.hash_position),
+            );
+        }
+        result
+    }
+
+    /// checks if the given import hash resolves to this very document, i.e. it is the hash of
+    /// this instance's text or it is mapped to this instance's uri in the Store's dotrain cache
+    pub(super) fn is_self_import(&self, hash_bytes: &[u8]) -> bool {
+        if let Some(uri) = &self.uri {
+            if self.meta_store.as_ref().is_some_and(|v| {
+                v.read()
+                    .unwrap()
+                    .get_dotrain_hash(uri)
+                    .is_some_and(|v| v == hash_bytes)
+            }) {
+                return true;
+            }
+        }
+        self.to_dotrain_meta()
+            .is_ok_and(|v| alloy_primitives::keccak256(v).0 == hash_bytes)
+    }
+
+    /// parses an import statement without resolving it, returns the unresolved import
+    /// alongside a flag indicating if the statement is valid and can be resolved
+    pub(super) fn process_import_statement(&self, statement: &ParsedItem) -> (Import, bool) {
         let at_pos: Offsets = [statement.1[0] - 1, statement.1[0] - 1];
         let mut result = Import {
             name: ".".to_owned(),
@@ -505,36 +625,11 @@ impl RainDocument {
                 .push(ErrorCode::InvalidImport.to_problem(vec![], at_pos));
         }
 
-        // do not continue if import statement is not valid
-        if !is_valid {
-            return result;
-        }
-
-        let hash_bytes = alloy_primitives::hex::decode(&result.hash).unwrap();
-        let subgraphs = { self.meta_store.read().unwrap().subgraphs().clone() };
-
-        // read the corresponding hash from CAS
-        let opt_meta_seq = self
-            .fetch_import_contents(&subgraphs, &hash_bytes, &mut result, remote_search)
-            .await;
-
-        // continue based on if the result was a deployer or a meta
-        if let Some(meta_items) = opt_meta_seq {
-            self.process_meta_import(meta_items, &mut result, remote_search)
-                .await;
-        } else if result
-            .problems
-            .iter()
-            .all(|p| p.code != ErrorCode::CorruptMeta && p.code != ErrorCode::InconsumableMeta)
-        {
-            result.problems.push(
-                ErrorCode::UndefinedImport.to_problem(vec![&result.hash], result.hash_position),
-            );
-        }
-        result
+        (result, is_valid)
     }
 
-    // read the corresponding hash from CAS, the result is either a deployer or a meta or not found
+    // read the corresponding hash from CAS, the result is either a meta (alongside its raw bytes
+    // and where it was found) or not found
     // this should be done with care for the CAS read/write lock
     pub(super) async fn fetch_import_contents(
         &self,
@@ -542,13 +637,17 @@ impl RainDocument {
         hash_bytes: &[u8],
         result: &mut Import,
         remote_search: bool,
-    ) -> Option<Vec<RainMetaDocumentV1Item>> {
+    ) -> Option<(Vec<u8>, Vec<RainMetaDocumentV1Item>, MetaSource)> {
         {
-            if let Some(cached_meta) = self.meta_store.read().unwrap().get_meta(hash_bytes) {
+            if let Some(cached_meta) = self
+                .meta_store
+                .as_ref()
+                .and_then(|v| v.read().unwrap().get_meta(hash_bytes).cloned())
+            {
                 match RainMetaDocumentV1Item::cbor_decode(&cached_meta.clone()) {
                     Ok(v) => {
                         if is_consumable(&v) {
-                            return Some(v);
+                            return Some((cached_meta.clone(), v, MetaSource::Cache));
                         } else {
                             result.problems.push(
                                 ErrorCode::InconsumableMeta
@@ -565,10 +664,10 @@ impl RainDocument {
                 return None;
             };
         }
-        if remote_search {
+        if remote_search && !self.is_cancelled() {
             if let Ok(meta_res) = search(&result.hash, subgraphs).await {
-                {
-                    self.meta_store
+                if let Some(meta_store) = &self.meta_store {
+                    meta_store
                         .write()
                         .unwrap()
                         .update_with(hash_bytes, &meta_res.bytes);
@@ -577,7 +676,7 @@ impl RainDocument {
                 match RainMetaDocumentV1Item::cbor_decode(&meta_res.bytes) {
                     Ok(v) => {
                         if is_consumable(&v) {
-                            return Some(v);
+                            return Some((meta_res.bytes, v, MetaSource::Remote));
                         } else {
                             result.problems.push(
                                 ErrorCode::InconsumableMeta
@@ -596,14 +695,110 @@ impl RainDocument {
         None
     }
 
+    /// processes the pragma statements at top of the text (before the first import or binding),
+    /// a pragma statement is a line that starts with a keyword, the known pragmas are validated
+    /// and their keyword and value are taken out of the text, the unknown ones are taken out as
+    /// a whole line and are handled by the unknown pragma policy of the parse options, lines that
+    /// do not start with a keyword are left in the text and are reported as unexpected tokens
+    pub(super) fn process_pragmas(&mut self, document: &mut String) -> Result<(), Error> {
+        let head_end = document.find(['@', '#']).unwrap_or(document.len());
+        let mut statements: Vec<Vec<ParsedItem>> = vec![];
+        let mut line_offset = 0;
+        for line in document[..head_end].split('\n') {
+            let tokens = inclusive_parse(line, &ANY_PATTERN, line_offset);
+            line_offset += line.len() + 1;
+            if tokens.first().is_some_and(|v| WORD_PATTERN.is_match(&v.0)) {
+                statements.push(tokens);
+            }
+        }
+        let mut processed_keywords = vec![];
+        for tokens in statements {
+            let keyword = &tokens[0];
+            let value = tokens.get(1);
+            if !DOTRAIN_PRAGMA_KEYWORDS.contains(&keyword.0.as_str()) {
+                let position = [keyword.1[0], tokens[tokens.len() - 1].1[1]];
+                fill_in(document, position)?;
+                match self.options.unknown_pragmas() {
+                    UnknownPragmaPolicy::Error => self
+                        .problems
+                        .push(ErrorCode::UndefinedPragma.to_problem(vec![&keyword.0], position)),
+                    UnknownPragmaPolicy::Warn => self
+                        .problems
+                        .push(ErrorCode::UnknownPragma.to_problem(vec![&keyword.0], position)),
+                    UnknownPragmaPolicy::Ignore => {}
+                }
+                continue;
+            }
+            let position = [keyword.1[0], value.map_or(keyword.1[1], |v| v.1[1])];
+            fill_in(document, position)?;
+            if processed_keywords.contains(&keyword.0) {
+                self.problems
+                    .push(ErrorCode::UnexpectedPragma.to_problem(vec![], position));
+                continue;
+            }
+            processed_keywords.push(keyword.0.clone());
+            if keyword.0 == WORD_SET_PRAGMA_KEYWORD {
+                self.process_words_pragma(keyword, value);
+            } else if !value.is_some_and(|v| VERSION_PATTERN.is_match(&v.0)) {
+                self.problems.push(
+                    ErrorCode::InvalidVersion.to_problem(vec![], value.map_or(keyword.1, |v| v.1)),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// processes the word set pragma, i.e. a `word-set` keyword followed by the hash of an
+    /// authoring meta, the authoring meta is read from the meta store (as a deployer's authoring
+    /// meta or as an authoring meta) and is set as this instance's known words
+    fn process_words_pragma(&mut self, keyword: &ParsedItem, hash: Option<&ParsedItem>) {
+        let Some(hash) = hash else {
+            self.problems
+                .push(ErrorCode::ExpectedHexLiteral.to_problem(vec![], keyword.1));
+            return;
+        };
+        if !HASH_PATTERN.is_match(&hash.0) {
+            self.problems
+                .push(ErrorCode::InvalidHash.to_problem(vec![], hash.1));
+            return;
+        }
+        match self.read_authoring_meta(&hash.0) {
+            Some(words) => self.known_words = Some(words),
+            None => self
+                .problems
+                .push(ErrorCode::UndefinedAuthoringMeta.to_problem(vec![&hash.0], hash.1)),
+        }
+    }
+
+    /// reads the authoring meta of the given hash from the meta store, either as the authoring
+    /// meta of a deployer or as a cbor encoded authoring meta
+    fn read_authoring_meta(&self, hash: &str) -> Option<AuthoringMeta> {
+        let hash_bytes = alloy_primitives::hex::decode(hash).ok()?;
+        let store = self.meta_store.as_ref()?.read().unwrap();
+        if let Some(deployer) = store.get_deployer(&hash_bytes) {
+            return deployer.authoring_meta.clone();
+        }
+        RainMetaDocumentV1Item::cbor_decode(store.get_meta(&hash_bytes)?)
+            .ok()?
+            .into_iter()
+            .find(|v| v.magic == KnownMagic::AuthoringMetaV1)
+            .and_then(|v| AuthoringMeta::abi_decode(&v.unpack().ok()?).ok())
+    }
+
     /// validates and processes an import that is meta
     pub(super) async fn process_meta_import(
         &self,
+        meta_bytes: Vec<u8>,
         meta_items: Vec<RainMetaDocumentV1Item>,
+        source: MetaSource,
         result: &mut Import,
         remote_search: bool,
     ) {
-        result.sequence = Some(ImportSequence { dotrain: None });
+        result.sequence = Some(ImportSequence {
+            dotrain: None,
+            meta: Some(meta_bytes),
+            source,
+        });
         for meta in meta_items {
             match meta.unpack() {
                 Ok(meta_data) => {
@@ -611,16 +806,18 @@ impl RainDocument {
                         if let Ok(dotrain_text) = DotrainMeta::from_utf8(meta_data) {
                             let mut dotrain = RainDocument::new(
                                 dotrain_text,
-                                Some(self.meta_store.clone()),
+                                self.meta_store.clone(),
                                 self.import_depth + 1,
                                 self.known_words.clone(),
                             );
+                            dotrain.options = self.options.clone();
+                            dotrain.cancellation = self.cancellation.clone();
                             if remote_search {
                                 dotrain.parse(true, None).await;
                             } else {
                                 dotrain.parse(false, None).await;
                             }
-                            if !dotrain.problems.is_empty() {
+                            if dotrain.problems.iter().any(|p| !p.code.is_warning()) {
                                 result.problems.push(
                                     ErrorCode::InvalidRainDocument
                                         .to_problem(vec![], result.hash_position),
@@ -649,12 +846,19 @@ impl RainDocument {
 
     /// processing imports' namespace and building a ready to merge namespace from each
     /// this requires checking each import's namespace for possible issues (duplicate keys, duplicate word sets, etc)
-    /// as well as applying renames, rebindings and elidings
+    /// as well as applying renames, rebindings and elidings, also returns the members that are
+    /// configured to be re-exported alongside their configuration position
+    #[allow(clippy::type_complexity)]
     pub(super) fn build_imports_namespaces(
         &mut self,
         namespace: &Namespace,
-    ) -> VecDeque<(String, Offsets, Namespace)> {
+    ) -> (
+        VecDeque<(String, Offsets, Namespace)>,
+        Vec<(String, Offsets, NamespaceItem)>,
+    ) {
         let mut imported_namespaces = VecDeque::new();
+        let mut reexports = vec![];
+        let mut configs_problems = vec![];
         for (i, imp) in self.imports.iter().enumerate() {
             if imp.problems.is_empty() {
                 if let Some(item) = namespace.get(&imp.name) {
@@ -678,11 +882,25 @@ impl RainDocument {
                         }
                         if let Some(configs) = &imp.configuration {
                             // applies the configurations and reports back the found problems
-                            // to be pushed to main problems list
-                            self.problems.extend(Self::apply_import_configs(
-                                configs,
-                                &mut new_imp_namespace,
-                            ));
+                            // to be added to the import's configuration and main problems lists
+                            let problems =
+                                Self::apply_import_configs(configs, &mut new_imp_namespace);
+                            if !problems.is_empty() {
+                                configs_problems.push((i, problems));
+                            }
+                            for (old_conf, opt_new_conf) in &configs.groups {
+                                if let Some(new_conf) = opt_new_conf {
+                                    if new_conf.0 == "^" {
+                                        if let Some(item) = new_imp_namespace.get(&old_conf.0) {
+                                            reexports.push((
+                                                old_conf.0.clone(),
+                                                [old_conf.1[0], new_conf.1[1]],
+                                                item.clone(),
+                                            ));
+                                        }
+                                    }
+                                }
+                            }
                         }
                         imported_namespaces.push_back((
                             imp.name.clone(),
@@ -693,7 +911,13 @@ impl RainDocument {
                 }
             }
         }
-        imported_namespaces
+        for (i, problems) in configs_problems {
+            if let Some(configs) = &mut self.imports[i].configuration {
+                configs.problems.extend(problems.iter().cloned());
+            }
+            self.problems.extend(problems);
+        }
+        (imported_namespaces, reexports)
     }
 
     /// applies the import configurations to their corresponding ready to merge namespace
@@ -712,6 +936,14 @@ impl RainDocument {
                                 .to_problem(vec![&old_conf.0], old_conf.1),
                         );
                     }
+                } else if new_conf.0 == "^" {
+                    // re-exports are only validated here and are merged into the main namespace later on
+                    if !new_imp_namespace.contains_key(&old_conf.0) {
+                        problems.push(
+                            ErrorCode::UndefinedIdentifier
+                                .to_problem(vec![&old_conf.0], old_conf.1),
+                        );
+                    }
                 } else {
                     let key = old_conf.0.strip_prefix('\'').unwrap_or(&old_conf.0);
                     if new_imp_namespace.contains_key(key) {
@@ -755,6 +987,10 @@ impl RainDocument {
     }
 
     /// processes a binding item
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(binding = tracing::field::Empty))
+    )]
     pub(super) fn process_binding<'a>(
         &mut self,
         parsed_binding: &'a ParsedItem,
@@ -807,7 +1043,9 @@ impl RainDocument {
             name_position = parsed_binding.1;
             content_position = [parsed_binding.1[1] + 1, parsed_binding.1[1] + 1];
         }
-        let invalid_id = !WORD_PATTERN.is_match(&name);
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("binding", name.as_str());
+        let invalid_id = !self.options.word_pattern().is_match(&name);
         let dup_id = namespace.contains_key(&name);
 
         if invalid_id {
@@ -815,8 +1053,28 @@ impl RainDocument {
                 .push(ErrorCode::InvalidWordPattern.to_problem(vec![&name], name_position));
         }
         if dup_id {
-            self.problems
-                .push(ErrorCode::DuplicateIdentifier.to_problem(vec![&name], name_position));
+            // a binding named the same as an imported namespace or member is reported as an
+            // occupied namespace, described by which of them comes later in the text
+            let import = match namespace.get(&name) {
+                Some(NamespaceItem::Leaf(leaf)) if leaf.import_index >= 0 => {
+                    self.imports.get(leaf.import_index as usize)
+                }
+                Some(NamespaceItem::Node(_)) => self.imports.iter().find(|v| v.name == name),
+                _ => None,
+            };
+            if let Some(import) = import {
+                let detail = if import.position[0] > name_position[0] {
+                    "import shadows a local binding"
+                } else {
+                    "local binding shadows an import"
+                };
+                self.problems.push(
+                    ErrorCode::OccupiedNamespace.to_problem(vec![&name, detail], name_position),
+                );
+            } else {
+                self.problems
+                    .push(ErrorCode::DuplicateIdentifier.to_problem(vec![&name], name_position));
+            }
         }
         if raw_content.is_empty() || raw_content.chars().all(|c| c.is_whitespace()) {
             self.problems
@@ -848,9 +1106,11 @@ impl RainDocument {
                 } else if HEX_PATTERN.is_match(&value) && value.len() % 2 == 1 {
                     self.problems
                         .push(ErrorCode::OddLenHex.to_problem(vec![], content_position));
-                } else if has_err {
-                    self.problems
-                        .push(ErrorCode::OutOfRangeValue.to_problem(vec![], content_position));
+                } else if let Some(detail) = has_err.then(|| u256_overflow_detail(&value)).flatten()
+                {
+                    self.problems.push(
+                        ErrorCode::OutOfRangeValue.to_problem(vec![&detail], content_position),
+                    );
                 }
                 item = BindingItem::Literal(LiteralBindingItem { value });
             } else if let Some((quote, rest)) = Self::is_quote(raw_content, content_position[0]) {
@@ -951,7 +1211,64 @@ impl RainDocument {
         }
     }
 
-    /// merges an imported namespaces to the main namespace
+    /// finds the colliding leaves of 2 namespaces that are of different binding types (such as
+    /// a constant in one and an expression in the other), returns the colliding keys (dot
+    /// separated paths prefixed by the given prefix) with their new and main leaves
+    pub(super) fn find_mixed_binding_types<'a>(
+        prefix: &str,
+        new: &'a Namespace,
+        main: &'a Namespace,
+    ) -> Vec<(String, &'a NamespaceLeaf, &'a NamespaceLeaf)> {
+        let mut mixed = vec![];
+        for (key, new_item) in new {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match (new_item, main.get(key)) {
+                (NamespaceItem::Leaf(new_leaf), Some(NamespaceItem::Leaf(main_leaf)))
+                    if std::mem::discriminant(&new_leaf.element.item)
+                        != std::mem::discriminant(&main_leaf.element.item) =>
+                {
+                    mixed.push((path, new_leaf, main_leaf));
+                }
+                (NamespaceItem::Node(new_node), Some(NamespaceItem::Node(main_node))) => {
+                    mixed.extend(Self::find_mixed_binding_types(&path, new_node, main_node));
+                }
+                _ => {}
+            }
+        }
+        mixed.sort_by(|a, b| a.0.cmp(&b.0));
+        mixed
+    }
+
+    /// reports a problem for each of the colliding leaves of the new and main namespaces that
+    /// are of different binding types, describing both types and their origin hashes, returns
+    /// true if any was found
+    fn report_mixed_binding_types(
+        &mut self,
+        prefix: &str,
+        new: &Namespace,
+        main: &Namespace,
+        hash_position: Offsets,
+    ) -> bool {
+        let mixed = Self::find_mixed_binding_types(prefix, new, main);
+        for (path, new_leaf, main_leaf) in &mixed {
+            let origin = |leaf: &NamespaceLeaf| {
+                format!("{} of {}", leaf.element.item.type_name(), leaf.hash)
+            };
+            self.problems
+                .push(ErrorCode::CollidingBindingTypes.to_problem(
+                    vec![path, &origin(main_leaf), &origin(new_leaf)],
+                    hash_position,
+                ));
+        }
+        !mixed.is_empty()
+    }
+
+    /// merges an imported namespaces to the main namespace, an imported namespace that has
+    /// bindings colliding with bindings of different types is not merged at all
     pub(super) fn merge_namespace(
         &mut self,
         name: String,
@@ -966,6 +1283,9 @@ impl RainDocument {
                         .problems
                         .push(ErrorCode::OccupiedNamespace.to_problem(vec![], hash_position)),
                     NamespaceItem::Node(node) => {
+                        if self.report_mixed_binding_types(&name, &new, node, hash_position) {
+                            return;
+                        }
                         if let Some(code) = Self::check_namespace(&new, node) {
                             self.problems.push(code.to_problem(vec![], hash_position));
                         } else {
@@ -976,7 +1296,7 @@ impl RainDocument {
             } else {
                 main.insert(name.clone(), NamespaceItem::Node(new));
             }
-        } else {
+        } else if !self.report_mixed_binding_types("", &new, main, hash_position) {
             Self::merge(&new, main);
         }
     }
@@ -1235,6 +1555,373 @@ impl RainDocument {
             vec![]
         }
     }
+
+    /// counts the opcodes of the given nodes recursively by their names
+    pub(super) fn count_opcodes(nodes: &[Node], counts: &mut HashMap<String, usize>) {
+        for node in nodes {
+            if let Node::Opcode(op) = node {
+                *counts.entry(op.opcode.name.clone()).or_insert(0) += 1;
+                Self::count_opcodes(&op.inputs, counts);
+            }
+        }
+    }
+
+    /// collects the given nodes' opcodes names, document positions and operands recursively
+    pub(super) fn collect_operands(
+        binding: &Binding,
+        nodes: &[Node],
+        operands: &mut Vec<(String, Offsets, Option<u8>)>,
+    ) {
+        for node in nodes {
+            if let Node::Opcode(op) = node {
+                operands.push((
+                    op.opcode.name.clone(),
+                    binding.map_to_document(op.position),
+                    op.resolved_operand(),
+                ));
+                Self::collect_operands(binding, &op.inputs, operands);
+            }
+        }
+    }
+
+    /// collects the literals of the given nodes and of their nested inputs with their resolved values
+    pub(super) fn collect_literals<'a>(
+        nodes: &'a [Node],
+        literals: &mut Vec<(&'a Literal, Result<U256, Error>)>,
+    ) {
+        for node in nodes {
+            match node {
+                Node::Literal(literal) => literals.push((literal, to_u256(&literal.value))),
+                Node::Opcode(op) => Self::collect_literals(&op.inputs, literals),
+                Node::Alias(_) => {}
+            }
+        }
+    }
+
+    /// reports a warning at the name of each of the given bindings that is named the same as one
+    /// of the given words, literal bindings are skipped unless they are referenced as a value, i.e.
+    /// constants that are only used as operand args are not ambiguous
+    pub(super) fn check_opcode_name_collisions(
+        bindings: &mut [Binding],
+        words: Option<&AuthoringMeta>,
+    ) {
+        fn value_references<'a>(nodes: &'a [Node], references: &mut HashSet<&'a str>) {
+            for node in nodes {
+                match node {
+                    Node::Literal(literal) => references.extend(literal.id.as_deref()),
+                    Node::Alias(alias) => {
+                        references.insert(&alias.name);
+                    }
+                    Node::Opcode(op) => value_references(&op.inputs, references),
+                }
+            }
+        }
+        let Some(words) = words else {
+            return;
+        };
+        let mut references = HashSet::new();
+        for binding in bindings.iter() {
+            if let BindingItem::Exp(exp) = &binding.item {
+                for src in &exp.ast {
+                    for line in &src.lines {
+                        value_references(&line.nodes, &mut references);
+                    }
+                }
+            }
+        }
+        let colliding: Vec<usize> = bindings
+            .iter()
+            .enumerate()
+            .filter(|(_, binding)| {
+                words.0.iter().any(|v| v.word == binding.name)
+                    && (!matches!(binding.item, BindingItem::Literal(_))
+                        || references.contains(binding.name.as_str()))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        for i in colliding {
+            let binding = &mut bindings[i];
+            binding.problems.push(
+                ErrorCode::CollidingOpcodeName
+                    .to_problem(vec![&binding.name], binding.name_position),
+            );
+        }
+    }
+
+    /// collects the references of the given binding to other bindings and imports, i.e. the
+    /// referenced literal, elided and quote bindings, quoted dependencies and pragma sources
+    pub(super) fn binding_references(binding: &Binding, references: &mut Vec<String>) {
+        fn nodes_references(nodes: &[Node], references: &mut Vec<String>) {
+            for node in nodes {
+                match node {
+                    Node::Literal(literal) => references.extend(literal.id.clone()),
+                    Node::Alias(alias) => references.push(alias.name.clone()),
+                    Node::Opcode(op) => {
+                        if let Some(operand_args) = &op.operand_args {
+                            references.extend(
+                                operand_args
+                                    .args
+                                    .iter()
+                                    .filter_map(|arg| arg.binding_id.as_ref())
+                                    .map(|v| v.0.clone()),
+                            );
+                        }
+                        nodes_references(&op.inputs, references);
+                    }
+                }
+            }
+        }
+        match &binding.item {
+            BindingItem::Exp(exp) => {
+                references.extend(exp.dependencies.iter().cloned());
+                references.extend(
+                    exp.pragmas
+                        .iter()
+                        .flat_map(|p| p.sources.iter().map(|v| v.0 .0.clone())),
+                );
+                for src in &exp.ast {
+                    for line in &src.lines {
+                        nodes_references(&line.nodes, references);
+                    }
+                }
+            }
+            BindingItem::Quote(quote) => references.push(quote.quote.clone()),
+            _ => {}
+        }
+    }
+
+    /// the length of the given not ended comment up to the next line that starts a binding
+    pub(super) fn unended_comment_len(comment: &str) -> usize {
+        comment
+            .match_indices('\n')
+            .find(|(i, _)| comment[i + 1..].trim_start().starts_with('#'))
+            .map_or(comment.len(), |(i, _)| i)
+    }
+
+    /// the end of the content of an import or a binding that starts at the given offset and
+    /// spans up to the given end, i.e. excluding the trailing whitespaces and comments
+    pub(super) fn content_end(&self, start: usize, end: usize) -> usize {
+        let mut end = start + self.text[start..end].trim_end().len();
+        while let Some(c) = self
+            .comments
+            .iter()
+            .find(|c| c.position[1] == end && c.position[0] > start)
+        {
+            end = start + self.text[start..c.position[0]].trim_end().len();
+        }
+        end
+    }
+
+    /// the text of the given import statement with the duplicate statements of its
+    /// configuration removed, the statement text is kept as is if it has none
+    pub(super) fn organized_import_text(imp: &Import, text: &str) -> String {
+        let Some(config) = imp.configuration.as_ref().filter(|v| {
+            v.problems
+                .iter()
+                .any(|p| p.code == ErrorCode::DuplicateImportStatement)
+        }) else {
+            return text.to_owned();
+        };
+        let mut pieces = vec![];
+        if imp.name != "." {
+            pieces.push(imp.name.clone());
+        }
+        pieces.push(imp.hash.clone());
+        let mut groups: Vec<(&str, Option<&str>)> = vec![];
+        for (first, second) in &config.groups {
+            let group = (first.0.as_str(), second.as_ref().map(|v| v.0.as_str()));
+            if !groups.contains(&group) {
+                groups.push(group);
+                pieces.push(first.0.clone());
+                pieces.extend(second.as_ref().map(|v| v.0.clone()));
+            }
+        }
+        format!("@{}", pieces.join(" "))
+    }
+
+    /// the configuration groups of the given import by their texts, empty if it has none
+    pub(super) fn import_configuration_groups(imp: &Import) -> Vec<(&str, Option<&str>)> {
+        imp.configuration
+            .as_ref()
+            .map(|config| {
+                config
+                    .groups
+                    .iter()
+                    .map(|(first, second)| {
+                        (first.0.as_str(), second.as_ref().map(|v| v.0.as_str()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// determines if the given import re-exports or rebinds any of its members, i.e. it has
+    /// effects beyond the bindings of this instance that reference it
+    pub(super) fn has_non_local_configuration(imp: &Import) -> bool {
+        Self::import_configuration_groups(imp)
+            .iter()
+            .any(|(first, second)| !first.starts_with('\'') && second.is_some_and(|v| v != "!"))
+    }
+
+    /// flattens the given namespace recursively into the dotted paths of its leaves
+    pub(super) fn flatten_namespace<'a>(
+        namespace: &'a Namespace,
+        path: &str,
+        flat: &mut BTreeMap<String, &'a NamespaceLeaf>,
+    ) {
+        for (key, item) in namespace {
+            let key_path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            match item {
+                NamespaceItem::Leaf(leaf) => {
+                    flat.insert(key_path, leaf);
+                }
+                NamespaceItem::Node(node) => Self::flatten_namespace(node, &key_path, flat),
+            }
+        }
+    }
+
+    /// collects the dependency entries of the given imports and their nested imports recursively,
+    /// skipping the hashes that are already collected
+    pub(super) fn collect_dependencies(imports: &[Import], entries: &mut Vec<DependencyEntry>) {
+        for imp in imports {
+            if imp.hash.is_empty() || entries.iter().any(|v| v.hash == imp.hash) {
+                continue;
+            }
+            let magics = imp
+                .sequence
+                .as_ref()
+                .and_then(|v| v.meta.as_ref())
+                .and_then(|v| RainMetaDocumentV1Item::cbor_decode(v).ok())
+                .map(|items| items.iter().map(|v| v.magic.to_string()).collect())
+                .unwrap_or_default();
+            entries.push(DependencyEntry {
+                name: imp.name.clone(),
+                hash: imp.hash.clone(),
+                magics,
+                source: imp.sequence.as_ref().map(|v| v.source),
+            });
+            if let Some(dotrain) = imp.sequence.as_ref().and_then(|v| v.dotrain.as_ref()) {
+                Self::collect_dependencies(&dotrain.imports, entries);
+            }
+        }
+    }
+
+    /// collects the meta bytes of the given imports and their nested imports by their hashes
+    pub(super) fn collect_metas(imports: &[Import], metas: &mut BTreeMap<String, Vec<u8>>) {
+        for imp in imports {
+            let Some(sequence) = &imp.sequence else {
+                continue;
+            };
+            if let Some(meta) = &sequence.meta {
+                metas
+                    .entry(imp.hash.to_ascii_lowercase())
+                    .or_insert_with(|| meta.clone());
+            }
+            if let Some(dotrain) = &sequence.dotrain {
+                Self::collect_metas(&dotrain.imports, metas);
+            }
+        }
+    }
+
+    /// collects the declared and the actual hashes of the given imports (and of their nested
+    /// dotrain imports recursively) whose resolved meta bytes do not hash to the declared hash
+    pub(super) fn collect_hash_mismatches(
+        imports: &[Import],
+        mismatches: &mut Vec<(String, String)>,
+    ) {
+        for imp in imports {
+            let Some(sequence) = &imp.sequence else {
+                continue;
+            };
+            if let Some(meta) = &sequence.meta {
+                let actual =
+                    alloy_primitives::hex::encode_prefixed(alloy_primitives::keccak256(meta));
+                if !actual.eq_ignore_ascii_case(&imp.hash) {
+                    mismatches.push((imp.hash.clone(), actual));
+                }
+            }
+            if let Some(dotrain) = &sequence.dotrain {
+                Self::collect_hash_mismatches(&dotrain.imports, mismatches);
+            }
+        }
+    }
+
+    /// compares the given nodes recursively by their structure, ignoring their positions
+    pub(super) fn nodes_eq_ignoring_positions(a: &[Node], b: &[Node]) -> bool {
+        fn operand_args(op: &Opcode) -> Option<Vec<(&String, &Option<String>)>> {
+            op.operand_args
+                .as_ref()
+                .map(|v| v.args.iter().map(|arg| (&arg.name, &arg.value)).collect())
+        }
+        a.len() == b.len()
+            && a.iter().zip(b).all(|pair| match pair {
+                (Node::Literal(x), Node::Literal(y)) => x.value == y.value && x.id == y.id,
+                (Node::Alias(x), Node::Alias(y)) => x.name == y.name,
+                (Node::Opcode(x), Node::Opcode(y)) => {
+                    x.opcode.name == y.opcode.name
+                        && x.operand == y.operand
+                        && x.output == y.output
+                        && operand_args(x) == operand_args(y)
+                        && Self::nodes_eq_ignoring_positions(&x.inputs, &y.inputs)
+                }
+                _ => false,
+            })
+    }
+
+    /// compares the given bindings by their structure, ignoring their positions
+    pub(super) fn bindings_eq_ignoring_positions(a: &Binding, b: &Binding) -> bool {
+        a.name == b.name
+            && Self::problems_codes(&a.problems) == Self::problems_codes(&b.problems)
+            && match (&a.item, &b.item) {
+                (BindingItem::Exp(x), BindingItem::Exp(y)) => {
+                    x.ast.len() == y.ast.len()
+                        && x.ast.iter().zip(&y.ast).all(|(src_x, src_y)| {
+                            src_x.lines.len() == src_y.lines.len()
+                                && src_x
+                                    .lines
+                                    .iter()
+                                    .zip(&src_y.lines)
+                                    .all(|(line_x, line_y)| {
+                                        line_x
+                                            .aliases
+                                            .iter()
+                                            .map(|v| &v.name)
+                                            .eq(line_y.aliases.iter().map(|v| &v.name))
+                                            && Self::nodes_eq_ignoring_positions(
+                                                &line_x.nodes,
+                                                &line_y.nodes,
+                                            )
+                                    })
+                        })
+                }
+                (x, y) => x == y,
+            }
+    }
+
+    /// compares the given imports by their structure, ignoring their positions
+    pub(super) fn imports_eq_ignoring_positions(a: &Import, b: &Import) -> bool {
+        let groups = |import: &Import| {
+            import.configuration.as_ref().map(|v| {
+                v.groups
+                    .iter()
+                    .map(|(key, value)| (key.0.clone(), value.as_ref().map(|v| v.0.clone())))
+                    .collect::<Vec<_>>()
+            })
+        };
+        a.name == b.name
+            && a.hash == b.hash
+            && Self::problems_codes(&a.problems) == Self::problems_codes(&b.problems)
+            && groups(a) == groups(b)
+    }
+
+    /// the error codes of the given problems
+    pub(super) fn problems_codes(problems: &[Problem]) -> Vec<ErrorCode> {
+        problems.iter().map(|v| v.code).collect()
+    }
 }
 
 impl PartialEq for RainDocument {
@@ -1247,6 +1934,7 @@ impl PartialEq for RainDocument {
             && self.namespace == other.namespace
             && self.imports == other.imports
             && self.known_words == other.known_words
+            && self.options == other.options
             && self.problems == other.problems
             && self.error == other.error
     }
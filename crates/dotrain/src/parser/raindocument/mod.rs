@@ -1,11 +1,24 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+};
 use serde::{Serialize, Deserialize};
 use async_recursion::async_recursion;
 use futures::executor::block_on;
-use rain_metadata::{types::authoring::v1::AuthoringMeta, Store};
-use super::super::{
-    error::{Error, ErrorCode},
-    types::{ast::*, patterns::*},
+use alloy_primitives::U256;
+use rain_metadata::{
+    types::authoring::v1::AuthoringMeta, ContentEncoding, ContentLanguage, ContentType, KnownMagic,
+    RainMetaDocumentV1Item, Store,
+};
+use super::{
+    line_offsets, line_number_at, to_u256, ParseOptions, ParseOptionsSnapshot,
+    super::{
+        error::{ComposeError, Error, ErrorCode},
+        types::{ast::*, patterns::*},
+    },
 };
 
 #[cfg(feature = "js-api")]
@@ -92,14 +105,26 @@ pub struct RainDocument {
     pub(crate) import_depth: usize,
     pub(crate) namespace: Namespace,
     #[serde(skip)]
-    pub(crate) meta_store: Arc<RwLock<Store>>,
+    pub(crate) meta_store: Option<Arc<RwLock<Store>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "js-api", tsify(type = "IAuthoringMeta"))]
     pub(crate) known_words: Option<AuthoringMeta>,
+    #[serde(skip)]
+    pub(crate) words: Option<AuthoringMeta>,
+    #[serde(skip)]
+    pub(crate) options: ParseOptions,
+    #[serde(skip)]
+    pub(crate) cancellation: Option<Arc<AtomicBool>>,
+    #[serde(skip)]
+    pub(crate) uri: Option<String>,
+    #[serde(skip)]
+    pub(crate) line_offsets: Vec<usize>,
 }
 
 impl RainDocument {
-    /// Creates an instance and parses with remote meta search enabled
+    /// Creates an instance and parses with remote meta search enabled, the given words (if any)
+    /// are the known words that the opcodes are validated with, unless the text pins a word set,
+    /// see [RainDocument::known_words]
     pub async fn create_async(
         text: String,
         meta_store: Option<Arc<RwLock<Store>>>,
@@ -111,7 +136,9 @@ impl RainDocument {
         rain_document
     }
 
-    /// Creates an instance and parses with remote meta search disabled (cached metas only)
+    /// Creates an instance and parses with remote meta search disabled (cached metas only), the
+    /// given words (if any) are the known words that the opcodes are validated with, unless the
+    /// text pins a word set, see [RainDocument::known_words]
     pub fn create(
         text: String,
         meta_store: Option<Arc<RwLock<Store>>>,
@@ -123,6 +150,12 @@ impl RainDocument {
         rain_document
     }
 
+    /// Starts building an instance of the given text, for creating it with a non default
+    /// configuration, i.e. parse options, uri or cancellation flag, see [RainDocumentBuilder]
+    pub fn builder(text: String) -> RainDocumentBuilder {
+        RainDocumentBuilder::new(text)
+    }
+
     /// Get the front matter without parsing the dotrain
     pub fn get_front_matter(text: &str) -> Option<&str> {
         // split front matter and rest of the text
@@ -134,6 +167,111 @@ impl RainDocument {
     }
 }
 
+/// Builder of a [RainDocument] with an optional meta store, words, rebinds, parse options, uri
+/// and cancellation flag, the unset ones are left as their defaults, i.e. the same as
+/// [RainDocument::create()]
+///
+/// The uri is looked up in the Store's dotrain cache for detecting imports that resolve to the
+/// document itself, see [ErrorCode::InvalidSelfImport]
+///
+/// Once the cancellation flag is set, no new remote meta searches are started for the document
+/// and its nested imports, searches that are already pending are not interrupted, they are
+/// awaited and their results are used, the imports that are not resolved from them or from the
+/// metas cached in the Store are reported as undefined imports, so the result is partial
+#[derive(Debug, Clone, Default)]
+pub struct RainDocumentBuilder {
+    text: String,
+    meta_store: Option<Arc<RwLock<Store>>>,
+    words: Option<AuthoringMeta>,
+    rebinds: Option<Vec<Rebind>>,
+    options: ParseOptions,
+    uri: Option<String>,
+    cancellation: Option<Arc<AtomicBool>>,
+}
+
+impl RainDocumentBuilder {
+    /// Creates a new builder for the given text
+    pub fn new(text: String) -> RainDocumentBuilder {
+        RainDocumentBuilder {
+            text,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the meta store that the imports and word sets are resolved from
+    pub fn with_meta_store(
+        mut self,
+        meta_store: Option<Arc<RwLock<Store>>>,
+    ) -> RainDocumentBuilder {
+        self.meta_store = meta_store;
+        self
+    }
+
+    /// Sets the words that the opcodes are validated with, unless the text pins a word set,
+    /// see [RainDocument::known_words]
+    pub fn with_words(mut self, words: Option<AuthoringMeta>) -> RainDocumentBuilder {
+        self.words = words;
+        self
+    }
+
+    /// Sets the rebinds that are applied to the document's bindings
+    pub fn with_rebinds(mut self, rebinds: Option<Vec<Rebind>>) -> RainDocumentBuilder {
+        self.rebinds = rebinds;
+        self
+    }
+
+    /// Sets the parse options instead of the default ones
+    pub fn with_options(mut self, options: ParseOptions) -> RainDocumentBuilder {
+        self.options = options;
+        self
+    }
+
+    /// Sets the uri that the text lives at
+    pub fn with_uri(mut self, uri: String) -> RainDocumentBuilder {
+        self.uri = Some(uri);
+        self
+    }
+
+    /// Sets the cancellation flag of remote meta searches, once set no new search is started
+    pub fn with_cancellation(mut self, cancellation: Arc<AtomicBool>) -> RainDocumentBuilder {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Creates the instance and parses it with remote meta search disabled (cached metas only)
+    pub fn build(self) -> RainDocument {
+        let (mut rain_document, rebinds) = self.into_parts();
+        block_on(rain_document.parse(false, rebinds));
+        rain_document
+    }
+
+    /// Creates the instance and parses it with remote meta search enabled
+    pub async fn build_async(self) -> RainDocument {
+        let (mut rain_document, rebinds) = self.into_parts();
+        rain_document.parse(true, rebinds).await;
+        rain_document
+    }
+
+    /// Creates the instance and parses only its syntax without a Store, skipping all meta and
+    /// import resolutions, the meta store and rebinds are not used, imports are recorded but are
+    /// left unresolved, so any references to imported bindings are reported as undefined
+    pub fn build_syntax_only(self) -> RainDocument {
+        let (mut rain_document, _) = self.into_parts();
+        rain_document.meta_store = None;
+        block_on(rain_document.parse_text(false, false, None));
+        rain_document
+    }
+
+    /// the unparsed instance alongside the rebinds it is parsed with
+    fn into_parts(self) -> (RainDocument, Option<Vec<Rebind>>) {
+        let mut rain_document = RainDocument::new(self.text, self.meta_store, 0, self.words);
+        rain_document.options = self.options;
+        rain_document.uri = self.uri;
+        rain_document.cancellation = self.cancellation;
+        (rain_document, self.rebinds)
+    }
+}
+
 impl RainDocument {
     /// Updates the text and parses right away with remote meta search disabled (cached metas only)
     pub fn update(&mut self, new_text: String, rebinds: Option<Vec<Rebind>>) {
@@ -152,6 +290,43 @@ impl RainDocument {
         &self.text
     }
 
+    /// The binding whose span (from its name up to the end of its content) contains the given
+    /// offset, the end of the span is exclusive
+    pub fn binding_at(&self, offset: usize) -> Option<&Binding> {
+        self.bindings
+            .iter()
+            .find(|v| v.position[0] <= offset && v.position[1] > offset)
+    }
+
+    /// Same as [RainDocument::binding_at] for the given zero-based line and character (byte
+    /// column) of this instance's text, the character is clamped to the line's length
+    pub fn binding_at_position(&self, line: usize, character: usize) -> Option<&Binding> {
+        let line_offset = *self.line_offsets.get(line)?;
+        let next_line_offset = self
+            .line_offsets
+            .get(line + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+        self.binding_at((line_offset + character).min(next_line_offset))
+    }
+
+    /// The text of the given span of this instance's text, None if the span is out of range,
+    /// reversed or does not fall on char boundaries
+    pub fn span_text(&self, offsets: Offsets) -> Option<&str> {
+        self.text.get(offsets[0]..offsets[1])
+    }
+
+    /// The offsets of the starts of this instance's text lines, computed once per parse
+    pub fn line_offsets(&self) -> &[usize] {
+        &self.line_offsets
+    }
+
+    /// Calculates the line number of the given position in this instance's text by
+    /// binary search over the cached line offsets, same as [super::line_number]
+    pub fn line_number(&self, pos: usize) -> usize {
+        line_number_at(&self.line_offsets, self.text.len(), pos)
+    }
+
     /// This instance's front matter
     pub fn front_matter(&self) -> &str {
         &self.text[0..self.front_matter_offset]
@@ -180,11 +355,58 @@ impl RainDocument {
         &self.comments
     }
 
+    /// This instance's comments that are not within any binding's span (such as header or
+    /// license comments at the top of the document), since a binding's span reaches up to the
+    /// next binding, these are the comments coming before the first binding
+    pub fn leading_comments(&self) -> Vec<&Comment> {
+        self.comments
+            .iter()
+            .filter(|c| {
+                !self.bindings.iter().any(|b| {
+                    // binding position starts after its "#"
+                    b.position[0].saturating_sub(1) <= c.position[0]
+                        && c.position[0] < b.position[1]
+                })
+            })
+            .collect()
+    }
+
+    /// Checks if the given offset is inside any of this instance's comments, i.e. strictly
+    /// after a comment's start and before its end, so the offsets right before and right
+    /// after a comment are outside of it, a comment that is not closed by a block delimiter
+    /// (a line comment or an unended block comment) also contains its end offset since
+    /// typing there continues the comment
+    pub fn is_in_comment(&self, offset: usize) -> bool {
+        self.comments.iter().any(|c| {
+            let closed = self.options.comment_styles().iter().any(|v| match v {
+                CommentStyle::Block { open, close } => {
+                    c.comment.len() >= open.len() + close.len()
+                        && c.comment.starts_with(open.as_str())
+                        && c.comment.ends_with(close.as_str())
+                }
+                CommentStyle::Line { .. } => false,
+            });
+            c.position[0] < offset
+                && (offset < c.position[1] || (!closed && offset == c.position[1]))
+        })
+    }
+
     /// This instance's imports
     pub fn imports(&self) -> &Vec<Import> {
         &self.imports
     }
 
+    /// The parsed document of this instance's import at the given index (in the same order as
+    /// [RainDocument::imports]), if the import is resolved and is a dotrain
+    pub fn imported_document(&self, import_index: usize) -> Option<&RainDocument> {
+        self.imports
+            .get(import_index)?
+            .sequence
+            .as_ref()?
+            .dotrain
+            .as_ref()
+    }
+
     /// This instance's bindings
     pub fn bindings(&self) -> &Vec<Binding> {
         &self.bindings
@@ -195,12 +417,37 @@ impl RainDocument {
         &self.namespace
     }
 
-    /// This instance's meta Store instance
+    /// This instance's meta Store instance, an instance that was parsed with
+    /// [RainDocumentBuilder::build_syntax_only] has none, so a new empty Store is returned for it
     pub fn store(&self) -> Arc<RwLock<Store>> {
-        self.meta_store.clone()
+        self.meta_store.clone().unwrap_or_default()
+    }
+
+    /// This instance's word pattern used for validating binding names and aliases
+    pub fn word_pattern(&self) -> &WordPattern {
+        self.options.word_pattern()
     }
 
-    /// This instance's words
+    /// This instance's parse options
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// The uri this instance's text lives at, if it was created with one
+    pub fn uri(&self) -> Option<&str> {
+        self.uri.as_deref()
+    }
+
+    /// If this instance was created with a cancellation flag that has been set, i.e. its
+    /// parsing may have stopped resolving imports remotely, see [RainDocumentBuilder::with_cancellation]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(|v| v.load(Ordering::Relaxed))
+    }
+
+    /// This instance's words, i.e. the authoring meta pinned by its word set pragma, otherwise
+    /// the words it was created with, which are kept across updates
     pub fn known_words(&self) -> &Option<AuthoringMeta> {
         &self.known_words
     }
@@ -218,17 +465,526 @@ impl RainDocument {
         all
     }
 
+    /// The set of the codes of all problems of this instance, i.e. the top problems, the
+    /// bindings problems (including the problems of the expression bindings' parsed rainlang)
+    /// and the imports' configuration problems
+    pub fn problem_codes(&self) -> HashSet<ErrorCode> {
+        let mut codes: HashSet<ErrorCode> = self.all_problems().iter().map(|v| v.code).collect();
+        codes.extend(
+            self.import_configuration_problems()
+                .into_iter()
+                .flatten()
+                .map(|v| v.code),
+        );
+        for binding in &self.bindings {
+            if let BindingItem::Exp(exp) = &binding.item {
+                codes.extend(exp.problems().iter().map(|v| v.code));
+            }
+        }
+        codes
+    }
+
     /// This instance's bindings problems
     pub fn bindings_problems(&self) -> Vec<&Problem> {
         self.bindings.iter().flat_map(|v| &v.problems).collect()
     }
 
+    /// The problems of each import's configuration (renames, rebindings, elisions and
+    /// re-exports), in the same order as [RainDocument::imports], an import without any
+    /// configuration has no problems, these problems are also part of the top problems
+    pub fn import_configuration_problems(&self) -> Vec<&[Problem]> {
+        self.imports
+            .iter()
+            .map(|imp| {
+                imp.configuration
+                    .as_ref()
+                    .map_or(&[][..], |v| v.problems.as_slice())
+            })
+            .collect()
+    }
+
+    /// The position and the declared namespace name (alias) of each of this instance's imports
+    /// in the order they are stated, None for an import that is not namespaced, i.e. its
+    /// members are imported directly into the root namespace
+    pub fn import_aliases(&self) -> Vec<(Offsets, Option<String>)> {
+        self.imports
+            .iter()
+            .map(|v| (v.position, (v.name != ".").then(|| v.name.clone())))
+            .collect()
+    }
+
+    /// Encodes this instance's text as a cbor encoded `DotrainV1` RainMetaDocument, i.e. the meta
+    /// that this document is published and imported as, its keccak256 hash is the hash that
+    /// imports of this document are stated with
+    pub fn to_dotrain_meta(&self) -> Result<Vec<u8>, Error> {
+        Ok(RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(self.text.as_bytes()),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }
+        .cbor_encode()?)
+    }
+
+    /// Recomputes the keccak256 hash of the meta bytes that each of this instance's imports was
+    /// resolved to and reports a problem at the hash of each import whose declared hash does
+    /// not match it, i.e. a poisoned cache or a wrong-hash meta, the imports of imported
+    /// dotrain documents are verified as well and are reported at the importing statement
+    pub fn verify_import_integrity(&self) -> Vec<Problem> {
+        let mut problems = vec![];
+        for imp in &self.imports {
+            let mut mismatches = vec![];
+            Self::collect_hash_mismatches(std::slice::from_ref(imp), &mut mismatches);
+            for (declared, actual) in mismatches {
+                problems.push(
+                    ErrorCode::MismatchImportHash
+                        .to_problem(vec![&declared, &actual], imp.hash_position),
+                );
+            }
+        }
+        problems
+    }
+
+    /// The meta bytes (cbor encoded RainMetaDocument) that the import at the given index was
+    /// resolved to, as they were read when parsing, None if the import was not resolved
+    pub fn import_meta_bytes(&self, import_index: usize) -> Option<&[u8]> {
+        self.imports
+            .get(import_index)?
+            .sequence
+            .as_ref()?
+            .meta
+            .as_deref()
+    }
+
+    /// Previews the rebindings of the import at the given index without applying them, i.e.
+    /// each original member name mapped to what its configuration binds it to (a new name for
+    /// renames, a value or quote for rebindings, `!` for elisions and `^` for re-exports) in the
+    /// order they are written, alongside the problems of the configurations that target members
+    /// which don't exist in the imported namespace
+    pub fn preview_rebindings(&self, import_index: usize) -> (Vec<(String, String)>, Vec<Problem>) {
+        let Some(imp) = self.imports.get(import_index) else {
+            return (vec![], vec![]);
+        };
+        let Some(configs) = &imp.configuration else {
+            return (vec![], vec![]);
+        };
+        let pairs = configs
+            .groups
+            .iter()
+            .filter_map(|(old_conf, opt_new_conf)| {
+                opt_new_conf.as_ref().map(|new_conf| {
+                    (
+                        old_conf
+                            .0
+                            .strip_prefix('\'')
+                            .unwrap_or(&old_conf.0)
+                            .to_owned(),
+                        new_conf.0.clone(),
+                    )
+                })
+            })
+            .collect();
+
+        // dry run of the configurations on a copy of the imported namespace
+        let problems = if let Some(dotrain) = imp.sequence.as_ref().and_then(|v| v.dotrain.as_ref())
+        {
+            let mut namespace =
+                Self::copy_namespace(&dotrain.namespace, import_index as isize, &imp.hash);
+            Self::apply_import_configs(configs, &mut namespace)
+                .into_iter()
+                .filter(|v| v.code == ErrorCode::UndefinedIdentifier)
+                .collect()
+        } else {
+            vec![]
+        };
+        (pairs, problems)
+    }
+
+    /// The dependency manifest of this instance, i.e. an entry for each of its imports and their
+    /// nested imports (recursively, each hash once, in the order they are declared) with the
+    /// declared hash, the magics of the meta it resolved to and whether it was resolved from the
+    /// Store's cache or by remote meta search, it is serializable, e.g. as a lockfile
+    pub fn dependency_manifest(&self) -> Vec<DependencyEntry> {
+        let mut entries = vec![];
+        Self::collect_dependencies(&self.imports, &mut entries);
+        entries
+    }
+
+    /// Takes a fully resolved snapshot of this instance, i.e. its text, namespace, bindings,
+    /// imports, authoring meta, parse options and the meta bytes of all of its imports (recursively) as they were
+    /// resolved when parsing, so it can be shipped and composed later without any further
+    /// meta search, errors if this instance is not consumable, i.e. it has a runtime error or
+    /// any problem that is not a warning
+    pub fn freeze(&self) -> Result<FrozenDocument, ComposeError> {
+        if let Some(error) = &self.error {
+            return Err(ComposeError::Reject(error.clone()));
+        }
+        let problems: Vec<Problem> = self
+            .all_problems()
+            .into_iter()
+            .filter(|v| !v.code.is_warning())
+            .cloned()
+            .collect();
+        if !problems.is_empty() {
+            return Err(ComposeError::Problems(problems));
+        }
+        let mut metas = BTreeMap::new();
+        Self::collect_metas(&self.imports, &mut metas);
+        Ok(FrozenDocument {
+            text: self.text.clone(),
+            namespace: self.namespace.clone(),
+            bindings: self.bindings.clone(),
+            imports: self.imports.clone(),
+            metas,
+            known_words: self.known_words.clone(),
+            options: ParseOptionsSnapshot::from(&self.options),
+        })
+    }
+
+    /// This instance's namespace flattened into the dotted paths of its leaves (bindings), i.e.
+    /// the intermediate nodes (imports' namespaces) are only reflected in the paths, ordered by
+    /// the paths, useful for inspecting namespace resolutions
+    pub fn flat_namespace(&self) -> BTreeMap<String, &NamespaceLeaf> {
+        let mut flat = BTreeMap::new();
+        Self::flatten_namespace(&self.namespace, "", &mut flat);
+        flat
+    }
+
+    /// The constant bindings of this instance's namespace (including the imported ones) fully
+    /// qualified by their dotted paths with their values resolved to U256, ordered by the paths,
+    /// constants whose values fail to resolve are left out, see [RainDocument::invalid_constants]
+    pub fn constants(&self) -> Vec<(String, U256)> {
+        self.flat_namespace()
+            .into_iter()
+            .filter_map(|(path, leaf)| match &leaf.element.item {
+                BindingItem::Literal(c) => to_u256(&c.value).ok().map(|v| (path, v)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The constant bindings of this instance's namespace (including the imported ones) whose
+    /// values fail to resolve to U256 (such as string literals or out of range values) fully
+    /// qualified by their dotted paths with the resolution errors, ordered by the paths
+    pub fn invalid_constants(&self) -> Vec<(String, Error)> {
+        self.flat_namespace()
+            .into_iter()
+            .filter_map(|(path, leaf)| match &leaf.element.item {
+                BindingItem::Literal(c) => to_u256(&c.value).err().map(|e| (path, e)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The index of the import that the given dotted path of this instance's namespace came from,
+    /// for namespace nodes it is the import of their members, None for local bindings and for
+    /// paths that are not in the namespace
+    pub fn origin_of(&self, path: &str) -> Option<usize> {
+        let path = path.strip_prefix('.').unwrap_or(path);
+        let flat_namespace = self.flat_namespace();
+        let leaf = flat_namespace.get(path).copied().or_else(|| {
+            let prefix = format!("{path}.");
+            flat_namespace
+                .iter()
+                .find(|(key, _)| key.starts_with(&prefix))
+                .map(|(_, leaf)| *leaf)
+        })?;
+        usize::try_from(leaf.import_index).ok()
+    }
+
+    /// Counts the occurrences of each opcode used across all expression bindings of this instance
+    pub fn used_opcodes(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for binding in &self.bindings {
+            if let BindingItem::Exp(exp) = &binding.item {
+                for src in exp.ast() {
+                    for line in &src.lines {
+                        Self::count_opcodes(&line.nodes, &mut counts);
+                    }
+                }
+            }
+        }
+        counts
+    }
+
+    /// The opcodes of all expression bindings of this instance with their document positions
+    /// and resolved operands (see [Opcode::resolved_operand]) in source order, i.e. by the
+    /// bindings order and an opcode coming before its inputs
+    pub fn resolved_operands(&self) -> Vec<(String, Offsets, Option<u8>)> {
+        let mut operands = vec![];
+        for binding in &self.bindings {
+            if let BindingItem::Exp(exp) = &binding.item {
+                for src in exp.ast() {
+                    for line in &src.lines {
+                        Self::collect_operands(binding, &line.nodes, &mut operands);
+                    }
+                }
+            }
+        }
+        operands
+    }
+
+    /// The literals of all expression bindings of this instance with their resolved values in
+    /// source order (see [RainDocument::resolved_operands]), literals that cannot be resolved to
+    /// a u256 (such as string literals or decimals) come with the resolving error, the literals'
+    /// positions are relative to their bindings' contents
+    pub fn literals(&self) -> Vec<(&Literal, Result<U256, Error>)> {
+        let mut literals = vec![];
+        for binding in &self.bindings {
+            if let BindingItem::Exp(exp) = &binding.item {
+                for src in exp.ast() {
+                    for line in &src.lines {
+                        Self::collect_literals(&line.nodes, &mut literals);
+                    }
+                }
+            }
+        }
+        literals
+    }
+
+    /// Compares the structure of this instance with the given one, i.e. the imports, bindings
+    /// and their parse trees and problems' codes, while ignoring all positions, so texts that
+    /// only differ in whitespaces or comments are considered equal
+    pub fn ast_eq_ignoring_positions(&self, other: &RainDocument) -> bool {
+        Self::problems_codes(&self.problems) == Self::problems_codes(&other.problems)
+            && self.imports.len() == other.imports.len()
+            && self
+                .imports
+                .iter()
+                .zip(&other.imports)
+                .all(|(a, b)| Self::imports_eq_ignoring_positions(a, b))
+            && self.bindings.len() == other.bindings.len()
+            && self
+                .bindings
+                .iter()
+                .zip(&other.bindings)
+                .all(|(a, b)| Self::bindings_eq_ignoring_positions(a, b))
+    }
+
+    /// Reports the semantic differences of the given RainDocument compared to this instance, i.e.
+    /// the added, removed and modified bindings (by their names), imports (by their names) and
+    /// namespace items (by their dotted paths), pure position shifts are ignored, useful for
+    /// reviewing changes or blocking semantic changes in CI
+    pub fn diff(&self, other: &RainDocument) -> DocumentDiff {
+        let mut diff = DocumentDiff::default();
+        for binding in &self.bindings {
+            match other.bindings.iter().find(|v| v.name == binding.name) {
+                Some(v) if !Self::bindings_eq_ignoring_positions(binding, v) => {
+                    diff.modified_bindings.push(binding.name.clone())
+                }
+                Some(_) => {}
+                None => diff.removed_bindings.push(binding.name.clone()),
+            }
+        }
+        for binding in &other.bindings {
+            if !self.bindings.iter().any(|v| v.name == binding.name) {
+                diff.added_bindings.push(binding.name.clone());
+            }
+        }
+
+        for imp in &self.imports {
+            match other.imports.iter().find(|v| v.name == imp.name) {
+                Some(v) if !Self::imports_eq_ignoring_positions(imp, v) => {
+                    diff.modified_imports.push(imp.name.clone())
+                }
+                Some(_) => {}
+                None => diff.removed_imports.push(imp.name.clone()),
+            }
+        }
+        for imp in &other.imports {
+            if !self.imports.iter().any(|v| v.name == imp.name) {
+                diff.added_imports.push(imp.name.clone());
+            }
+        }
+
+        let namespace = self.flat_namespace();
+        let other_namespace = other.flat_namespace();
+        for (path, leaf) in &namespace {
+            match other_namespace.get(path) {
+                Some(v)
+                    if leaf.hash != v.hash
+                        || !Self::bindings_eq_ignoring_positions(&leaf.element, &v.element) =>
+                {
+                    diff.modified_namespace.push(path.clone())
+                }
+                Some(_) => {}
+                None => diff.removed_namespace.push(path.clone()),
+            }
+        }
+        for path in other_namespace.keys() {
+            if !namespace.contains_key(path) {
+                diff.added_namespace.push(path.clone());
+            }
+        }
+        diff
+    }
+
+    /// Builds a minimal dotrain text out of this instance's text that contains only the given
+    /// entrypoints and their transitive dependencies (referenced bindings and the imports they
+    /// use) alongside the front matter and the comments that precede the kept bindings, useful
+    /// for producing small self-contained reproductions, unknown entrypoints are ignored
+    pub fn minimize(&self, keep: &[&str]) -> String {
+        let mut kept_bindings = vec![false; self.bindings.len()];
+        let mut kept_imports = vec![false; self.imports.len()];
+        let mut queue: Vec<String> = keep.iter().map(|v| v.to_string()).collect();
+        while let Some(reference) = queue.pop() {
+            let root = reference
+                .trim_start_matches(['\'', '.'])
+                .split('.')
+                .next()
+                .unwrap_or_default();
+            if let Some(i) = self.bindings.iter().position(|b| b.name == root) {
+                if !kept_bindings[i] {
+                    kept_bindings[i] = true;
+                    Self::binding_references(&self.bindings[i], &mut queue);
+                }
+            } else {
+                for (i, imp) in self.imports.iter().enumerate() {
+                    if imp.name == root {
+                        kept_imports[i] = true;
+                    }
+                }
+            }
+        }
+
+        let mut minimized = String::new();
+        let mut cursor = 0;
+        if Self::get_front_matter(&self.text).is_some() {
+            minimized.push_str(self.front_matter());
+            minimized.push_str(FRONTMATTER_SEPARATOR);
+            minimized.push('\n');
+            cursor = self.front_matter_offset + FRONTMATTER_SEPARATOR.len();
+        }
+        // imports and bindings span up to the next binding, so any comments trailing
+        // their content are excluded here and attached to the binding that follows
+        let mut blocks = vec![];
+        let mut imports = vec![];
+        for (imp, is_kept) in self.imports.iter().zip(kept_imports) {
+            let end = self.content_end(imp.position[0], imp.position[1]);
+            if is_kept {
+                imports.push(&self.text[imp.position[0]..end]);
+            }
+            cursor = end;
+        }
+        if !imports.is_empty() {
+            blocks.push(imports.join("\n"));
+        }
+        for (binding, is_kept) in self.bindings.iter().zip(kept_bindings) {
+            let end = self.content_end(binding.position[0], binding.content_position[1]);
+            if is_kept {
+                let mut block: Vec<&str> = self
+                    .comments
+                    .iter()
+                    .filter(|c| c.position[0] >= cursor && c.position[1] < binding.position[0])
+                    .map(|c| c.comment.as_str())
+                    .collect();
+                block.push(&self.text[binding.position[0] - 1..end]);
+                blocks.push(block.join("\n"));
+            }
+            cursor = end;
+        }
+        minimized.push_str(&blocks.join("\n\n"));
+        minimized.push('\n');
+        minimized
+    }
+
+    /// Organizes this instance's imports, i.e. sorts them by their names and then their hashes,
+    /// removes the repeated imports (same name, hash and configuration) and the duplicate
+    /// statements of the imports' configurations and drops the resolved imports that none of the
+    /// bindings reference, unless they re-export or rebind any member as those are not local
+    /// only, returns the range of the current imports block alongside its replacement, comments
+    /// in between the imports are kept at the top of the block, None if there are no imports
+    pub fn organize_imports(&self) -> Option<(Offsets, String)> {
+        let start = self.imports.first()?.position[0];
+        let last = self.imports.last()?;
+        let end = self.content_end(last.position[0], last.position[1]);
+
+        // an import is referenced if any of the bindings' references resolves to one
+        // of its members or if the reference's root segment is the import's name
+        let flat_namespace = self.flat_namespace();
+        let mut references = vec![];
+        for binding in &self.bindings {
+            Self::binding_references(binding, &mut references);
+        }
+        let mut is_used = vec![false; self.imports.len()];
+        for reference in &references {
+            let path = reference.trim_start_matches(['\'', '.']);
+            if let Some(leaf) = flat_namespace.get(path) {
+                if let Some(v) = is_used.get_mut(leaf.import_index as usize) {
+                    *v = true;
+                }
+            }
+            let root = path.split('.').next().unwrap_or_default();
+            for (i, imp) in self.imports.iter().enumerate() {
+                if imp.name == root {
+                    is_used[i] = true;
+                }
+            }
+        }
+
+        let mut indexes: Vec<usize> = (0..self.imports.len()).collect();
+        indexes.sort_by(|a, b| {
+            let (a, b) = (&self.imports[*a], &self.imports[*b]);
+            (&a.name, &a.hash).cmp(&(&b.name, &b.hash))
+        });
+        let mut block: Vec<String> = self
+            .comments
+            .iter()
+            .filter(|c| c.position[0] >= start && c.position[1] <= end)
+            .map(|c| c.comment.clone())
+            .collect();
+        let mut kept: Vec<&Import> = vec![];
+        for i in indexes {
+            let imp = &self.imports[i];
+            if (imp.sequence.is_some() && !is_used[i] && !Self::has_non_local_configuration(imp))
+                || kept.iter().any(|v| {
+                    v.name == imp.name
+                        && v.hash == imp.hash
+                        && !imp.hash.is_empty()
+                        && Self::import_configuration_groups(v)
+                            == Self::import_configuration_groups(imp)
+                })
+            {
+                continue;
+            }
+            kept.push(imp);
+            block.push(Self::organized_import_text(
+                imp,
+                &self.text[imp.position[0]..self.content_end(imp.position[0], imp.position[1])],
+            ));
+        }
+        Some(([start, end], block.join("\n")))
+    }
+
     /// Parses this instance's text
     #[cfg_attr(target_family = "wasm", async_recursion(?Send))]
     #[cfg_attr(not(target_family = "wasm"), async_recursion)]
     pub async fn parse(&mut self, enable_remote: bool, rebinds: Option<Vec<Rebind>>) {
+        self.meta_store.get_or_insert_with(Default::default);
+        self.parse_text(enable_remote, true, rebinds).await;
+    }
+}
+
+impl RainDocument {
+    /// parses this instance's text, optionally skipping the imports resolution
+    async fn parse_text(
+        &mut self,
+        enable_remote: bool,
+        resolve_imports: bool,
+        rebinds: Option<Vec<Rebind>>,
+    ) {
+        self.line_offsets = line_offsets(&self.text);
         if NON_EMPTY_PATTERN.is_match(&self.text) {
-            if let Err(e) = self._parse(enable_remote, rebinds).await {
+            let result = match self.options.max_document_bytes() {
+                // too large documents are not parsed at all
+                Some(max) if self.text.len() > max => {
+                    self.reset_state();
+                    Err(Error::DocumentTooLarge(max))
+                }
+                _ => self._parse(enable_remote, resolve_imports, rebinds).await,
+            };
+            if let Err(e) = result {
                 if let Error::InvalidOverride(err_msg) = e {
                     self.problems.push(
                         ErrorCode::InvalidSuppliedRebindings.to_problem(vec![&err_msg], [0, 0]),
@@ -240,39 +996,48 @@ impl RainDocument {
                 }
             }
         } else {
-            self.error = None;
-            self.imports.clear();
-            self.problems.clear();
-            self.comments.clear();
-            self.bindings.clear();
-            self.namespace.clear();
-            self.known_words = None;
-            self.front_matter_offset = 0;
+            // an empty or whitespace only text results in an empty instance with no problems
+            self.reset_state();
         }
     }
-}
 
-impl RainDocument {
     pub(crate) fn new(
         text: String,
         meta_store: Option<Arc<RwLock<Store>>>,
         import_depth: usize,
-        known_words: Option<AuthoringMeta>,
+        words: Option<AuthoringMeta>,
     ) -> RainDocument {
         RainDocument {
-            meta_store: meta_store.unwrap_or(Arc::new(RwLock::new(Store::default()))),
+            meta_store: Some(meta_store.unwrap_or_default()),
             text,
             front_matter_offset: 0,
             error: None,
             bindings: vec![],
             namespace: std::collections::HashMap::new(),
             imports: vec![],
-            known_words,
+            known_words: words.clone(),
+            words,
             comments: vec![],
             problems: vec![],
             import_depth,
+            options: ParseOptions::default(),
+            cancellation: None,
+            uri: None,
+            line_offsets: vec![],
         }
     }
+
+    /// resets all the parsed items of this instance
+    pub(crate) fn reset_state(&mut self) {
+        self.error = None;
+        self.imports.clear();
+        self.problems.clear();
+        self.comments.clear();
+        self.bindings.clear();
+        self.namespace.clear();
+        self.known_words = None;
+        self.front_matter_offset = 0;
+    }
 }
 
 #[cfg(test)]
@@ -298,7 +1063,8 @@ mod tests {
 
         let text = " \n 99999e99999 \n";
         let result = RainDocument::is_literal(text);
-        assert_eq!(result, Some(("99999e99999".to_owned(), 2, false)));
+        // out of uint256 range
+        assert_eq!(result, Some(("99999e99999".to_owned(), 2, true)));
 
         let text = "\" some\n literal  \nvalue\t\n \"";
         let result = RainDocument::is_literal(text);
@@ -659,84 +1425,410 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_method() -> anyhow::Result<()> {
-        let store = Store::new();
-        let meta_store = Arc::new(RwLock::new(store));
-
+    fn test_binding_map_to_document_method() -> anyhow::Result<()> {
         let text = r"some front matter
 ---
-/** this is test */
-                                                                           
-
 #const-binding 4e18
-#elided-binding ! this elided, rebind before use
 #exp-binding
 _: opcode-1(0xabcd 456);
 ";
-        let rain_document =
-            RainDocument::create(text.to_owned(), Some(meta_store.clone()), None, None);
-        let expected_bindings: Vec<Binding> = vec![
-            Binding {
-                name: "const-binding".to_owned(),
-                name_position: [120, 133],
-                content: "4e18".to_owned(),
-                content_position: [134, 138],
-                position: [120, 139],
-                problems: vec![],
-                item: BindingItem::Literal(LiteralBindingItem {
-                    value: "4e18".to_owned(),
-                }),
-            },
-            Binding {
-                name: "elided-binding".to_owned(),
-                name_position: [140, 154],
-                content: "! this elided, rebind before use".to_owned(),
-                content_position: [155, 187],
-                position: [140, 188],
-                problems: vec![],
-                item: BindingItem::Elided(ElidedBindingItem {
-                    msg: "this elided, rebind before use".to_owned(),
-                }),
-            },
-            Binding {
-                name: "exp-binding".to_owned(),
-                name_position: [189, 200],
-                content: "_: opcode-1(0xabcd 456);".to_owned(),
-                content_position: [201, 225],
-                position: [189, 226],
-                problems: vec![],
-                item: BindingItem::Exp(RainlangDocument::create(
-                    "_: opcode-1(0xabcd 456);".to_owned(),
-                    &HashMap::new(),
-                    None,
-                )),
-            },
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let binding = &rain_document.bindings()[1];
+        let BindingItem::Exp(exp) = &binding.item else {
+            panic!("expected an expression binding");
+        };
+
+        let inner = exp.ast()[0].lines[0].aliases[0].position;
+        let result = binding.map_to_document(inner);
+        let expected = [binding.content_position[0], binding.content_position[0] + 1];
+        assert_eq!(result, expected);
+        assert_eq!(&text[result[0]..result[1]], "_");
+
+        let Node::Opcode(op) = &exp.ast()[0].lines[0].nodes[0] else {
+            panic!("expected an opcode node");
+        };
+        let result = binding.map_to_document(op.inputs[0].position());
+        let expected = [
+            binding.content_position[0] + 12,
+            binding.content_position[0] + 18,
         ];
-        let mut expected_namespace: Namespace = HashMap::new();
-        expected_namespace.insert(
-            expected_bindings[0].name.to_owned(),
-            NamespaceItem::Leaf(NamespaceLeaf {
-                hash: String::new(),
-                import_index: -1,
-                element: expected_bindings[0].clone(),
-            }),
-        );
-        expected_namespace.insert(
-            expected_bindings[1].name.to_owned(),
-            NamespaceItem::Leaf(NamespaceLeaf {
-                hash: String::new(),
-                import_index: -1,
-                element: expected_bindings[1].clone(),
-            }),
-        );
-        expected_namespace.insert(
-            expected_bindings[2].name.to_owned(),
-            NamespaceItem::Leaf(NamespaceLeaf {
-                hash: String::new(),
-                import_index: -1,
-                element: expected_bindings[2].clone(),
-            }),
-        );
+        assert_eq!(result, expected);
+        assert_eq!(&text[result[0]..result[1]], "0xabcd");
+
+        let result = binding.map_to_document([0, 0]);
+        let expected = [binding.content_position[0], binding.content_position[0]];
+        assert_eq!(result, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_import_hash() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#some-value 4e18", "some.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\n@ns1 {hash}\n@ns2 {hash}\n#binding\n_: 1;\n");
+        let rain_document = RainDocument::create(text, Some(meta_store.clone()), None, None);
+        let second_import = &rain_document.imports()[1];
+        let expected_problems =
+            vec![ErrorCode::DuplicateImportHash.to_problem(vec!["ns1"], second_import.position)];
+        assert_eq!(rain_document.problems(), &expected_problems);
+        assert!(rain_document.problems()[0].code.is_warning());
+        assert!(rain_document.compose(&["binding"]).is_ok());
+
+        let text = format!("---\n@ns1 {hash}\n@ns1 {hash}\n#binding\n_: 1;\n");
+        let rain_document = RainDocument::create(text, Some(meta_store), None, None);
+        let second_import = &rain_document.imports()[1];
+        assert!(rain_document
+            .problems()
+            .contains(&ErrorCode::DuplicateImport.to_problem(vec![], second_import.hash_position)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_reexport() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) =
+            store.set_dotrain("---\n#value 4e18\n#other 2", "some.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let (hash_bytes, _) = store.set_dotrain("---\n#value 3", "another.rain", false)?;
+        let another_hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\n@lib {hash} value ^\n#binding\n_: value lib.other;\n");
+        let rain_document = RainDocument::create(text, Some(meta_store.clone()), None, None);
+        assert!(rain_document.all_problems().is_empty());
+        assert!(rain_document.namespace().get("value").unwrap().is_leaf());
+        assert!(!rain_document.namespace().get("lib").unwrap().is_leaf());
+        assert_eq!(
+            rain_document.compose(&["binding"])?,
+            "/* 0. binding */ \n_: 4e18 2;"
+        );
+
+        // renamed before being re-exported
+        let text = format!("---\n@lib {hash} 'value renamed renamed ^\n#binding\n_: renamed;\n");
+        let rain_document = RainDocument::create(text, Some(meta_store.clone()), None, None);
+        assert!(rain_document.all_problems().is_empty());
+        assert!(rain_document.namespace().get("value").is_none());
+        assert!(rain_document.namespace().get("renamed").unwrap().is_leaf());
+
+        // collision with another re-export
+        let text = format!("---\n@lib {hash} value ^\n@another {another_hash} value ^\n");
+        let rain_document =
+            RainDocument::create(text.clone(), Some(meta_store.clone()), None, None);
+        let start = text.rfind("value ^").unwrap();
+        assert_eq!(
+            rain_document.problems(),
+            &vec![ErrorCode::OccupiedNamespace.to_problem(vec![], [start, start + 7])]
+        );
+
+        // undefined member
+        let text = format!("---\n@lib {hash} missing ^\n");
+        let rain_document = RainDocument::create(text.clone(), Some(meta_store), None, None);
+        let start = text.find("missing").unwrap();
+        assert_eq!(
+            rain_document.problems(),
+            &vec![ErrorCode::UndefinedIdentifier.to_problem(vec!["missing"], [start, start + 7])]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_u256_overflow_literal_binding() -> anyhow::Result<()> {
+        let text = "---\n#big 1e100\n#ok 1e18";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let start = text.find("1e100").unwrap();
+        let expected = vec![ErrorCode::OutOfRangeValue.to_problem(
+            vec!["1e100 is not within uint256 range [0, 2^256 - 1]"],
+            [start, start + 5],
+        )];
+        assert_eq!(rain_document.problems(), &expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_meta_bytes() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#some-value 4e18", "some.rain", false)?;
+        let meta_bytes = store.get_meta(&hash_bytes).unwrap().clone();
+        let hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\n@ns {hash}\n@other 0xabcd\n#binding\n_: 1;\n");
+        let rain_document = RainDocument::create(text, Some(meta_store), None, None);
+        assert_eq!(
+            rain_document.import_meta_bytes(0),
+            Some(meta_bytes.as_slice())
+        );
+        assert_eq!(rain_document.import_meta_bytes(1), None);
+        assert_eq!(rain_document.import_meta_bytes(2), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_empty_document() -> anyhow::Result<()> {
+        for text in ["", "\n", "  \n\t \n "] {
+            let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+            assert!(rain_document.problems().is_empty());
+            assert!(rain_document.comments().is_empty());
+            assert!(rain_document.imports().is_empty());
+            assert!(rain_document.bindings().is_empty());
+            assert!(rain_document.namespace().is_empty());
+            assert!(rain_document.runtime_error().is_none());
+            assert_eq!(rain_document.front_matter_offset(), 0);
+            assert_eq!(rain_document.front_matter(), "");
+            assert_eq!(rain_document.body(), text);
+        }
+
+        // updating a parsed instance with an empty text should clear it
+        let mut rain_document =
+            RainDocument::create("---\n#binding 1\n".to_owned(), None, None, None);
+        assert_eq!(rain_document.bindings().len(), 1);
+        rain_document.update(String::new(), None);
+        assert!(rain_document.bindings().is_empty());
+        assert!(rain_document.namespace().is_empty());
+        assert!(rain_document.all_problems().is_empty());
+
+        let text = "---\n/* some comment */\n/** another comment */\n";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        assert!(rain_document.problems().is_empty());
+        assert!(rain_document.imports().is_empty());
+        assert!(rain_document.bindings().is_empty());
+        assert!(rain_document.namespace().is_empty());
+        let expected_comments = vec![
+            Comment {
+                comment: "/* some comment */".to_owned(),
+                position: [4, 22],
+            },
+            Comment {
+                comment: "/** another comment */".to_owned(),
+                position: [23, 45],
+            },
+        ];
+        assert_eq!(rain_document.comments(), &expected_comments);
+
+        let text = "/* some comment */";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let expected_problems = vec![ErrorCode::NoFrontMatterSplitter.to_problem(vec![], [0, 0])];
+        assert_eq!(rain_document.problems(), &expected_problems);
+        assert!(rain_document.bindings().is_empty());
+        assert!(rain_document.namespace().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_used_opcodes() -> anyhow::Result<()> {
+        let text = "---\n#a 1\n#exp1\n_: add(mul(1 2) add(3 4));\n#exp2\n_ _: mul(a 2) sub(1 1);";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let expected = HashMap::from([
+            ("add".to_owned(), 2),
+            ("mul".to_owned(), 2),
+            ("sub".to_owned(), 1),
+        ]);
+        assert_eq!(rain_document.used_opcodes(), expected);
+
+        let rain_document = RainDocument::create("---\n#a 1".to_owned(), None, None, None);
+        assert!(rain_document.used_opcodes().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_syntax_only() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#some-value 4e18", "some.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\n@ns {hash}\n@0xabc\n#binding\n_: 0x12;\n");
+        let rain_document = RainDocument::builder(text.clone()).build_syntax_only();
+        let expected_imports = vec![
+            Import {
+                name: "ns".to_owned(),
+                name_position: [5, 7],
+                hash: hash.clone(),
+                hash_position: [8, 74],
+                position: [4, 75],
+                problems: vec![],
+                configuration: None,
+                sequence: None,
+            },
+            Import {
+                name: ".".to_owned(),
+                name_position: [76, 81],
+                hash: "0xabc".to_owned(),
+                hash_position: [76, 81],
+                position: [75, 82],
+                problems: vec![ErrorCode::OddLenHex.to_problem(vec![], [76, 81])],
+                configuration: None,
+                sequence: None,
+            },
+        ];
+        assert_eq!(rain_document.imports(), &expected_imports);
+        let expected_problems = vec![ErrorCode::OddLenHex.to_problem(vec![], [76, 81])];
+        assert_eq!(rain_document.problems(), &expected_problems);
+        assert_eq!(rain_document.bindings()[0].problems, vec![]);
+        assert!(matches!(
+            rain_document.bindings()[0].item,
+            BindingItem::Exp(_)
+        ));
+
+        // no store is allocated, the uri is kept
+        assert!(rain_document.meta_store.is_none());
+        assert!(rain_document.store().read().unwrap().cache().is_empty());
+        let rain_document = RainDocument::builder(text.clone())
+            .with_uri("file:///a.rain".to_owned())
+            .build_syntax_only();
+        assert_eq!(rain_document.uri(), Some("file:///a.rain"));
+
+        // same text is resolved by regular parsing
+        let rain_document = RainDocument::create(text, Some(meta_store), None, None);
+        assert!(rain_document.imports()[0].sequence.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancellation_with_pending_search() -> anyhow::Result<()> {
+        use std::io::Write;
+
+        // a local subgraph that sets the flag once the search request reaches it, i.e. while
+        // the search is pending, and then fails that request
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let subgraph = format!("http://{}", listener.local_addr()?);
+        let cancellation = Arc::new(AtomicBool::new(false));
+        let flag = cancellation.clone();
+        let server = std::thread::spawn(move || -> std::io::Result<std::net::TcpListener> {
+            let (mut stream, _) = listener.accept()?;
+            flag.store(true, Ordering::SeqCst);
+            stream.write_all(
+                b"HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            )?;
+            Ok(listener)
+        });
+
+        let hash = "0x6518ec1930d8846b093dcff41a6ee6f6352c72b82e48584cce741a9e8a6d6184";
+        let store = Store::create(
+            &vec![subgraph],
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            false,
+        );
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let mut rain_document = runtime.block_on(
+            RainDocument::builder(format!("---\n@lib {hash}\n#exp\n_: 1;"))
+                .with_meta_store(Some(Arc::new(RwLock::new(store))))
+                .with_cancellation(cancellation)
+                .build_async(),
+        );
+        let listener = server.join().unwrap()?;
+
+        // the pending search was awaited and the parsing completed with a partial result
+        assert!(rain_document.is_cancelled());
+        assert_eq!(rain_document.problems().len(), 1);
+        assert_eq!(rain_document.problems()[0].code, ErrorCode::UndefinedImport);
+
+        // no new search is started after the flag is set
+        runtime.block_on(rain_document.update_async(rain_document.text().to_owned(), None));
+        assert_eq!(rain_document.problems()[0].code, ErrorCode::UndefinedImport);
+        listener.set_nonblocking(true)?;
+        assert!(listener
+            .accept()
+            .is_err_and(|e| e.kind() == std::io::ErrorKind::WouldBlock));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_method() -> anyhow::Result<()> {
+        let store = Store::new();
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = r"some front matter
+---
+/** this is test */
+                                                                           
+
+#const-binding 4e18
+#elided-binding ! this elided, rebind before use
+#exp-binding
+_: opcode-1(0xabcd 456);
+";
+        let rain_document =
+            RainDocument::create(text.to_owned(), Some(meta_store.clone()), None, None);
+        let expected_bindings: Vec<Binding> = vec![
+            Binding {
+                name: "const-binding".to_owned(),
+                name_position: [120, 133],
+                content: "4e18".to_owned(),
+                content_position: [134, 138],
+                position: [120, 139],
+                problems: vec![],
+                item: BindingItem::Literal(LiteralBindingItem {
+                    value: "4e18".to_owned(),
+                }),
+            },
+            Binding {
+                name: "elided-binding".to_owned(),
+                name_position: [140, 154],
+                content: "! this elided, rebind before use".to_owned(),
+                content_position: [155, 187],
+                position: [140, 188],
+                problems: vec![],
+                item: BindingItem::Elided(ElidedBindingItem {
+                    msg: "this elided, rebind before use".to_owned(),
+                }),
+            },
+            Binding {
+                name: "exp-binding".to_owned(),
+                name_position: [189, 200],
+                content: "_: opcode-1(0xabcd 456);".to_owned(),
+                content_position: [201, 225],
+                position: [189, 226],
+                problems: vec![],
+                item: BindingItem::Exp(RainlangDocument::create(
+                    "_: opcode-1(0xabcd 456);".to_owned(),
+                    &HashMap::new(),
+                    None,
+                    &ParseOptions::default(),
+                )),
+            },
+        ];
+        let mut expected_namespace: Namespace = HashMap::new();
+        expected_namespace.insert(
+            expected_bindings[0].name.to_owned(),
+            NamespaceItem::Leaf(NamespaceLeaf {
+                hash: String::new(),
+                import_index: -1,
+                element: expected_bindings[0].clone(),
+            }),
+        );
+        expected_namespace.insert(
+            expected_bindings[1].name.to_owned(),
+            NamespaceItem::Leaf(NamespaceLeaf {
+                hash: String::new(),
+                import_index: -1,
+                element: expected_bindings[1].clone(),
+            }),
+        );
+        expected_namespace.insert(
+            expected_bindings[2].name.to_owned(),
+            NamespaceItem::Leaf(NamespaceLeaf {
+                hash: String::new(),
+                import_index: -1,
+                element: expected_bindings[2].clone(),
+            }),
+        );
 
         let expected_rain_document = RainDocument {
             text: text.to_owned(),
@@ -751,8 +1843,13 @@ _: opcode-1(0xabcd 456);
             problems: vec![],
             import_depth: 0,
             namespace: expected_namespace,
-            meta_store: meta_store.clone(),
+            meta_store: Some(meta_store.clone()),
             known_words: None,
+            words: None,
+            options: ParseOptions::default(),
+            cancellation: None,
+            uri: None,
+            line_offsets: vec![],
         };
         assert_eq!(rain_document, expected_rain_document);
 
@@ -794,11 +1891,1325 @@ _: opcode-1(0xabcd 456);
             problems: vec![ErrorCode::DuplicateIdentifier.to_problem(vec![], [37, 44])],
             import_depth: 0,
             namespace: expected_namespace,
-            meta_store,
+            meta_store: Some(meta_store),
             known_words: None,
+            words: None,
+            options: ParseOptions::default(),
+            cancellation: None,
+            uri: None,
+            line_offsets: vec![],
         };
         assert_eq!(rain_document, expected_rain_document);
 
         Ok(())
     }
+
+    #[test]
+    fn test_builder_with_options() -> anyhow::Result<()> {
+        let text = "---\n#$amount 12\n#calc\n_$x: $amount,\n_: _$x;";
+
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let invalids: Vec<&str> = rain_document
+            .all_problems()
+            .iter()
+            .filter(|p| p.code == ErrorCode::InvalidWordPattern)
+            .map(|p| &text[p.position[0]..p.position[1]])
+            .collect();
+        assert_eq!(invalids, vec!["$amount", "_$x", "$amount", "_$x"]);
+
+        let word_pattern = WordPattern::new(r"[a-z$][0-9a-z$-]*")?;
+        let rain_document = RainDocument::builder(text.to_owned())
+            .with_options(ParseOptions::default().with_word_pattern(word_pattern.clone()))
+            .build();
+        assert!(rain_document.all_problems().is_empty());
+        assert_eq!(rain_document.word_pattern(), &word_pattern);
+        assert_eq!(
+            rain_document.compose(&["calc"])?,
+            "/* 0. calc */ \n_$x: 12,\n_: _$x;"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ast_eq_ignoring_positions() -> anyhow::Result<()> {
+        let text = "---\n#value 12\n#elided ! some msg\n#exp\n_ _: add(value 2) sub<1>(3),\n_: _;";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+
+        let reformatted = "\n---\n\n#value   12\n/* comment */\n#elided ! some msg\n\n#exp\n  _ _ : add( value 2 )   sub<1>(3),\n\n  _: _ ;";
+        let other = RainDocument::create(reformatted.to_owned(), None, None, None);
+        assert_ne!(rain_document, other);
+        assert!(rain_document.ast_eq_ignoring_positions(&other));
+        assert!(other.ast_eq_ignoring_positions(&rain_document));
+
+        let different =
+            "---\n#value 12\n#elided ! some msg\n#exp\n_ _: add(value 3) sub<1>(3),\n_: _;";
+        let other = RainDocument::create(different.to_owned(), None, None, None);
+        assert!(!rain_document.ast_eq_ignoring_positions(&other));
+
+        let different =
+            "---\n#value 12\n#elided ! other msg\n#exp\n_ _: add(value 2) sub<1>(3),\n_: _;";
+        let other = RainDocument::create(different.to_owned(), None, None, None);
+        assert!(!rain_document.ast_eq_ignoring_positions(&other));
+
+        let different = "---\n#value 12\n#elided ! some msg\n#exp\n_ _: add(value 2) sub<1>(3);";
+        let other = RainDocument::create(different.to_owned(), None, None, None);
+        assert!(!rain_document.ast_eq_ignoring_positions(&other));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_configuration_problems() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#value 4e18", "some.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\n@lib {hash} valeu 12\n@other {hash} value 12\n@lib2 {hash}\n");
+        let rain_document = RainDocument::create(text.clone(), Some(meta_store), None, None);
+        let typo_position = text.find("valeu").unwrap();
+        let expected = ErrorCode::UndefinedIdentifier
+            .to_problem(vec!["valeu"], [typo_position, typo_position + 5]);
+
+        let result = rain_document.import_configuration_problems();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0], std::slice::from_ref(&expected));
+        assert!(result[1].is_empty());
+        assert!(result[2].is_empty());
+        assert!(rain_document.problems().contains(&expected));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimize() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#value 4e18", "some.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!(
+            "some front matter\n---\n@lib {hash}\n@unused {hash}\n\n/* the value */\n#value 12\n\n/* unused */\n#unused-binding 3\n\n#elided ! elided msg\n\n/** calculates */\n#calc\n_: add(value lib.value),\n_: call<'other>();\n\n#other\n/* inner */ _: 1;\n"
+        );
+        let rain_document = RainDocument::create(text, Some(meta_store.clone()), None, None);
+
+        let result = rain_document.minimize(&["calc"]);
+        let expected = format!(
+            "some front matter\n---\n@lib {hash}\n\n/* the value */\n#value 12\n\n/** calculates */\n#calc\n_: add(value lib.value),\n_: call<'other>();\n\n#other\n/* inner */ _: 1;\n"
+        );
+        assert_eq!(result, expected);
+
+        // minimized text is self-contained and composes the same
+        let minimized = RainDocument::create(result, Some(meta_store.clone()), None, None);
+        assert_eq!(
+            minimized.compose(&["calc"]),
+            rain_document.compose(&["calc"])
+        );
+
+        let result = rain_document.minimize(&["elided", "unknown"]);
+        assert_eq!(result, "some front matter\n---\n#elided ! elided msg\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_self_import() -> anyhow::Result<()> {
+        // the store maps the uri to the hash of an earlier revision of the document
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#value 4e18", "file:///self.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\n@lib {hash}\n#exp\n_: lib.value;");
+        let rain_document = RainDocument::builder(text.clone())
+            .with_meta_store(Some(meta_store.clone()))
+            .with_uri("file:///self.rain".to_owned())
+            .build();
+        assert_eq!(
+            rain_document.imports[0].problems,
+            vec![ErrorCode::InvalidSelfImport.to_problem(vec![], [9, 75])]
+        );
+        assert!(rain_document.imports[0].sequence.is_none());
+
+        // the same text at another uri resolves the import
+        let rain_document = RainDocument::builder(text)
+            .with_meta_store(Some(meta_store))
+            .with_uri("file:///other.rain".to_owned())
+            .build();
+        assert!(rain_document.imports[0].problems.is_empty());
+        assert!(rain_document.problems.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_opcode_name_collisions() -> anyhow::Result<()> {
+        let words = rain_metadata::types::authoring::v1::AuthoringMeta(
+            ["add", "sub", "mul"]
+                .iter()
+                .map(|v| rain_metadata::types::authoring::v1::AuthoringMetaItem {
+                    word: v.to_string(),
+                    operand_parser_offset: 0,
+                    description: String::new(),
+                })
+                .collect(),
+        );
+        let text = "---\n#add 1\n#sub 2\n#value 3\n#mul\n_: 1;\n#exp\n_: add<sub>(add value);";
+        let rain_document = RainDocument::create(text.to_owned(), None, Some(words), None);
+        let result: Vec<_> = rain_document
+            .bindings
+            .iter()
+            .map(|v| (v.name.as_str(), v.problems.clone()))
+            .collect();
+        let expected = vec![
+            (
+                "add",
+                vec![ErrorCode::CollidingOpcodeName.to_problem(vec!["add"], [5, 8])],
+            ),
+            // only used as an operand arg
+            ("sub", vec![]),
+            ("value", vec![]),
+            (
+                "mul",
+                vec![ErrorCode::CollidingOpcodeName.to_problem(vec!["mul"], [28, 31])],
+            ),
+            ("exp", vec![]),
+        ];
+        assert_eq!(result, expected);
+        assert!(rain_document.compose(&["mul"]).is_ok());
+
+        // nothing is reported without known words
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        assert!(rain_document.all_problems().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_operand_args_schema() -> anyhow::Result<()> {
+        use rain_metadata::types::authoring::v1::AuthoringMetaItem;
+        let word = |word: &str, operand_parser_offset: u8| AuthoringMetaItem {
+            word: word.to_owned(),
+            operand_parser_offset,
+            description: String::new(),
+        };
+        let words = AuthoringMeta(vec![
+            word("no-args", 0x00),
+            word("two-bytes", 0x20),
+            word("byte-bits", 0x40),
+            word("unknown", 0xff),
+        ]);
+        let text = "---\n#exp\n_: no-args<1>(),\n_: two-bytes(),\n_: two-bytes<1>(),\n_: byte-bits<1 0 1 1>(),\n_: unknown<1 2 3 4 5>(),\n_: two-bytes<1 2>() byte-bits<1>() no-args();";
+        let rain_document = RainDocument::builder(text.to_owned())
+            .with_words(Some(words))
+            .build();
+        let no_args = text.find("<1>").unwrap();
+        let two_bytes = text.find("two-bytes").unwrap();
+        let one_arg = text.find("two-bytes<1>").unwrap() + 9;
+        let four_args = text.find("<1 0 1 1>").unwrap();
+        let expected_problems = vec![
+            ErrorCode::UnexpectedOperandArgs.to_problem(vec!["no-args"], [no_args, no_args + 3]),
+            ErrorCode::ExpectedOperandArgs
+                .to_problem(vec!["first-byte second-byte"], [two_bytes, two_bytes + 9]),
+            ErrorCode::OutOfRangeOperandArgs
+                .to_problem(vec!["at least 2", "1"], [one_arg, one_arg + 3]),
+            ErrorCode::OutOfRangeOperandArgs
+                .to_problem(vec!["at most 3", "4"], [four_args, four_args + 9]),
+        ];
+        assert_eq!(rain_document.bindings[0].problems, expected_problems);
+        assert_eq!(
+            rain_document.bindings[0].problems[1].msg,
+            "expected operand args: <first-byte second-byte>"
+        );
+        assert!(ErrorCode::UnexpectedOperandArgs.is_warning());
+        assert!(!ErrorCode::ExpectedOperandArgs.is_warning());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_given_words() -> anyhow::Result<()> {
+        let words = rain_metadata::types::authoring::v1::AuthoringMeta(vec![
+            rain_metadata::types::authoring::v1::AuthoringMetaItem {
+                word: "add".to_owned(),
+                operand_parser_offset: 0,
+                description: "adds the inputs".to_owned(),
+            },
+        ]);
+        let options = ParseOptions::default()
+            .with_opcode_aliases(&HashMap::from([("minus".to_owned(), "sub".to_owned())]))?;
+        let text = "---\n#exp\n_: add(1 2),\n_: minus(1 2),\na b: 1;".to_owned();
+        let minus = text.find("minus").unwrap();
+        let lhs = text.find("a b").unwrap();
+        let expected_problems = vec![
+            ErrorCode::UndefinedOpcode.to_problem(vec!["minus", "sub"], [minus, minus + 5]),
+            ErrorCode::MismatchLHS.to_problem(vec!["1", "2"], [lhs, lhs + 3]),
+        ];
+
+        // the opcodes and the lhs/rhs counts are validated against the given words
+        let mut rain_document = RainDocument::builder(text.clone())
+            .with_words(Some(words.clone()))
+            .with_options(options.clone())
+            .build();
+        assert_eq!(rain_document.known_words(), &Some(words.clone()));
+        assert_eq!(rain_document.bindings[0].problems, expected_problems);
+        let BindingItem::Exp(exp) = &rain_document.bindings[0].item else {
+            panic!("expected expression binding");
+        };
+        let Node::Opcode(op) = &exp.ast[0].lines[0].nodes[0] else {
+            panic!("expected opcode node");
+        };
+        assert_eq!(op.opcode.description, "adds the inputs");
+        assert!(rain_document.compose(&["exp"]).is_err());
+
+        // the given words are kept across updates
+        rain_document.update(text.clone(), None);
+        assert_eq!(rain_document.known_words(), &Some(words));
+        assert_eq!(rain_document.bindings[0].problems, expected_problems);
+
+        // nothing is validated without words
+        let rain_document = RainDocument::builder(text).with_options(options).build();
+        assert_eq!(rain_document.known_words(), &None);
+        assert!(rain_document.all_problems().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_flat_namespace() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (lib_hash, _) = store.set_dotrain("---\n#value 4e18\n#other 1", "lib.rain", false)?;
+        let lib_hash = alloy_primitives::hex::encode_prefixed(lib_hash);
+        let (hash, _) = store.set_dotrain(
+            &format!("---\n@inner {lib_hash}\n#x 2"),
+            "outer.rain",
+            false,
+        )?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\n@lib {lib_hash}\n@outer {hash}\n#exp\n_: lib.value;");
+        let rain_document = RainDocument::create(text, Some(meta_store), None, None);
+        let result = rain_document.flat_namespace();
+        assert_eq!(
+            result.keys().collect::<Vec<_>>(),
+            vec![
+                "exp",
+                "lib.other",
+                "lib.value",
+                "outer.inner.other",
+                "outer.inner.value",
+                "outer.x"
+            ]
+        );
+        assert_eq!(result["exp"].import_index, -1);
+        assert_eq!(result["lib.value"].hash, lib_hash);
+        assert_eq!(result["lib.value"].import_index, 0);
+        assert_eq!(result["lib.value"].element.name, "value");
+        assert_eq!(result["outer.x"].import_index, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_organize_imports() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let mut hashes = vec![];
+        for (i, text) in ["---\n#value 1", "---\n#other 2", "---\n#unused 3"]
+            .iter()
+            .enumerate()
+        {
+            let (hash, _) = store.set_dotrain(text, &format!("{i}.rain"), false)?;
+            hashes.push(alloy_primitives::hex::encode_prefixed(hash));
+        }
+        let (a, b, c) = (&hashes[0], &hashes[1], &hashes[2]);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!(
+            "---\n@zlib {a}\n/* comment */\n@alib {b} other 5 other 5\n@unused {c}\n@zlib {a}\n\n#exp\n_ _: zlib.value alib.other;"
+        );
+        let rain_document =
+            RainDocument::create(text.clone(), Some(meta_store.clone()), None, None);
+        let (range, organized) = rain_document.organize_imports().unwrap();
+        assert_eq!(range, [4, text.find("\n\n#exp").unwrap()]);
+        assert_eq!(
+            organized,
+            format!("/* comment */\n@alib {b} other 5\n@zlib {a}")
+        );
+
+        // unreferenced imports that re-export or rebind members are kept, and repeated imports
+        // are only removed if their configurations are the same too
+        let text = format!(
+            "---\n@zlib {a}\n@rebound {b} other 6\n@reexport {c} unused ^\n@zlib {a} value 2\n@zlib {a}\n\n#exp\n_: zlib.value;"
+        );
+        let rain_document = RainDocument::create(text, Some(meta_store), None, None);
+        let (_, organized) = rain_document.organize_imports().unwrap();
+        assert_eq!(
+            organized,
+            format!("@rebound {b} other 6\n@reexport {c} unused ^\n@zlib {a}\n@zlib {a} value 2")
+        );
+
+        let rain_document = RainDocument::create("---\n#exp\n_: 1;".to_owned(), None, None, None);
+        assert!(rain_document.organize_imports().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_bindings_recovery() -> anyhow::Result<()> {
+        let text = "---\n#a\n_: /* not ended;\n#b\n_: add(1 2;\n#c\n_: 1;";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        assert_eq!(
+            rain_document.comments,
+            vec![Comment {
+                comment: "/* not ended;".to_owned(),
+                position: [10, 23],
+            }]
+        );
+        let names: Vec<_> = rain_document.bindings.iter().map(|v| &v.name).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+
+        // both broken bindings report their own errors
+        let codes = |i: usize| -> Vec<ErrorCode> {
+            rain_document.bindings[i]
+                .problems
+                .iter()
+                .map(|v| v.code)
+                .collect()
+        };
+        assert!(codes(0).contains(&ErrorCode::UnexpectedEndOfComment));
+        assert_eq!(codes(1), vec![ErrorCode::ExpectedClosingParen]);
+        assert!(codes(2).is_empty());
+        assert_eq!(
+            rain_document.problems,
+            vec![ErrorCode::UnexpectedEndOfComment.to_problem(vec![], [10, 23])]
+        );
+        assert!(matches!(
+            &rain_document.bindings[2].item,
+            BindingItem::Exp(exp) if exp.ast.len() == 1
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_preview_rebindings() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash, _) =
+            store.set_dotrain("---\n#value 4e18\n#other 1\n#extra 2", "lib.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!(
+            "---\n@lib {hash} value 12 'other renamed extra ! missing 3\n#exp\n_: lib.value;"
+        );
+        let missing_position = text.find("missing").unwrap();
+        let rain_document = RainDocument::create(text, Some(meta_store), None, None);
+        let (pairs, problems) = rain_document.preview_rebindings(0);
+        assert_eq!(
+            pairs,
+            vec![
+                ("value".to_owned(), "12".to_owned()),
+                ("other".to_owned(), "renamed".to_owned()),
+                ("extra".to_owned(), "!".to_owned()),
+                ("missing".to_owned(), "3".to_owned()),
+            ]
+        );
+        assert_eq!(
+            problems,
+            vec![ErrorCode::UndefinedIdentifier
+                .to_problem(vec!["missing"], [missing_position, missing_position + 7])]
+        );
+
+        // the document itself is not affected by the preview
+        assert_eq!(
+            rain_document.imports()[0]
+                .configuration
+                .as_ref()
+                .unwrap()
+                .groups
+                .len(),
+            4
+        );
+        assert_eq!(rain_document.preview_rebindings(1), (vec![], vec![]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash1, _) = store.set_dotrain("---\n#value 1", "lib1.rain", false)?;
+        let hash1 = alloy_primitives::hex::encode_prefixed(hash1);
+        let (hash2, _) = store.set_dotrain("---\n#value 2\n#other 3", "lib2.rain", false)?;
+        let hash2 = alloy_primitives::hex::encode_prefixed(hash2);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\n@lib {hash1}\n@old {hash1}\n#a 1\n#b\n_: add(a 2);\n#c 3");
+        let rain_document = RainDocument::create(text, Some(meta_store.clone()), None, None);
+
+        // only positions are shifted
+        let text = format!(
+            "---\n\n@lib   {hash1}\n@old {hash1}\n\n#a 1\n/* comment */\n#b\n_:   add(a 2);\n#c 3"
+        );
+        let shifted = RainDocument::create(text, Some(meta_store.clone()), None, None);
+        assert!(rain_document.diff(&shifted).is_empty());
+
+        let text = format!("---\n@lib {hash2}\n@new {hash1}\n#a 1\n#b\n_: add(a 3);\n#d 4");
+        let other = RainDocument::create(text, Some(meta_store), None, None);
+        let result = rain_document.diff(&other);
+        let expected = DocumentDiff {
+            added_bindings: vec!["d".to_owned()],
+            removed_bindings: vec!["c".to_owned()],
+            modified_bindings: vec!["b".to_owned()],
+            added_imports: vec!["new".to_owned()],
+            removed_imports: vec!["old".to_owned()],
+            modified_imports: vec!["lib".to_owned()],
+            added_namespace: vec![
+                "d".to_owned(),
+                "lib.other".to_owned(),
+                "new.value".to_owned(),
+            ],
+            removed_namespace: vec!["c".to_owned(), "old.value".to_owned()],
+            modified_namespace: vec!["b".to_owned(), "lib.value".to_owned()],
+        };
+        assert_eq!(result, expected);
+        assert_eq!(
+            other.diff(&rain_document).added_bindings,
+            vec!["c".to_owned()]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dependency_manifest() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (lib_hash, _) = store.set_dotrain("---\n#value 1", "lib.rain", false)?;
+        let lib_hash = alloy_primitives::hex::encode_prefixed(lib_hash);
+        let (hash, _) = store.set_dotrain(
+            &format!("---\n@inner {lib_hash}\n#x 2"),
+            "outer.rain",
+            false,
+        )?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let unknown_hash = format!("0x{}", "ab".repeat(32));
+        let text = format!("---\n@outer {hash}\n@lib {lib_hash}\n@unknown {unknown_hash}\n#a 1");
+        let rain_document = RainDocument::create(text, Some(meta_store), None, None);
+        let result = rain_document.dependency_manifest();
+        let expected = vec![
+            DependencyEntry {
+                name: "outer".to_owned(),
+                hash: hash.clone(),
+                magics: vec!["dotrain-v1".to_owned()],
+                source: Some(MetaSource::Cache),
+            },
+            DependencyEntry {
+                name: "inner".to_owned(),
+                hash: lib_hash.clone(),
+                magics: vec!["dotrain-v1".to_owned()],
+                source: Some(MetaSource::Cache),
+            },
+            DependencyEntry {
+                name: "unknown".to_owned(),
+                hash: unknown_hash.clone(),
+                magics: vec![],
+                source: None,
+            },
+        ];
+        assert_eq!(result, expected);
+
+        let json = serde_json::to_string(&result[2])?;
+        assert_eq!(
+            json,
+            format!(r#"{{"name":"unknown","hash":"{unknown_hash}","magics":[]}}"#)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_origin_of() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (lib_hash, _) = store.set_dotrain("---\n#value 1", "lib.rain", false)?;
+        let lib_hash = alloy_primitives::hex::encode_prefixed(lib_hash);
+        let (hash, _) = store.set_dotrain(
+            &format!("---\n@inner {lib_hash}\n#x 2"),
+            "outer.rain",
+            false,
+        )?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\n@lib {lib_hash}\n@outer {hash}\n#exp\n_: lib.value;");
+        let rain_document = RainDocument::create(text, Some(meta_store), None, None);
+        assert_eq!(rain_document.origin_of("lib.value"), Some(0));
+        assert_eq!(rain_document.origin_of(".lib.value"), Some(0));
+        assert_eq!(rain_document.origin_of("outer.x"), Some(1));
+        assert_eq!(rain_document.origin_of("outer.inner.value"), Some(1));
+        assert_eq!(rain_document.origin_of("outer.inner"), Some(1));
+        assert_eq!(rain_document.origin_of("lib"), Some(0));
+        assert_eq!(rain_document.origin_of("exp"), None);
+        assert_eq!(rain_document.origin_of("missing"), None);
+        assert_eq!(rain_document.origin_of("li"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_comment_styles() -> anyhow::Result<()> {
+        let text = "---\n// a note\n#a\n_: 1; // trailing\n#b\n_: /* open;\n#c\n_: 2;";
+        let options = ParseOptions::default().with_comment_styles(vec![
+            CommentStyle::Block {
+                open: "/*".to_owned(),
+                close: "*/".to_owned(),
+            },
+            CommentStyle::Line {
+                prefix: "//".to_owned(),
+            },
+        ])?;
+        let rain_document = RainDocument::builder(text.to_owned())
+            .with_options(options)
+            .build();
+        let expected_comments = vec![
+            Comment {
+                comment: "// a note".to_owned(),
+                position: [4, 13],
+            },
+            Comment {
+                comment: "// trailing".to_owned(),
+                position: [23, 34],
+            },
+            Comment {
+                comment: "/* open;".to_owned(),
+                position: [41, 49],
+            },
+        ];
+        assert_eq!(rain_document.comments, expected_comments);
+        assert_eq!(
+            rain_document.problems,
+            vec![ErrorCode::UnexpectedEndOfComment.to_problem(vec![], [41, 49])]
+        );
+        let names: Vec<_> = rain_document.bindings.iter().map(|v| &v.name).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+        assert!(rain_document.bindings[0].problems.is_empty());
+        assert!(rain_document.bindings[2].problems.is_empty());
+
+        // line comments are not recognized by default
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        assert!(rain_document
+            .comments
+            .iter()
+            .all(|v| !v.comment.starts_with("//")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_text() -> anyhow::Result<()> {
+        let text = "---\n#a\n_: \"é\";";
+        let rain_document = RainDocument::builder(text.to_owned()).build_syntax_only();
+        assert_eq!(rain_document.span_text([4, 6]), Some("#a"));
+        assert_eq!(rain_document.span_text([0, 0]), Some(""));
+        assert_eq!(rain_document.span_text([0, text.len()]), Some(text));
+        // out of range
+        assert_eq!(rain_document.span_text([4, text.len() + 1]), None);
+        // reversed
+        assert_eq!(rain_document.span_text([6, 4]), None);
+        // not on a char boundary
+        assert_eq!(rain_document.span_text([11, 12]), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binding_at() -> anyhow::Result<()> {
+        let text = "---\n#a 1\n\n#b\n  2";
+        let rain_document = RainDocument::builder(text.to_owned()).build_syntax_only();
+        let a = &rain_document.bindings()[0];
+        let b = &rain_document.bindings()[1];
+        assert_eq!(a.name, "a");
+        assert_eq!(b.name, "b");
+
+        // start of the span is inclusive, the end is exclusive
+        assert_eq!(
+            rain_document.binding_at(a.position[0]).map(|v| &v.name),
+            Some(&a.name)
+        );
+        assert_eq!(
+            rain_document.binding_at(a.position[1] - 1).map(|v| &v.name),
+            Some(&a.name)
+        );
+        assert_eq!(rain_document.binding_at(a.position[1]), None);
+        assert_eq!(
+            rain_document
+                .binding_at(b.content_position[0])
+                .map(|v| &v.name),
+            Some(&b.name)
+        );
+        // front matter and out of range offsets
+        assert_eq!(rain_document.binding_at(0), None);
+        assert_eq!(rain_document.binding_at(text.len() + 1), None);
+
+        // the span starts after the "#"
+        assert_eq!(rain_document.binding_at_position(1, 0), None);
+        assert_eq!(
+            rain_document.binding_at_position(1, 1).map(|v| &v.name),
+            Some(&a.name)
+        );
+        // clamped to the line's end, a binding's span runs up to the next binding
+        assert_eq!(
+            rain_document.binding_at_position(1, 100).map(|v| &v.name),
+            Some(&a.name)
+        );
+        assert_eq!(
+            rain_document.binding_at_position(2, 0).map(|v| &v.name),
+            Some(&a.name)
+        );
+        assert_eq!(
+            rain_document.binding_at_position(4, 2).map(|v| &v.name),
+            Some(&b.name)
+        );
+        assert_eq!(rain_document.binding_at_position(10, 0), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_constants() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (lib_hash, _) =
+            store.set_dotrain("---\n#value 4e18\n#other 0x10", "lib.rain", false)?;
+        let lib_hash = alloy_primitives::hex::encode_prefixed(lib_hash);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let big = format!("1{}", "0".repeat(78));
+        let text =
+            format!("---\n@lib {lib_hash}\n#a 5\n#s \"abcd\"\n#big {big}\n#exp\n_: lib.value;");
+        let rain_document = RainDocument::create(text, Some(meta_store), None, None);
+        assert_eq!(
+            rain_document.constants(),
+            vec![
+                ("a".to_owned(), U256::from(5)),
+                ("lib.other".to_owned(), U256::from(16)),
+                (
+                    "lib.value".to_owned(),
+                    U256::from(4_000_000_000_000_000_000u128)
+                ),
+            ]
+        );
+        let invalid: Vec<_> = rain_document
+            .invalid_constants()
+            .into_iter()
+            .map(|(path, err)| (path, matches!(err, Error::InvalidNumbericValue)))
+            .collect();
+        assert_eq!(
+            invalid,
+            vec![("big".to_owned(), false), ("s".to_owned(), true)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_problem_codes() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (lib_hash, _) = store.set_dotrain("---\n#value 1", "lib.rain", false)?;
+        let lib_hash = alloy_primitives::hex::encode_prefixed(lib_hash);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\n@lib {lib_hash} 'missing 2\n#a\n#exp\n_: add(1 2;\n#b\n_: b;");
+        let rain_document = RainDocument::create(text, Some(meta_store), None, None);
+        let codes = rain_document.problem_codes();
+        // import configuration, binding and nested rainlang problems, each once
+        for code in [
+            ErrorCode::UndefinedIdentifier,
+            ErrorCode::InvalidEmptyBinding,
+            ErrorCode::ExpectedClosingParen,
+        ] {
+            assert!(codes.contains(&code), "{:?}", code);
+        }
+        let all: HashSet<ErrorCode> = rain_document
+            .all_problems()
+            .iter()
+            .map(|v| v.code)
+            .collect();
+        assert!(all.is_subset(&codes));
+
+        let rain_document = RainDocument::create("---\n#a 1".to_owned(), None, None, None);
+        assert!(rain_document.problem_codes().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_leading_comments() -> anyhow::Result<()> {
+        let text = "---\n/* license */\n/* header */\n#a /* a's */ 1\n/* between */\n#b 2";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        assert_eq!(rain_document.comments().len(), 4);
+        let leading: Vec<&str> = rain_document
+            .leading_comments()
+            .iter()
+            .map(|v| v.comment.as_str())
+            .collect();
+        assert_eq!(leading, vec!["/* license */", "/* header */"]);
+
+        let text = "---\n/* only */";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        assert_eq!(rain_document.leading_comments().len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_in_comment() -> anyhow::Result<()> {
+        let text = "---\n#a /* c */ 1\n#b 2 /* unended";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let start = text.find("/*").unwrap();
+        let end = start + "/* c */".len();
+        assert!(!rain_document.is_in_comment(start));
+        assert!(rain_document.is_in_comment(start + 1));
+        assert!(rain_document.is_in_comment(end - 1));
+        assert!(!rain_document.is_in_comment(end));
+        assert!(!rain_document.is_in_comment(text.find('1').unwrap()));
+
+        // unended comment contains its end
+        let start = text.rfind("/*").unwrap();
+        assert!(!rain_document.is_in_comment(start));
+        assert!(rain_document.is_in_comment(text.len()));
+
+        // line comments contain their end
+        let options = ParseOptions::default().with_comment_styles(vec![CommentStyle::Line {
+            prefix: "//".to_owned(),
+        }])?;
+        let text = "---\n// line\n#a 1";
+        let rain_document = RainDocument::builder(text.to_owned())
+            .with_options(options)
+            .build();
+        assert!(rain_document.is_in_comment(text.find('\n').unwrap() + 1 + "// line".len()));
+        assert!(!rain_document.is_in_comment(text.find('#').unwrap()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolved_operands() -> anyhow::Result<()> {
+        let text = "---\n#a 1\n#exp\n_: add(mul(a 2) 3),\n_: sub(4 5);";
+        let mut rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let operands: Vec<_> = rain_document
+            .resolved_operands()
+            .into_iter()
+            .map(|(name, position, operand)| (name, position[0], operand))
+            .collect();
+        assert_eq!(
+            operands,
+            vec![
+                ("add".to_owned(), text.find("add").unwrap(), None),
+                ("mul".to_owned(), text.find("mul").unwrap(), None),
+                ("sub".to_owned(), text.find("sub").unwrap(), None),
+            ]
+        );
+
+        if let BindingItem::Exp(exp) = &mut rain_document.bindings[1].item {
+            if let Node::Opcode(op) = &mut exp.ast[0].lines[1].nodes[0] {
+                op.operand = Some(7);
+            }
+        }
+        let operands: Vec<_> = rain_document
+            .resolved_operands()
+            .into_iter()
+            .map(|v| v.2)
+            .collect();
+        assert_eq!(operands, vec![None, None, Some(7)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_aliases() -> anyhow::Result<()> {
+        let hash = format!("0x{}", "ab".repeat(32));
+        let text = format!("---\n@math {hash}\n@{hash}\n#a 1");
+        let rain_document = RainDocument::create(text.clone(), None, None, None);
+        let aliases: Vec<_> = rain_document
+            .import_aliases()
+            .into_iter()
+            .map(|(position, alias)| (position[0], alias))
+            .collect();
+        assert_eq!(
+            aliases,
+            vec![
+                (text.find("@math").unwrap(), Some("math".to_owned())),
+                (text.rfind('@').unwrap(), None),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_document_bytes() -> anyhow::Result<()> {
+        let text = "---\n#a 1\n#exp\n_: a;";
+        let create = |max_document_bytes: Option<usize>| {
+            RainDocument::builder(text.to_owned())
+                .with_options(ParseOptions::default().with_max_document_bytes(max_document_bytes))
+                .build()
+        };
+
+        let rain_document = create(Some(10));
+        let msg = "document exceeds the maximum size of 10 bytes";
+        assert_eq!(
+            rain_document.problems,
+            vec![ErrorCode::RuntimeError.to_problem(vec![msg], [0, 0])]
+        );
+        assert_eq!(rain_document.runtime_error(), &Some(msg.to_owned()));
+        assert!(rain_document.bindings.is_empty());
+        assert!(rain_document.comments.is_empty());
+
+        for max_document_bytes in [None, Some(text.len())] {
+            let rain_document = create(max_document_bytes);
+            assert!(rain_document.all_problems().is_empty());
+            assert_eq!(rain_document.bindings.len(), 2);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_import_integrity() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#value 5", "lib.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(&hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\n@lib {hash}\n#exp\n_: lib.value;");
+        let mut rain_document = RainDocument::create(text.clone(), Some(meta_store), None, None);
+        assert!(rain_document.verify_import_integrity().is_empty());
+
+        // the store only accepts metas that match their hash, but a deserialized (or otherwise
+        // tampered) document can carry metas that do not
+        let poisoned = format!("0x{}", "ab".repeat(32));
+        rain_document.imports[0].hash = poisoned.clone();
+        let start = text.find(&hash).unwrap();
+        assert_eq!(
+            rain_document.verify_import_integrity(),
+            vec![ErrorCode::MismatchImportHash
+                .to_problem(vec![&poisoned, &hash], [start, start + hash.len()])]
+        );
+
+        // unresolved imports are not verified
+        let text = format!("---\n@lib {poisoned}");
+        let rain_document = RainDocument::create(text, None, None, None);
+        assert!(rain_document.verify_import_integrity().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_words_pragma() -> anyhow::Result<()> {
+        use rain_metadata::{
+            types::authoring::v1::AuthoringMetaItem, ContentEncoding, ContentLanguage, ContentType,
+            KnownMagic, NPE2Deployer, RainMetaDocumentV1Item,
+        };
+        let words = |description: &str| {
+            AuthoringMeta(vec![AuthoringMetaItem {
+                word: "opcode-1".to_owned(),
+                operand_parser_offset: 0,
+                description: description.to_owned(),
+            }])
+        };
+        let opcode_description =
+            |rain_document: &RainDocument| match &rain_document.bindings[0].item {
+                BindingItem::Exp(exp) => match &exp.ast[0].lines[0].nodes[0] {
+                    Node::Opcode(op) => op.opcode.description.clone(),
+                    _ => String::new(),
+                },
+                _ => String::new(),
+            };
+        let mut store = Store::new();
+
+        // a deployer's authoring meta
+        let deployer_hash = format!("0x{}", "ab".repeat(32));
+        store.set_deployer(
+            &alloy_primitives::hex::decode(&deployer_hash)?,
+            &NPE2Deployer {
+                meta_hash: "meta-hash".as_bytes().to_vec(),
+                meta_bytes: "meta-bytes".as_bytes().to_vec(),
+                bytecode: "bytecode".as_bytes().to_vec(),
+                parser: "parser".as_bytes().to_vec(),
+                store: "store".as_bytes().to_vec(),
+                interpreter: "interpreter".as_bytes().to_vec(),
+                authoring_meta: Some(words("deployer word")),
+            },
+            None,
+        );
+
+        // an authoring meta
+        let meta_bytes = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(words("meta word").abi_encode()?),
+            magic: KnownMagic::AuthoringMetaV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }
+        .cbor_encode()?;
+        let meta_hash_bytes = alloy_primitives::keccak256(&meta_bytes).0;
+        store.update_with(&meta_hash_bytes, &meta_bytes);
+        let meta_hash = alloy_primitives::hex::encode_prefixed(meta_hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let text = format!("---\nword-set {deployer_hash}\n#exp\n_: opcode-1(1);");
+        let rain_document =
+            RainDocument::create(text, Some(meta_store.clone()), Some(words("given")), None);
+        assert!(rain_document.all_problems().is_empty());
+        assert_eq!(rain_document.known_words(), &Some(words("deployer word")));
+        assert_eq!(opcode_description(&rain_document), "deployer word");
+
+        let text = format!("---\nword-set {meta_hash}\n#exp\n_: opcode-1(1);");
+        let rain_document = RainDocument::create(text, Some(meta_store.clone()), None, None);
+        assert!(rain_document.all_problems().is_empty());
+        assert_eq!(opcode_description(&rain_document), "meta word");
+
+        // unresolved
+        let hash = format!("0x{}", "cd".repeat(32));
+        let text = format!("---\nword-set {hash}\n#exp\n_: opcode-1(1);");
+        let rain_document =
+            RainDocument::create(text.clone(), Some(meta_store.clone()), None, None);
+        let start = text.find(&hash).unwrap();
+        assert_eq!(
+            rain_document.problems(),
+            &vec![ErrorCode::UndefinedAuthoringMeta
+                .to_problem(vec![&hash], [start, start + hash.len()])]
+        );
+        assert_eq!(rain_document.known_words(), &None);
+
+        // the pinned words are dropped once the pragma is removed from the text or is pointed
+        // at an unresolved hash, the given words are known again
+        let text = format!("---\nword-set {deployer_hash}\n#exp\n_: opcode-1(1);");
+        let mut rain_document =
+            RainDocument::create(text, Some(meta_store.clone()), Some(words("given")), None);
+        assert_eq!(rain_document.known_words(), &Some(words("deployer word")));
+        rain_document.update("---\n#exp\n_: opcode-1(1);".to_owned(), None);
+        assert!(rain_document.all_problems().is_empty());
+        assert_eq!(rain_document.known_words(), &Some(words("given")));
+        assert_eq!(opcode_description(&rain_document), "given");
+        rain_document.update(format!("---\nword-set {deployer_hash}\n#exp\n_: 1;"), None);
+        assert_eq!(rain_document.known_words(), &Some(words("deployer word")));
+        rain_document.update(format!("---\nword-set {hash}\n#exp\n_: 1;"), None);
+        assert_eq!(rain_document.known_words(), &Some(words("given")));
+
+        // a rainlang pragma is not a word set pragma
+        let text = format!("---\nusing-words-from {deployer_hash}\n#exp\n_: 1;");
+        let rain_document = RainDocument::create(text, Some(meta_store.clone()), None, None);
+        assert_eq!(rain_document.known_words(), &None);
+
+        // invalid, missing and repeated
+        let rain_document = RainDocument::create(
+            "---\nword-set 0x1234\n#exp\n_: 1;".to_owned(),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            rain_document.problems(),
+            &vec![ErrorCode::InvalidHash.to_problem(vec![], [13, 19])]
+        );
+        let rain_document = RainDocument::create("---\nword-set".to_owned(), None, None, None);
+        assert_eq!(
+            rain_document.problems(),
+            &vec![ErrorCode::ExpectedHexLiteral.to_problem(vec![], [4, 12])]
+        );
+        let text =
+            format!("---\nword-set {meta_hash}\nword-set {deployer_hash}\n#exp\n_: opcode-1(1);");
+        let rain_document = RainDocument::create(text.clone(), Some(meta_store), None, None);
+        let start = text.rfind("word-set").unwrap();
+        assert_eq!(
+            rain_document.problems(),
+            &vec![ErrorCode::UnexpectedPragma
+                .to_problem(vec![], [start, start + 9 + deployer_hash.len()])]
+        );
+        assert_eq!(opcode_description(&rain_document), "meta word");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_literals() -> anyhow::Result<()> {
+        let text = "---\n#c 0x10\n#a\n_ _: add(1e3 c) \"str\";\n#b\n_: 2.5;";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let literals = rain_document.literals();
+        let values: Vec<_> = literals
+            .iter()
+            .map(|(literal, value)| (literal.value.as_str(), value.as_ref().ok().copied()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("1e3", Some(U256::from(1000))),
+                ("0x10", Some(U256::from(16))),
+                ("\"str\"", None),
+                ("2.5", None),
+            ]
+        );
+        assert_eq!(literals[1].0.id, Some("c".to_owned()));
+        assert!(matches!(literals[3].1, Err(Error::InvalidNumbericValue)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_dotrain_meta() -> anyhow::Result<()> {
+        let text = "---\n#value 5\n#exp\n_: value;";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let meta_bytes = rain_document.to_dotrain_meta()?;
+
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain(text, "lib.rain", false)?;
+        assert_eq!(
+            alloy_primitives::keccak256(&meta_bytes).0.to_vec(),
+            hash_bytes
+        );
+        assert_eq!(store.get_meta(&hash_bytes), Some(&meta_bytes));
+
+        let items = RainMetaDocumentV1Item::cbor_decode(&meta_bytes)?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].magic, KnownMagic::DotrainV1);
+        assert_eq!(items[0].clone().unpack()?, text.as_bytes());
+
+        // round trips through importing
+        let hash = alloy_primitives::hex::encode_prefixed(&hash_bytes);
+        let mut store = Store::new();
+        store.update_with(&hash_bytes, &meta_bytes);
+        let rain_document = RainDocument::create(
+            format!("---\n@lib {hash}\n#exp\n_: lib.value;"),
+            Some(Arc::new(RwLock::new(store))),
+            None,
+            None,
+        );
+        assert!(rain_document.all_problems().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_and_local_collisions() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#value 5", "lib.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(&hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+        let occupied = |text: &str| {
+            RainDocument::create(text.to_owned(), Some(meta_store.clone()), None, None)
+                .problems()
+                .iter()
+                .filter(|v| v.code == ErrorCode::OccupiedNamespace)
+                .map(|v| (v.msg.clone(), text[v.position[0]..v.position[1]].to_owned()))
+                .collect::<Vec<_>>()
+        };
+
+        // local binding named the same as an import namespace
+        let text = format!("---\n@lib {hash}\n#lib 1");
+        assert_eq!(
+            occupied(&text),
+            vec![(
+                "occupied namespace: lib, local binding shadows an import".to_owned(),
+                "lib".to_owned()
+            )]
+        );
+
+        // local binding named the same as a member imported into the root namespace
+        let text = format!("---\n@{hash}\n#value 1");
+        assert_eq!(
+            occupied(&text),
+            vec![(
+                "occupied namespace: value, local binding shadows an import".to_owned(),
+                "value".to_owned()
+            )]
+        );
+
+        // import stated after the local binding
+        let text = format!("---\n#value 1\n@{hash}\n");
+        assert_eq!(
+            occupied(&text),
+            vec![(
+                "occupied namespace: value, import shadows a local binding".to_owned(),
+                "value".to_owned()
+            )]
+        );
+
+        // local duplicates are still duplicate identifiers
+        let rain_document = RainDocument::create("---\n#a 1\n#a 2".to_owned(), None, None, None);
+        assert_eq!(
+            rain_document.problems(),
+            &vec![ErrorCode::DuplicateIdentifier.to_problem(vec![], [10, 11])]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mixed_binding_types_imports() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (constant_hash, _) = store.set_dotrain("---\n#foo 5", "constant.rain", false)?;
+        let (exp_hash, _) = store.set_dotrain("---\n#foo\n_: 1;\n#bar 3", "exp.rain", false)?;
+        let (other_hash, _) = store.set_dotrain("---\n#foo 6", "other.rain", false)?;
+        let constant_hash = alloy_primitives::hex::encode_prefixed(&constant_hash);
+        let exp_hash = alloy_primitives::hex::encode_prefixed(&exp_hash);
+        let other_hash = alloy_primitives::hex::encode_prefixed(&other_hash);
+        let meta_store = Arc::new(RwLock::new(store));
+        let mixed = |text: &str| {
+            RainDocument::create(text.to_owned(), Some(meta_store.clone()), None, None)
+                .problems()
+                .iter()
+                .filter(|v| v.code == ErrorCode::CollidingBindingTypes)
+                .map(|v| (v.msg.clone(), text[v.position[0]..v.position[1]].to_owned()))
+                .collect::<Vec<_>>()
+        };
+
+        // imported into the root namespace
+        let text = format!("---\n@{constant_hash}\n@{exp_hash}\n");
+        assert_eq!(
+            mixed(&text),
+            vec![(
+                format!(
+                    "colliding binding types of foo: constant of {constant_hash} and expression of {exp_hash}"
+                ),
+                exp_hash.clone()
+            )]
+        );
+        // the colliding import is not merged
+        let rain_document = RainDocument::create(text, Some(meta_store.clone()), None, None);
+        assert!(rain_document.namespace().contains_key("foo"));
+        assert!(!rain_document.namespace().contains_key("bar"));
+
+        // imported into the same named namespace
+        let text = format!("---\n@lib {exp_hash}\n@lib {constant_hash}\n");
+        assert_eq!(
+            mixed(&text),
+            vec![(
+                format!(
+                    "colliding binding types of lib.foo: expression of {exp_hash} and constant of {constant_hash}"
+                ),
+                constant_hash.clone()
+            )]
+        );
+        // the colliding import is not merged
+        let text = format!("---\n@lib {constant_hash}\n@lib {exp_hash}\n");
+        let rain_document = RainDocument::create(text, Some(meta_store.clone()), None, None);
+        match rain_document.namespace().get("lib") {
+            Some(NamespaceItem::Node(node)) => {
+                assert!(node.contains_key("foo"));
+                assert!(!node.contains_key("bar"));
+            }
+            _ => panic!("expected lib namespace node"),
+        }
+
+        // same binding types only collide by name
+        let text = format!("---\n@lib {constant_hash}\n@lib {other_hash}\n");
+        assert!(mixed(&text).is_empty());
+        assert!(
+            RainDocument::create(text, Some(meta_store.clone()), None, None)
+                .problems()
+                .iter()
+                .any(|v| v.code == ErrorCode::CollidingNamespaceNodes)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_imported_document() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#value 5", "lib.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(&hash_bytes);
+        let unknown_hash = format!("0x{}", "ab".repeat(32));
+        let rain_document = RainDocument::create(
+            format!("---\n@lib {hash}\n@other {unknown_hash}\n#exp\n_: lib.value;"),
+            Some(Arc::new(RwLock::new(store))),
+            None,
+            None,
+        );
+
+        let imported = rain_document
+            .imported_document(0)
+            .expect("resolved dotrain import");
+        assert_eq!(imported.text(), "---\n#value 5");
+        assert_eq!(imported.bindings()[0].name, "value");
+        assert!(rain_document.imported_document(1).is_none());
+        assert!(rain_document.imported_document(2).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_pragmas() -> anyhow::Result<()> {
+        let text = "---\nsome-pragma 0x1234 other\nnext-pragma\n#exp\n_: 1;";
+        let problems = |policy: UnknownPragmaPolicy| {
+            RainDocument::builder(text.to_owned())
+                .with_options(ParseOptions::default().with_unknown_pragmas(policy))
+                .build()
+                .problems()
+                .clone()
+        };
+
+        // a statement is a line that starts with a keyword and spans the whole line
+        assert_eq!(
+            problems(UnknownPragmaPolicy::Error),
+            vec![
+                ErrorCode::UndefinedPragma.to_problem(vec!["some-pragma"], [4, 28]),
+                ErrorCode::UndefinedPragma.to_problem(vec!["next-pragma"], [29, 40]),
+            ]
+        );
+        assert_eq!(
+            problems(UnknownPragmaPolicy::Warn)[0],
+            ErrorCode::UnknownPragma.to_problem(vec!["some-pragma"], [4, 28])
+        );
+        assert!(ErrorCode::UnknownPragma.is_warning());
+        assert!(problems(UnknownPragmaPolicy::Ignore).is_empty());
+
+        // lines that do not start with a keyword are not pragmas and are unexpected
+        let rain_document = RainDocument::builder("---\n0x12 some-pragma\n".to_owned())
+            .with_options(ParseOptions::default().with_unknown_pragmas(UnknownPragmaPolicy::Ignore))
+            .build();
+        assert_eq!(
+            rain_document.problems(),
+            &vec![
+                ErrorCode::UnexpectedToken.to_problem(vec![], [4, 8]),
+                ErrorCode::UnexpectedToken.to_problem(vec![], [9, 20]),
+            ]
+        );
+
+        // known pragmas are validated regardless of the policy and only span their keyword and
+        // value, anything after that is unexpected
+        let problems = |text: &str| {
+            RainDocument::builder(text.to_owned())
+                .with_options(
+                    ParseOptions::default().with_unknown_pragmas(UnknownPragmaPolicy::Ignore),
+                )
+                .build()
+                .problems()
+                .clone()
+        };
+        assert!(problems("---\nversion 1\nversion-x 2\n#exp\n_: 1;").is_empty());
+        assert!(problems("---\nversion 1.2.3\n#exp\n_: 1;").is_empty());
+        assert_eq!(
+            problems("---\nversion\n#exp\n_: 1;"),
+            vec![ErrorCode::InvalidVersion.to_problem(vec![], [4, 11])]
+        );
+        assert_eq!(
+            problems("---\nversion v1\n#exp\n_: 1;"),
+            vec![ErrorCode::InvalidVersion.to_problem(vec![], [12, 14])]
+        );
+        assert_eq!(
+            problems("---\nversion 1\nversion 2\n#exp\n_: 1;"),
+            vec![ErrorCode::UnexpectedPragma.to_problem(vec![], [14, 23])]
+        );
+        assert_eq!(
+            problems("---\nversion 1 2\n#exp\n_: 1;"),
+            vec![ErrorCode::UnexpectedToken.to_problem(vec![], [14, 15])]
+        );
+
+        Ok(())
+    }
 }
@@ -7,7 +7,7 @@ use super::super::{
         error::{Error, ErrorCode},
         types::patterns::*,
     },
-    inclusive_parse, fill_in, exclusive_parse, tracked_trim,
+    inclusive_parse, fill_in, exclusive_parse, tracked_trim, u256_overflow_detail, to_u256,
 };
 
 impl RainlangDocument {
@@ -35,9 +35,17 @@ impl RainlangDocument {
             return Ok(());
         };
 
+        // report tab characters if enabled, they do not affect the parsing
+        if self.state.options.lints_tabs() {
+            for (pos, _) in document.match_indices('\t') {
+                self.problems
+                    .push(ErrorCode::TabCharacter.to_problem(vec![], [pos, pos + 1]));
+            }
+        }
+
         // parse and take out comments
-        for parsed_comment in inclusive_parse(&document, &COMMENT_PATTERN, 0) {
-            if !parsed_comment.0.ends_with("*/") {
+        for parsed_comment in inclusive_parse(&document, self.state.options.comment_pattern(), 0) {
+            if self.state.options.is_unended_comment(&parsed_comment.0) {
                 self.problems
                     .push(ErrorCode::UnexpectedEndOfComment.to_problem(vec![], parsed_comment.1));
             }
@@ -162,9 +170,14 @@ impl RainlangDocument {
             self.problems
                 .push(ErrorCode::ExpectedSemi.to_problem(vec![], [p, p + 1]));
         }
-        for v in parsed_sources {
+        let sources_count = parsed_sources.len();
+        for (i, v) in parsed_sources.into_iter().enumerate() {
             let trimmed = tracked_trim(&v.0);
             if trimmed.0.is_empty() {
+                // a trailing empty source is dropped in lenient mode
+                if self.state.options.is_lenient() && i > 0 && i == sources_count - 1 {
+                    continue;
+                }
                 self.problems.push(
                     ErrorCode::InvalidEmptyBinding
                         .to_problem(vec![], [v.1[1] - trimmed.2, v.1[1] - trimmed.2]),
@@ -201,19 +214,28 @@ impl RainlangDocument {
                     sub_src_items_pos.push([v.1[0] + trimmed.1, v.1[1] - trimmed.2]);
                     ends_diff.push(trimmed.2);
                 });
+            // a trailing empty line is dropped in lenient mode
+            if self.state.options.is_lenient()
+                && sub_src_items.len() > 1
+                && sub_src_items.last().is_some_and(|v| v.is_empty())
+            {
+                sub_src_items.pop();
+                sub_src_items_pos.pop();
+                ends_diff.pop();
+            }
 
             for (j, sub_src) in sub_src_items.iter().enumerate() {
                 self.reset_state();
                 let cursor_offset = sub_src_items_pos[j][0];
                 if !self.ast[i].lines.is_empty() {
                     // add parsed lhs words to the occupied keys
-                    occupied_keys.extend(self.ast[i].lines[j - 1].aliases.iter().filter_map(|v| {
-                        if v.name != "_" {
-                            Some(v.name.clone())
-                        } else {
-                            None
-                        }
-                    }))
+                    occupied_keys.extend(
+                        self.ast[i].lines[j - 1]
+                            .aliases
+                            .iter()
+                            .filter(|v| !v.is_placeholder())
+                            .map(|v| v.name.clone()),
+                    )
                 }
                 if let Some((lhs, rhs)) = sub_src.split_once(':') {
                     // check for invalid comments
@@ -234,7 +256,7 @@ impl RainlangDocument {
                                 position: item.1,
                                 lhs_alias: None,
                             });
-                            if !LHS_PATTERN.is_match(&item.0) {
+                            if !self.state.options.word_pattern().is_lhs_match(&item.0) {
                                 self.problems.push(
                                     ErrorCode::InvalidWordPattern.to_problem(vec![&item.0], item.1),
                                 );
@@ -254,6 +276,10 @@ impl RainlangDocument {
                         namespace,
                         authoring_meta, // resolveQuotes
                     )?;
+                    // like the opcodes, the outputs are only validated against known words
+                    if !authoring_meta.0.is_empty() {
+                        self.check_outputs(cursor_offset);
+                    }
                 } else {
                     // error if sub source is empty
                     if sub_src.is_empty() || sub_src.trim().is_empty() {
@@ -279,6 +305,37 @@ impl RainlangDocument {
         Ok(())
     }
 
+    /// Reports a problem if the number of lhs aliases of the current line does not match the
+    /// number of its rhs values, lines with opcodes are skipped since the authoring meta does
+    /// not declare the number of outputs of the words, so only literals and aliases (that each
+    /// produce exactly one value) are counted
+    pub(super) fn check_outputs(&mut self, lhs_offset: usize) {
+        if self
+            .state
+            .nodes
+            .iter()
+            .any(|node| matches!(node, Node::Opcode(_)))
+        {
+            return;
+        }
+        let lhs_count = self.state.aliases.len();
+        let rhs_count = self.state.nodes.len();
+        if lhs_count == rhs_count {
+            return;
+        }
+        let position = match (self.state.aliases.first(), self.state.aliases.last()) {
+            (Some(first), Some(last)) => [first.position[0], last.position[1]],
+            _ => [lhs_offset, lhs_offset],
+        };
+        let (code, expected, found) = if lhs_count > rhs_count {
+            (ErrorCode::MismatchLHS, rhs_count, lhs_count)
+        } else {
+            (ErrorCode::MismatchRHS, lhs_count, rhs_count)
+        };
+        self.problems
+            .push(code.to_problem(vec![&expected.to_string(), &found.to_string()], position));
+    }
+
     /// resets the parse state
     pub(super) fn reset_state(&mut self) {
         self.state.depth = 0;
@@ -365,9 +422,8 @@ impl RainlangDocument {
             node.position[1] = end_position + 1;
             node.parens[1] = end_position;
             self.problems.retain(|v| {
-                v.msg != "expected \")\""
-                    || v.position[0] != node.opcode.position[0]
-                    || v.position[1] != node.parens[0] + 1
+                v.code != ErrorCode::ExpectedClosingParen
+                    || v.position != [node.parens[0], node.parens[0] + 1]
             });
             Ok(())
         } else {
@@ -375,6 +431,43 @@ impl RainlangDocument {
         }
     }
 
+    /// validates the operand args of the given opcode against the operand args schema of its
+    /// word, missing required args are reported at the opcode and the args given to a word that
+    /// takes none are reported as a warning
+    fn validate_operand_args(&mut self, op: &Opcode, schema: OperandArgsSchema) {
+        let Some(operand_args) = &op.operand_args else {
+            if schema.required > 0 {
+                let required = schema.names[..schema.required].join(" ");
+                self.problems.push(
+                    ErrorCode::ExpectedOperandArgs.to_problem(vec![&required], op.opcode.position),
+                );
+            }
+            return;
+        };
+        let count = operand_args.args.len();
+        if schema.names.is_empty() {
+            self.problems.push(
+                ErrorCode::UnexpectedOperandArgs
+                    .to_problem(vec![&op.opcode.name], operand_args.position),
+            );
+        } else if count < schema.required {
+            self.problems
+                .push(ErrorCode::OutOfRangeOperandArgs.to_problem(
+                    vec![&format!("at least {}", schema.required), &count.to_string()],
+                    operand_args.position,
+                ));
+        } else if count > schema.names.len() {
+            self.problems
+                .push(ErrorCode::OutOfRangeOperandArgs.to_problem(
+                    vec![
+                        &format!("at most {}", schema.names.len()),
+                        &count.to_string(),
+                    ],
+                    operand_args.position,
+                ));
+        }
+    }
+
     /// handles operand arguments
     pub(super) fn process_operand(
         &mut self,
@@ -396,7 +489,27 @@ impl RainlangDocument {
             });
             let mut operand_args_items = vec![];
             for v in operand_args {
-                if OPERAND_ARG_PATTERN.is_match(&v.0) {
+                if v.0.starts_with(|c: char| c.is_ascii_digit()) {
+                    // numeric operand args in any form that resolve to a valid u256 value, or
+                    // decimals with a fractional part which are valid as they are
+                    let is_valid = match to_u256(&v.0) {
+                        Ok(_) => true,
+                        Err(Error::InvalidNumbericValue) => NUMERIC_PATTERN.is_match(&v.0),
+                        Err(_) => false,
+                    };
+                    if is_valid {
+                        operand_args_items.push(OperandArgItem {
+                            value: Some(v.0.clone()),
+                            name: "operand arg".to_owned(),
+                            position: v.1,
+                            description: String::new(),
+                            binding_id: None,
+                        });
+                    } else {
+                        self.problems
+                            .push(ErrorCode::InvalidOperandArg.to_problem(vec![&v.0], v.1));
+                    }
+                } else if OPERAND_ARG_PATTERN.is_match(&v.0) {
                     if LITERAL_PATTERN.is_match(&v.0) {
                         operand_args_items.push(OperandArgItem {
                             value: Some(v.0.clone()),
@@ -632,6 +745,7 @@ impl RainlangDocument {
                     name: next.to_owned(),
                     description: String::new(),
                     position: next_pos,
+                    canonical: None,
                 },
                 operand: None,
                 output: None,
@@ -641,14 +755,36 @@ impl RainlangDocument {
                 operand_args: None,
                 lhs_alias: None,
             };
+            let mut operand_args_schema = None;
             if next.is_empty() {
                 self.problems
                     .push(ErrorCode::ExpectedOpcode.to_problem(vec![], next_pos));
             } else if !WORD_PATTERN.is_match(next) {
                 self.problems
                     .push(ErrorCode::InvalidWordPattern.to_problem(vec![next], next_pos));
+            } else if let Some(canonical) = self
+                .state
+                .options
+                .opcode_alias(next)
+                .or_else(|| Self::meta_opcode_alias(authoring_meta, next))
+            {
+                // aliases (the ones of the parse options override the ones declared by the
+                // authoring meta) resolve to their canonical word, they are only reported if
+                // the canonical word is not found among the known words
+                if let Some(word) = authoring_meta.0.iter().find(|&v| v.word == canonical) {
+                    op.opcode.description = word.description.clone();
+                    op.opcode.canonical = Some(canonical.to_owned());
+                    operand_args_schema =
+                        OperandArgsSchema::of_operand_parser(word.operand_parser_offset);
+                } else if !authoring_meta.0.is_empty() {
+                    self.problems.push(
+                        ErrorCode::UndefinedOpcode.to_problem(vec![next, canonical], next_pos),
+                    );
+                }
             } else if let Some(word) = authoring_meta.0.iter().find(|&v| v.word.as_str() == next) {
                 op.opcode.description = word.description.clone();
+                operand_args_schema =
+                    OperandArgsSchema::of_operand_parser(word.operand_parser_offset);
             }
 
             if remaining.starts_with('<') {
@@ -657,6 +793,9 @@ impl RainlangDocument {
                 offset += consumed;
                 remaining = &remaining[consumed..];
             }
+            if let Some(schema) = operand_args_schema {
+                self.validate_operand_args(&op, schema);
+            }
             if remaining.starts_with('(') {
                 let pos = {
                     if let Some(operand_arg) = &op.operand_args {
@@ -670,20 +809,35 @@ impl RainlangDocument {
                 op.parens[0] = pos;
                 self.update_state(Node::Opcode(op))?;
                 self.state.depth += 1;
-                self.problems.push(
-                    ErrorCode::ExpectedClosingParen.to_problem(vec![], [next_pos[0], pos + 1]),
-                );
+                self.problems
+                    .push(ErrorCode::ExpectedClosingParen.to_problem(vec![], [pos, pos + 1]));
             } else {
                 self.problems
                     .push(ErrorCode::ExpectedOpeningParen.to_problem(vec![], next_pos));
             }
         } else if LITERAL_PATTERN.is_match(next) {
+            if let Some(detail) = u256_overflow_detail(next) {
+                self.problems
+                    .push(ErrorCode::OutOfRangeValue.to_problem(vec![&detail], next_pos));
+            }
             self.update_state(Node::Literal(Literal {
                 value: next.to_owned(),
                 position: next_pos,
                 lhs_alias: None,
                 id: None,
             }))?;
+        } else if let Some(value) = self
+            .state
+            .options
+            .keyword_literal(next)
+            .map(|v| v.to_string())
+        {
+            self.update_state(Node::Literal(Literal {
+                value,
+                position: next_pos,
+                lhs_alias: None,
+                id: Some(next.to_owned()),
+            }))?;
         } else if next.contains('.') {
             if let Some(b) = self.search_namespace(next, cursor, namespace) {
                 match &b.item {
@@ -734,7 +888,7 @@ impl RainlangDocument {
                     lhs_alias: None,
                 }))?;
             }
-        } else if LHS_WORD_PATTERN.is_match(next) || WORD_PATTERN.is_match(next) {
+        } else if self.state.options.word_pattern().is_lhs_word_match(next) {
             if self.ast[self.ast.len() - 1]
                 .lines
                 .iter()
@@ -844,7 +998,10 @@ impl RainlangDocument {
             return None;
         }
         let mut is_invalid = false;
-        for invalid_segment in segments.iter().filter(|v| !WORD_PATTERN.is_match(&v.0)) {
+        for invalid_segment in segments
+            .iter()
+            .filter(|v| !self.state.options.word_pattern().is_match(&v.0))
+        {
             self.problems.push(
                 ErrorCode::InvalidWordPattern
                     .to_problem(vec![&invalid_segment.0], invalid_segment.1),
@@ -898,4 +1055,206 @@ impl RainlangDocument {
             None
         }
     }
+
+    /// checks the literal inputs of the given nodes' opcodes against the expected widths recursively
+    pub(super) fn check_literal_widths(
+        nodes: &[Node],
+        expected_widths: &HashMap<String, Vec<Option<usize>>>,
+        problems: &mut Vec<Problem>,
+    ) {
+        for node in nodes {
+            if let Node::Opcode(op) = node {
+                if let Some(widths) = expected_widths.get(&op.opcode.name) {
+                    for (input, width) in op.inputs.iter().zip(widths) {
+                        if let (Node::Literal(literal), Some(expected)) = (input, width) {
+                            if !HEX_PATTERN.is_match(&literal.value) {
+                                problems.push(
+                                    ErrorCode::ExpectedHexLiteral
+                                        .to_problem(vec![], literal.position),
+                                );
+                            } else {
+                                let actual = (literal.value.len() - 1) / 2;
+                                if actual != *expected {
+                                    problems.push(ErrorCode::OutOfRangeValue.to_problem(
+                                        vec![&format!(
+                                            "expected {} bytes hex literal but got {} bytes",
+                                            expected, actual
+                                        )],
+                                        literal.position,
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+                Self::check_literal_widths(&op.inputs, expected_widths, problems);
+            }
+        }
+    }
+
+    /// checks the number of inputs of the given opcodes recursively against their ranges
+    pub(super) fn check_inputs(
+        nodes: &[Node],
+        inputs_ranges: &HashMap<String, InputsRange>,
+        problems: &mut Vec<Problem>,
+    ) {
+        for node in nodes {
+            if let Node::Opcode(op) = node {
+                if let Some(range) = inputs_ranges.get(&op.opcode.name) {
+                    if !range.contains(op.inputs.len()) {
+                        problems.push(ErrorCode::OutOfRangeInputs.to_problem(
+                            vec![&range.to_string(), &op.inputs.len().to_string()],
+                            op.parens,
+                        ));
+                    }
+                }
+                Self::check_inputs(&op.inputs, inputs_ranges, problems);
+            }
+        }
+    }
+
+    /// the canonical word of the given word if the authoring meta declares it as an alias, i.e.
+    /// the first sentence of its description is `alias of:` followed by the canonical word, see
+    /// [META_ALIAS_PATTERN]
+    pub(super) fn meta_opcode_alias<'a>(
+        authoring_meta: &'a AuthoringMeta,
+        word: &str,
+    ) -> Option<&'a str> {
+        let item = authoring_meta.0.iter().find(|v| v.word == word)?;
+        META_ALIAS_PATTERN
+            .captures(&item.description)
+            .and_then(|v| v.get(1))
+            .map(|v| v.as_str())
+            .filter(|v| *v != word)
+    }
+
+    /// checks if any of the given nodes is or contains a side effecting opcode
+    pub(super) fn has_side_effect(nodes: &[Node], side_effect_words: &HashSet<String>) -> bool {
+        nodes.iter().any(|node| match node {
+            Node::Opcode(op) => {
+                side_effect_words.contains(&op.opcode.name)
+                    || Self::has_side_effect(&op.inputs, side_effect_words)
+            }
+            _ => false,
+        })
+    }
+
+    /// collects the quote operand args of the given nodes and of their nested inputs
+    pub(super) fn collect_quotes(nodes: &[Node], quotes: &mut Vec<(String, Offsets)>) {
+        for node in nodes {
+            if let Node::Opcode(op) = node {
+                if let Some(operand_args) = &op.operand_args {
+                    for arg in &operand_args.args {
+                        if let Some(name) = arg
+                            .binding_id
+                            .as_ref()
+                            .and_then(|(id, _)| id.strip_prefix('\''))
+                        {
+                            quotes.push((name.to_owned(), arg.position));
+                        }
+                    }
+                }
+                Self::collect_quotes(&op.inputs, quotes);
+            }
+        }
+    }
+
+    /// checks if any of the given nodes is or contains a reference to the given alias
+    pub(super) fn references_alias(nodes: &[Node], name: &str) -> bool {
+        nodes.iter().any(|node| match node {
+            Node::Alias(alias) => alias.name == name,
+            Node::Opcode(op) => Self::references_alias(&op.inputs, name),
+            _ => false,
+        })
+    }
+
+    /// finds the deepest opcode of the given node and its depth recursively
+    pub(super) fn deepest_opcode(node: &Node, depth: usize) -> Option<(usize, Offsets)> {
+        if let Node::Opcode(op) = node {
+            let mut result = (depth + 1, op.opcode.position);
+            for input in &op.inputs {
+                if let Some(deepest) = Self::deepest_opcode(input, depth + 1) {
+                    if deepest.0 > result.0 {
+                        result = deepest;
+                    }
+                }
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// lexes the given text into tokens in a single pass without building any parse tree, the
+    /// comments are matched by the comment styles of the given options before anything else,
+    /// the same way they are taken out of the text when parsing
+    pub(super) fn lex(text: &str, options: &ParseOptions) -> Vec<Token> {
+        let is_delimiter = |c: char| c.is_whitespace() || "()<>:;,\"[".contains(c);
+        let mut comments = options.comment_pattern().find_iter(text).peekable();
+        let mut tokens = vec![];
+        let mut chars = text.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            if c.is_whitespace() {
+                continue;
+            }
+            while comments.peek().is_some_and(|v| v.start() < start) {
+                comments.next();
+            }
+            let next_comment = comments.peek().map_or(text.len(), |v| v.start());
+            let (kind, end) = match c {
+                _ if next_comment == start => (
+                    TokenKind::Comment,
+                    comments.next().map_or(text.len(), |v| v.end()),
+                ),
+                '"' => (
+                    TokenKind::StringLiteral,
+                    text[start + 1..]
+                        .find('"')
+                        .map_or(text.len(), |v| start + 1 + v + 1)
+                        .min(next_comment),
+                ),
+                '[' => (
+                    TokenKind::SubParserLiteral,
+                    text[start + 1..]
+                        .find(']')
+                        .map_or(text.len(), |v| start + 1 + v + 1)
+                        .min(next_comment),
+                ),
+                '(' => (TokenKind::OpenParen, start + 1),
+                ')' => (TokenKind::CloseParen, start + 1),
+                ':' | ';' | ',' | '<' | '>' => (TokenKind::Operator, start + 1),
+                _ => {
+                    let mut end = start + c.len_utf8();
+                    while let Some((i, next)) = chars.peek() {
+                        if is_delimiter(*next) || *i == next_comment {
+                            break;
+                        }
+                        end = i + next.len_utf8();
+                        chars.next();
+                    }
+                    let word = &text[start..end];
+                    let path = word.strip_prefix('\'').unwrap_or(word);
+                    let kind = if NUMERIC_PATTERN.is_match(word) {
+                        TokenKind::Number
+                    } else if word == "_"
+                        || (NAMESPACE_PATTERN.is_match(path)
+                            && path.contains(|v: char| v.is_ascii_lowercase()))
+                    {
+                        TokenKind::Word
+                    } else {
+                        TokenKind::Unknown
+                    };
+                    (kind, end)
+                }
+            };
+            while chars.peek().is_some_and(|(i, _)| *i < end) {
+                chars.next();
+            }
+            tokens.push(Token {
+                kind,
+                position: [start, end],
+            });
+        }
+        tokens
+    }
 }
@@ -1,5 +1,9 @@
+use std::collections::{HashMap, HashSet};
 use serde::{Serialize, Deserialize};
-use super::super::{types::ast::*, error::ErrorCode};
+use super::{
+    ParseOptions,
+    super::{types::ast::*, error::ErrorCode},
+};
 use rain_metadata::types::authoring::v1::AuthoringMeta;
 
 #[cfg(feature = "js-api")]
@@ -19,6 +23,7 @@ struct RainlangState {
     aliases: Vec<Alias>,
     parens: Parens,
     depth: usize,
+    options: ParseOptions,
 }
 
 /// Data structure (parse tree) of a Rainlang text
@@ -65,6 +70,168 @@ impl RainlangDocument {
     pub fn comments(&self) -> &Vec<Comment> {
         &self.comments
     }
+
+    /// Validates the byte length of the literal inputs of opcodes against the given expected
+    /// widths, `expected_widths` maps an opcode name to the expected byte length of each of its
+    /// inputs, where `None` means no expectation for that input
+    pub fn validate_literal_widths(
+        &self,
+        expected_widths: &HashMap<String, Vec<Option<usize>>>,
+    ) -> Vec<Problem> {
+        let mut problems = vec![];
+        for src in &self.ast {
+            for line in &src.lines {
+                Self::check_literal_widths(&line.nodes, expected_widths, &mut problems);
+            }
+        }
+        problems
+    }
+
+    /// Validates the opcodes nesting depth of this instance's expressions against the given max
+    /// depth and reports a warning at the deepest opcode of each expression that exceeds it
+    pub fn validate_nesting_depth(&self, max_depth: usize) -> Vec<Problem> {
+        let mut problems = vec![];
+        for src in &self.ast {
+            for line in &src.lines {
+                for node in &line.nodes {
+                    if let Some((depth, position)) = Self::deepest_opcode(node, 0) {
+                        if depth > max_depth {
+                            problems.push(
+                                ErrorCode::DeepNesting
+                                    .to_problem(vec![&max_depth.to_string()], position),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        problems
+    }
+
+    /// Reports a warning for each line of this instance's sources that comes after the lines
+    /// whose lhs aliases already satisfy the given number of outputs, such lines are possibly
+    /// dead code, lines that contain any of the given side effecting opcodes are not reported
+    pub fn validate_unreachable_lines(
+        &self,
+        outputs: usize,
+        side_effect_words: &HashSet<String>,
+    ) -> Vec<Problem> {
+        let mut problems = vec![];
+        for src in &self.ast {
+            let mut satisfied = 0;
+            for line in &src.lines {
+                if satisfied >= outputs && !Self::has_side_effect(&line.nodes, side_effect_words) {
+                    problems.push(ErrorCode::UnreachableLine.to_problem(vec![], line.position));
+                }
+                satisfied += line.aliases.len();
+            }
+        }
+        problems
+    }
+
+    /// Reports a warning at the last line of each of this instance's sources that has no lhs
+    /// aliases, i.e. the source produces zero outputs, sources that contain any of the given
+    /// side effecting opcodes are considered side effect only and are not reported, empty
+    /// sources (such as the one of a repeated `;`) are reported at their position as well
+    pub fn validate_zero_outputs(&self, side_effect_words: &HashSet<String>) -> Vec<Problem> {
+        let mut problems = vec![];
+        for src in &self.ast {
+            if let Some(last) = src.lines.last() {
+                if last.aliases.is_empty()
+                    && !src
+                        .lines
+                        .iter()
+                        .any(|line| Self::has_side_effect(&line.nodes, side_effect_words))
+                {
+                    problems.push(ErrorCode::ZeroOutputs.to_problem(vec![], last.position));
+                }
+            } else {
+                problems.push(ErrorCode::ZeroOutputs.to_problem(vec![], src.position));
+            }
+        }
+        // empty sources are left out of the parse tree and are only reported as empty bindings
+        problems.extend(
+            self.problems
+                .iter()
+                .filter(|v| v.code == ErrorCode::InvalidEmptyBinding)
+                .map(|v| ErrorCode::ZeroOutputs.to_problem(vec![], v.position)),
+        );
+        problems.sort_by_key(|v| v.position);
+        problems
+    }
+
+    /// Reports a hint for each lhs alias of this instance's sources that is never referenced
+    /// by the lines coming after its own line within the same source, placeholder aliases
+    /// (`_`) and the aliases of each source's last line (which are the source's outputs) are
+    /// not reported
+    pub fn validate_unused_aliases(&self) -> Vec<Problem> {
+        let mut problems = vec![];
+        for src in &self.ast {
+            for (i, line) in src
+                .lines
+                .iter()
+                .enumerate()
+                .take(src.lines.len().saturating_sub(1))
+            {
+                for alias in line.aliases.iter().filter(|v| !v.is_placeholder()) {
+                    if !src.lines[i + 1..]
+                        .iter()
+                        .any(|v| Self::references_alias(&v.nodes, &alias.name))
+                    {
+                        problems.push(
+                            ErrorCode::UnusedAlias.to_problem(vec![&alias.name], alias.position),
+                        );
+                    }
+                }
+            }
+        }
+        problems
+    }
+
+    /// Validates the number of inputs of this instance's opcodes against the given inputs
+    /// ranges of the opcodes (keyed by their words) and reports a problem at the parens of
+    /// each opcode that is out of its range, opcodes without a range are skipped
+    pub fn validate_inputs(&self, inputs_ranges: &HashMap<String, InputsRange>) -> Vec<Problem> {
+        let mut problems = vec![];
+        for src in &self.ast {
+            for line in &src.lines {
+                Self::check_inputs(&line.nodes, inputs_ranges, &mut problems);
+            }
+        }
+        problems
+    }
+
+    /// The quotes (`'name` operand args) of this instance's sources with their positions in
+    /// source order, including the ones of nested opcodes, the names are without the leading
+    /// `'` while the positions cover the whole quotes
+    pub fn quotes(&self) -> Vec<(String, Offsets)> {
+        let mut quotes = vec![];
+        for src in &self.ast {
+            for line in &src.lines {
+                Self::collect_quotes(&line.nodes, &mut quotes);
+            }
+        }
+        quotes
+    }
+
+    /// Splits the given rainlang text into lexical tokens (words, literals, parens, operators
+    /// and comments) without building the parse tree or resolving anything, useful for
+    /// lightweight syntax highlighting, comments are matched by the given options' comment styles
+    pub fn tokens(text: &str, options: &ParseOptions) -> Vec<Token> {
+        Self::lex(text, options)
+    }
+
+    /// Parses the given rainlang text on its own, without any surrounding dotrain, into a new
+    /// instance with an empty namespace and the default word pattern, the given authoring meta
+    /// (if any) is used to validate the opcodes, problems' offsets are relative to the given text
+    pub fn parse(text: &str, words: Option<&AuthoringMeta>) -> RainlangDocument {
+        Self::create(
+            text.to_owned(),
+            &HashMap::new(),
+            words,
+            &ParseOptions::default(),
+        )
+    }
 }
 
 impl RainlangDocument {
@@ -73,6 +240,7 @@ impl RainlangDocument {
         text: String,
         namespace: &Namespace,
         authoring_meta: Option<&AuthoringMeta>,
+        options: &ParseOptions,
     ) -> RainlangDocument {
         let mut rainlang_doc = RainlangDocument {
             text,
@@ -82,9 +250,12 @@ impl RainlangDocument {
             dependencies: vec![],
             pragmas: vec![],
             error: None,
-            state: RainlangState::default(),
+            state: RainlangState {
+                options: options.clone(),
+                ..Default::default()
+            },
         };
-        rainlang_doc.parse(namespace, authoring_meta.unwrap_or(&AuthoringMeta(vec![])));
+        rainlang_doc.do_parse(namespace, authoring_meta.unwrap_or(&AuthoringMeta(vec![])));
         rainlang_doc
     }
 
@@ -101,7 +272,7 @@ impl RainlangDocument {
         }
     }
 
-    pub(crate) fn parse(&mut self, namespace: &Namespace, authoring_meta: &AuthoringMeta) {
+    pub(crate) fn do_parse(&mut self, namespace: &Namespace, authoring_meta: &AuthoringMeta) {
         if let Err(e) = self._parse(namespace, authoring_meta) {
             self.error = Some(e.to_string());
             self.problems
@@ -132,6 +303,7 @@ mod tests {
                 name: "add".to_owned(),
                 description: String::new(),
                 position: [5, 8],
+                canonical: None,
             },
             operand: None,
             output: None,
@@ -162,6 +334,7 @@ mod tests {
                 name: "add".to_owned(),
                 description: String::new(),
                 position: [5, 8],
+                canonical: None,
             },
             operand: None,
             output: None,
@@ -202,6 +375,7 @@ mod tests {
                     name: "opc".to_owned(),
                     description: String::new(),
                     position: [5, 8],
+                    canonical: None,
                 },
                 operand: None,
                 output: None,
@@ -217,6 +391,7 @@ mod tests {
                 name: "opc".to_owned(),
                 description: String::new(),
                 position: [5, 8],
+                canonical: None,
             }
         }
 
@@ -466,6 +641,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_numeric_operand_args() -> anyhow::Result<()> {
+        let mut rl = RainlangDocument::new();
+        let namespace = HashMap::new();
+        let mut op = Opcode {
+            opcode: OpcodeDetails {
+                name: "opc".to_owned(),
+                description: String::new(),
+                position: [0, 3],
+                canonical: None,
+            },
+            operand: None,
+            output: None,
+            position: [0, 0],
+            parens: [0, 0],
+            inputs: vec![],
+            lhs_alias: None,
+            operand_args: None,
+        };
+        let exp = "<12 0x0a 0b101 1e3 1.5 0b102 1e78 0x>";
+        let consumed_count = rl.process_operand(exp, 3, &mut op, &namespace);
+        assert_eq!(consumed_count, exp.len());
+        let values: Vec<_> = op
+            .operand_args
+            .unwrap()
+            .args
+            .into_iter()
+            .map(|v| v.value.unwrap())
+            .collect();
+        assert_eq!(values, vec!["12", "0x0a", "0b101", "1e3", "1.5"]);
+        let expected_problems = vec![
+            ErrorCode::InvalidOperandArg.to_problem(vec!["0b102"], [26, 31]),
+            ErrorCode::InvalidOperandArg.to_problem(vec!["1e78"], [32, 36]),
+            ErrorCode::InvalidOperandArg.to_problem(vec!["0x"], [37, 39]),
+        ];
+        assert_eq!(rl.problems, expected_problems);
+
+        Ok(())
+    }
+
     #[test]
     fn test_consume_method() -> anyhow::Result<()> {
         let mut rl = RainlangDocument::new();
@@ -495,6 +710,7 @@ mod tests {
                 name: "opcode".to_owned(),
                 description: String::new(),
                 position: [10, 16],
+                canonical: None,
             },
             operand: None,
             output: None,
@@ -533,6 +749,7 @@ mod tests {
                 name: "opcode".to_owned(),
                 description: String::new(),
                 position: [10, 16],
+                canonical: None,
             },
             operand: None,
             output: None,
@@ -571,6 +788,7 @@ mod tests {
                 name: "another-opcode".to_owned(),
                 description: String::new(),
                 position: [24, 38],
+                canonical: None,
             },
             operand: None,
             output: None,
@@ -591,6 +809,7 @@ mod tests {
                 name: "another-opcode-2".to_owned(),
                 description: String::new(),
                 position: [77, 93],
+                canonical: None,
             },
             operand: None,
             output: None,
@@ -629,6 +848,7 @@ mod tests {
                 name: "another-opcode-2".to_owned(),
                 description: String::new(),
                 position: [77, 93],
+                canonical: None,
             },
             operand: None,
             output: None,
@@ -670,6 +890,7 @@ mod tests {
                 name: "another-opcode-2".to_owned(),
                 description: String::new(),
                 position: [77, 93],
+                canonical: None,
             },
             operand: None,
             output: None,
@@ -706,6 +927,258 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_validate_literal_widths_method() -> anyhow::Result<()> {
+        let text = "_ _ _: opcode-1(0xabcd 0x12 3) opcode-2(opcode-1(0xab 4 5)) opcode-3(0x1);";
+        let rl = RainlangDocument::create(
+            text.to_owned(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+        let mut expected_widths = HashMap::new();
+        expected_widths.insert("opcode-1".to_owned(), vec![Some(2), None, Some(1)]);
+        expected_widths.insert("opcode-3".to_owned(), vec![Some(1)]);
+
+        let result = rl.validate_literal_widths(&expected_widths);
+        let expected = vec![
+            ErrorCode::ExpectedHexLiteral.to_problem(vec![], [28, 29]),
+            ErrorCode::OutOfRangeValue.to_problem(
+                vec!["expected 2 bytes hex literal but got 1 bytes"],
+                [49, 53],
+            ),
+            ErrorCode::ExpectedHexLiteral.to_problem(vec![], [56, 57]),
+        ];
+        assert_eq!(result, expected);
+        assert_eq!(
+            result[1].msg,
+            "value out of range, expected 2 bytes hex literal but got 1 bytes"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_unreachable_lines_method() -> anyhow::Result<()> {
+        let text = "a: 1,\nb: 2,\n: ensure(a),\nc: add(a b);\nx y: 1 2,\nz: 3;";
+        let rl = RainlangDocument::create(
+            text.to_owned(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+        let unreachables = |outputs: usize, side_effect_words: &HashSet<String>| -> Vec<&str> {
+            rl.validate_unreachable_lines(outputs, side_effect_words)
+                .into_iter()
+                .inspect(|p| assert_eq!(p.code, ErrorCode::UnreachableLine))
+                .map(|p| &text[p.position[0]..p.position[1]])
+                .collect()
+        };
+
+        let side_effect_words = HashSet::from(["ensure".to_owned()]);
+        assert_eq!(
+            unreachables(2, &side_effect_words),
+            vec!["c: add(a b)", "z: 3"]
+        );
+        assert_eq!(
+            unreachables(2, &HashSet::new()),
+            vec![": ensure(a)", "c: add(a b)", "z: 3"]
+        );
+        assert_eq!(unreachables(3, &side_effect_words), Vec::<&str>::new());
+        assert!(ErrorCode::UnreachableLine.is_warning());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_zero_outputs_method() -> anyhow::Result<()> {
+        let text = "a: 1,\n: ensure(a);\nb: 2,\n: b;\nc: 3;\n;";
+        let rl = RainlangDocument::create(
+            text.to_owned(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+        let zero_outputs = |side_effect_words: &HashSet<String>| -> Vec<&str> {
+            rl.validate_zero_outputs(side_effect_words)
+                .into_iter()
+                .inspect(|p| assert_eq!(p.code, ErrorCode::ZeroOutputs))
+                .map(|p| &text[p.position[0]..p.position[1]])
+                .collect()
+        };
+
+        // the empty source before the last `;` is reported at its (empty) position
+        assert_eq!(
+            zero_outputs(&HashSet::new()),
+            vec![": ensure(a)", ": b", ""]
+        );
+        let side_effect_words = HashSet::from(["ensure".to_owned()]);
+        assert_eq!(zero_outputs(&side_effect_words), vec![": b", ""]);
+        assert!(ErrorCode::ZeroOutputs.is_warning());
+        assert!(!ErrorCode::ZeroOutputs.is_hint());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_unused_aliases_method() -> anyhow::Result<()> {
+        let text = "a b _: 1 2 3,\nc: add(a mul(1 b)),\nd: 4,\ne: c;\nf: 1,\ng: 2;";
+        let rl = RainlangDocument::create(
+            text.to_owned(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+        let result = rl.validate_unused_aliases();
+        assert!(result.iter().all(|p| p.code == ErrorCode::UnusedAlias));
+        let unused: Vec<&str> = result
+            .iter()
+            .map(|p| &text[p.position[0]..p.position[1]])
+            .collect();
+        assert_eq!(unused, vec!["d", "f"]);
+        assert_eq!(result[0].msg, "unused alias: d");
+        assert!(ErrorCode::UnusedAlias.is_warning());
+        assert!(ErrorCode::UnusedAlias.is_hint());
+        assert!(!ErrorCode::UnreachableLine.is_hint());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_inputs_method() -> anyhow::Result<()> {
+        let text = "_ _ _: fixed(1 2 3) variadic(fixed(1)) variadic(), _: ranged(1 2);";
+        let rl = RainlangDocument::create(
+            text.to_owned(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+        let mut inputs_ranges = HashMap::new();
+        inputs_ranges.insert(
+            "fixed".to_owned(),
+            InputsRange {
+                min: 2,
+                max: Some(2),
+            },
+        );
+        inputs_ranges.insert("variadic".to_owned(), InputsRange { min: 1, max: None });
+        inputs_ranges.insert(
+            "ranged".to_owned(),
+            InputsRange {
+                min: 1,
+                max: Some(3),
+            },
+        );
+
+        let result = rl.validate_inputs(&inputs_ranges);
+        let found: Vec<(&str, &str)> = result
+            .iter()
+            .map(|p| (p.msg.as_str(), &text[p.position[0]..p.position[1]]))
+            .collect();
+        let expected = vec![
+            ("expected 2 inputs, found 3", "(1 2 3)"),
+            ("expected 2 inputs, found 1", "(1)"),
+            ("expected at least 1 inputs, found 0", "()"),
+        ];
+        assert_eq!(found, expected);
+        assert!(result.iter().all(|p| p.code == ErrorCode::OutOfRangeInputs));
+
+        // opcodes without a range are skipped
+        inputs_ranges.clear();
+        assert!(rl.validate_inputs(&inputs_ranges).is_empty());
+
+        let range = InputsRange {
+            min: 1,
+            max: Some(3),
+        };
+        assert!(range.contains(1) && range.contains(3));
+        assert!(!range.contains(0) && !range.contains(4));
+        assert_eq!(range.to_string(), "1 to 3");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_nesting_depth_method() -> anyhow::Result<()> {
+        let text = "_ _: opcode-1(opcode-2(opcode-3(1) 2) opcode-2(3)) opcode-1(4);";
+        let rl = RainlangDocument::create(
+            text.to_owned(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+
+        let result = rl.validate_nesting_depth(3);
+        assert_eq!(result, vec![]);
+
+        let result = rl.validate_nesting_depth(2);
+        let expected = vec![ErrorCode::DeepNesting.to_problem(vec!["2"], [23, 31])];
+        assert_eq!(result, expected);
+        assert!(result[0].code.is_warning());
+
+        let result = rl.validate_nesting_depth(0);
+        let expected = vec![
+            ErrorCode::DeepNesting.to_problem(vec!["0"], [23, 31]),
+            ErrorCode::DeepNesting.to_problem(vec!["0"], [51, 59]),
+        ];
+        assert_eq!(result, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_source_and_line_text() -> anyhow::Result<()> {
+        let text = "_: opcode-1(1 2),\n_ _: 3 4;\n_: 5;";
+        let rl = RainlangDocument::create(
+            text.to_owned(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+        let ast = rl.ast();
+
+        assert_eq!(ast[0].text(text), Some("_: opcode-1(1 2),\n_ _: 3 4"));
+        assert_eq!(ast[0].lines[0].text(text), Some("_: opcode-1(1 2)"));
+        assert_eq!(ast[0].lines[1].text(text), Some("_ _: 3 4"));
+        assert_eq!(ast[1].text(text), Some("_: 5"));
+        assert_eq!(ast[1].lines[0].text(text), Some("_: 5"));
+
+        // out of bounds content
+        assert_eq!(ast[1].text("_: 5;"), None);
+        assert_eq!(ast[0].lines[1].text("_: 5;"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_u256_overflow_literals() -> anyhow::Result<()> {
+        let hex = format!("0x1{}", "0".repeat(64));
+        let text = format!("_ _ _: 1e100 {} 1e18;", hex);
+        let rl = RainlangDocument::create(
+            text.clone(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+        let hex_start = text.find(&hex).unwrap();
+        let expected = vec![
+            ErrorCode::OutOfRangeValue.to_problem(
+                vec!["1e100 is not within uint256 range [0, 2^256 - 1]"],
+                [7, 12],
+            ),
+            ErrorCode::OutOfRangeValue.to_problem(
+                vec![&format!(
+                    "{} is not within uint256 range [0, 2^256 - 1]",
+                    hex
+                )],
+                [hex_start, hex_start + hex.len()],
+            ),
+        ];
+        assert_eq!(rl.problems(), &expected);
+
+        Ok(())
+    }
+
     #[test]
     fn test_search_namespace_method() -> anyhow::Result<()> {
         let mut rl = RainlangDocument::new();
@@ -782,4 +1255,473 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_lhs_rhs_mismatch() -> anyhow::Result<()> {
+        let words = AuthoringMeta(vec![AuthoringMetaItem {
+            word: "set".to_owned(),
+            operand_parser_offset: 0,
+            description: String::new(),
+        }]);
+        let mismatches = |text: &str, words: Option<&AuthoringMeta>| -> Vec<Problem> {
+            RainlangDocument::create(
+                text.to_owned(),
+                &HashMap::new(),
+                words,
+                &ParseOptions::default(),
+            )
+            .problems
+            .into_iter()
+            .filter(|p| matches!(p.code, ErrorCode::MismatchLHS | ErrorCode::MismatchRHS))
+            .collect()
+        };
+
+        assert_eq!(
+            mismatches("a b: 1;", Some(&words)),
+            vec![ErrorCode::MismatchLHS.to_problem(vec!["1", "2"], [0, 3])]
+        );
+        assert_eq!(
+            mismatches("a: 1 b;", Some(&words)),
+            vec![ErrorCode::MismatchRHS.to_problem(vec!["1", "2"], [0, 1])]
+        );
+        assert_eq!(mismatches("a b: 1 2,\n_: a;", Some(&words)), vec![]);
+        assert_eq!(
+            mismatches("a: 1,\n: a;", Some(&words)),
+            vec![ErrorCode::MismatchRHS.to_problem(vec!["0", "1"], [6, 6])]
+        );
+        assert_eq!(
+            ErrorCode::MismatchLHS
+                .to_problem(vec!["1", "2"], [0, 3])
+                .msg,
+            "expected 1 values on LHS, found 2"
+        );
+        assert_eq!(
+            ErrorCode::MismatchRHS
+                .to_problem(vec!["1", "2"], [0, 1])
+                .msg,
+            "expected 1 values on RHS, found 2"
+        );
+
+        // the number of outputs of opcodes is not known, so their lines are not checked
+        assert_eq!(mismatches(": set(a b) set(c d);", Some(&words)), vec![]);
+        assert_eq!(mismatches("a b c: set(1 2);", Some(&words)), vec![]);
+        assert_eq!(mismatches("a: 1 set(2 3);", Some(&words)), vec![]);
+
+        // not checked without known words
+        assert_eq!(mismatches("a b: 1;", None), vec![]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokens_method() -> anyhow::Result<()> {
+        let text = "_ x: add<1>(0x1f 'calc lib.value) /* c */, \"s\" [sub] 2e18 ?;";
+        let result: Vec<(TokenKind, &str)> =
+            RainlangDocument::tokens(text, &ParseOptions::default())
+                .iter()
+                .map(|v| (v.kind, &text[v.position[0]..v.position[1]]))
+                .collect();
+        let expected = vec![
+            (TokenKind::Word, "_"),
+            (TokenKind::Word, "x"),
+            (TokenKind::Operator, ":"),
+            (TokenKind::Word, "add"),
+            (TokenKind::Operator, "<"),
+            (TokenKind::Number, "1"),
+            (TokenKind::Operator, ">"),
+            (TokenKind::OpenParen, "("),
+            (TokenKind::Number, "0x1f"),
+            (TokenKind::Word, "'calc"),
+            (TokenKind::Word, "lib.value"),
+            (TokenKind::CloseParen, ")"),
+            (TokenKind::Comment, "/* c */"),
+            (TokenKind::Operator, ","),
+            (TokenKind::StringLiteral, "\"s\""),
+            (TokenKind::SubParserLiteral, "[sub]"),
+            (TokenKind::Number, "2e18"),
+            (TokenKind::Unknown, "?"),
+            (TokenKind::Operator, ";"),
+        ];
+        assert_eq!(result, expected);
+
+        // unterminated comments span to the end of the text
+        let result = RainlangDocument::tokens("x /* unterminated", &ParseOptions::default());
+        assert_eq!(
+            result,
+            vec![
+                Token {
+                    kind: TokenKind::Word,
+                    position: [0, 1]
+                },
+                Token {
+                    kind: TokenKind::Comment,
+                    position: [2, 17]
+                },
+            ]
+        );
+
+        // comments of custom comment styles, the default style is no longer a comment
+        let options = ParseOptions::default().with_comment_styles(vec![
+            crate::types::patterns::CommentStyle::Line {
+                prefix: "//".to_owned(),
+            },
+        ])?;
+        let text = "_: a// line comment\n/* b */";
+        let result: Vec<(TokenKind, &str)> = RainlangDocument::tokens(text, &options)
+            .iter()
+            .map(|v| (v.kind, &text[v.position[0]..v.position[1]]))
+            .collect();
+        let expected = vec![
+            (TokenKind::Word, "_"),
+            (TokenKind::Operator, ":"),
+            (TokenKind::Word, "a"),
+            (TokenKind::Comment, "// line comment"),
+            (TokenKind::Unknown, "/*"),
+            (TokenKind::Word, "b"),
+            (TokenKind::Unknown, "*/"),
+        ];
+        assert_eq!(result, expected);
+        let rl = RainlangDocument::create(text.to_owned(), &HashMap::new(), None, &options);
+        assert_eq!(rl.comments[0].comment, "// line comment");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_method() -> anyhow::Result<()> {
+        let text = "_: add(1 2),\n_: sub(1 x);";
+        let words = AuthoringMeta(vec![
+            AuthoringMetaItem {
+                word: "add".to_owned(),
+                operand_parser_offset: 0,
+                description: "adds".to_owned(),
+            },
+            AuthoringMetaItem {
+                word: "sub".to_owned(),
+                operand_parser_offset: 0,
+                description: String::new(),
+            },
+        ]);
+        let rl = RainlangDocument::parse(text, Some(&words));
+        assert_eq!(rl.text, text);
+        assert_eq!(rl.ast.len(), 1);
+        assert_eq!(rl.ast[0].lines.len(), 2);
+        let expected_problems = vec![ErrorCode::UndefinedWord.to_problem(vec!["x"], [22, 23])];
+        assert_eq!(rl.problems, expected_problems);
+
+        let Node::Opcode(op) = &rl.ast[0].lines[0].nodes[0] else {
+            panic!("expected opcode");
+        };
+        assert_eq!(op.opcode.description, "adds");
+
+        let rl = RainlangDocument::parse(text, None);
+        assert_eq!(rl.ast[0].lines.len(), 2);
+        assert_eq!(rl.problems, expected_problems);
+        let Node::Opcode(op) = &rl.ast[0].lines[0].nodes[0] else {
+            panic!("expected opcode");
+        };
+        assert!(op.opcode.description.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_keyword_literals() -> anyhow::Result<()> {
+        let options = ParseOptions::default().with_keyword_literals(&HashMap::from([
+            ("true".to_owned(), "1".to_owned()),
+            ("false".to_owned(), "0".to_owned()),
+            ("max".to_owned(), "0xff".to_owned()),
+        ]))?;
+        let text = "_ _ _: true max maybe;";
+        let rl = RainlangDocument::create(text.to_owned(), &HashMap::new(), None, &options);
+        let expected_nodes = vec![
+            Node::Literal(Literal {
+                value: "1".to_owned(),
+                position: [7, 11],
+                lhs_alias: None,
+                id: Some("true".to_owned()),
+            }),
+            Node::Literal(Literal {
+                value: "255".to_owned(),
+                position: [12, 15],
+                lhs_alias: None,
+                id: Some("max".to_owned()),
+            }),
+        ];
+        assert_eq!(rl.ast[0].lines[0].nodes[..2], expected_nodes);
+        // unknown keywords are resolved as identifiers
+        assert_eq!(
+            rl.problems,
+            vec![ErrorCode::UndefinedWord.to_problem(vec!["maybe"], [16, 21])]
+        );
+
+        // not keyword literals by default
+        let rl = RainlangDocument::parse(text, None);
+        assert!(rl
+            .problems
+            .contains(&ErrorCode::UndefinedWord.to_problem(vec!["true"], [7, 11])));
+
+        let result = ParseOptions::default()
+            .with_keyword_literals(&HashMap::from([("bad".to_owned(), "abc".to_owned())]));
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_opcode_aliases() -> anyhow::Result<()> {
+        let words = AuthoringMeta(vec![AuthoringMetaItem {
+            word: "add".to_owned(),
+            operand_parser_offset: 0,
+            description: "adds".to_owned(),
+        }]);
+        let options = ParseOptions::default().with_opcode_aliases(&HashMap::from([
+            ("plus".to_owned(), "add".to_owned()),
+            ("minus".to_owned(), "sub".to_owned()),
+        ]))?;
+        let text = "_: add(1 2),\n_: plus(1 2),\n_: minus(1 2);";
+        let rl = RainlangDocument::create(text.to_owned(), &HashMap::new(), Some(&words), &options);
+        let opcode = |i: usize| {
+            let Node::Opcode(op) = &rl.ast[0].lines[i].nodes[0] else {
+                panic!("expected opcode");
+            };
+            op.opcode.clone()
+        };
+        assert_eq!(opcode(0).description, "adds");
+        assert_eq!(opcode(0).canonical, None);
+        assert_eq!(opcode(1).name, "plus");
+        assert_eq!(opcode(1).description, "adds");
+        assert_eq!(opcode(1).canonical, Some("add".to_owned()));
+        assert_eq!(opcode(2).canonical, None);
+        assert_eq!(
+            rl.problems,
+            vec![ErrorCode::UndefinedOpcode.to_problem(vec!["minus", "sub"], [30, 35])]
+        );
+
+        // aliases are not resolved by default
+        let rl = RainlangDocument::parse(text, Some(&words));
+        assert!(rl.problems.is_empty());
+
+        let result = ParseOptions::default()
+            .with_opcode_aliases(&HashMap::from([("+".to_owned(), "add".to_owned())]));
+        assert!(result.is_err());
+
+        // aliases declared by the authoring meta, the ones of the parse options override them
+        let item = |word: &str, description: &str| AuthoringMetaItem {
+            word: word.to_owned(),
+            operand_parser_offset: 0,
+            description: description.to_owned(),
+        };
+        let words = AuthoringMeta(vec![
+            item("add", "adds"),
+            item("sub", "subtracts"),
+            item("plus", "Alias of: `add`. Same as add."),
+            item("minus", "alias of add"),
+            item("mul", "Alias of the multiplication of inputs"),
+            item("times", "alias of: mult"),
+        ]);
+        let options = ParseOptions::default()
+            .with_opcode_aliases(&HashMap::from([("minus".to_owned(), "sub".to_owned())]))?;
+        let text = "_: plus(1 2),\n_: minus(1 2),\n_: mul(1 2),\n_: times(1 2);";
+        let rl = RainlangDocument::create(text.to_owned(), &HashMap::new(), Some(&words), &options);
+        let opcode = |i: usize| {
+            let Node::Opcode(op) = &rl.ast[0].lines[i].nodes[0] else {
+                panic!("expected opcode");
+            };
+            op.opcode.clone()
+        };
+        assert_eq!(opcode(0).canonical, Some("add".to_owned()));
+        assert_eq!(opcode(0).description, "adds");
+        assert_eq!(opcode(1).canonical, Some("sub".to_owned()));
+        assert_eq!(opcode(1).description, "subtracts");
+        assert_eq!(opcode(2).canonical, None);
+        assert_eq!(
+            opcode(2).description,
+            "Alias of the multiplication of inputs"
+        );
+        let position = text.find("times").unwrap();
+        assert_eq!(
+            rl.problems,
+            vec![ErrorCode::UndefinedOpcode
+                .to_problem(vec!["times", "mult"], [position, position + 5])]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unbalanced_parens_positions() -> anyhow::Result<()> {
+        let parse = |text: &str| {
+            RainlangDocument::create(
+                text.to_owned(),
+                &HashMap::new(),
+                None,
+                &ParseOptions::default(),
+            )
+            .problems
+        };
+
+        // only the outer opener is left unclosed
+        assert_eq!(
+            parse("_: add(1 sub(2 3);"),
+            vec![ErrorCode::ExpectedClosingParen.to_problem(vec![], [6, 7])]
+        );
+
+        // both outer openers are left unclosed, the innermost one is closed
+        assert_eq!(
+            parse("_: add(sub(1 2 mul(3);"),
+            vec![
+                ErrorCode::ExpectedClosingParen.to_problem(vec![], [6, 7]),
+                ErrorCode::ExpectedClosingParen.to_problem(vec![], [10, 11]),
+            ]
+        );
+
+        // each extra closer is reported at its own offset
+        assert_eq!(
+            parse("_ _: add(sub(1 2))) mul(1));"),
+            vec![
+                ErrorCode::UnexpectedClosingParen.to_problem(vec![], [18, 19]),
+                ErrorCode::UnexpectedClosingParen.to_problem(vec![], [26, 27]),
+            ]
+        );
+
+        // balanced
+        assert!(parse("_: add(sub(1 2) mul(3));").is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_trailing_empty_lines() -> anyhow::Result<()> {
+        let codes = |text: &str, options: &ParseOptions| -> Vec<ErrorCode> {
+            RainlangDocument::create(text.to_owned(), &HashMap::new(), None, options)
+                .problems
+                .iter()
+                .map(|v| v.code)
+                .collect()
+        };
+        let strict = ParseOptions::default();
+        let lenient = ParseOptions::default().with_lenient(true);
+
+        // trailing comma
+        let text = "_: 1,\n_: 2,\n;";
+        assert_eq!(codes(text, &strict), vec![ErrorCode::InvalidEmptyLine]);
+        assert!(codes(text, &lenient).is_empty());
+        let rl = RainlangDocument::create(text.to_owned(), &HashMap::new(), None, &lenient);
+        assert_eq!(rl.ast[0].lines.len(), 2);
+
+        // trailing empty source
+        let text = "_: 1;\n\n;";
+        assert_eq!(codes(text, &strict), vec![ErrorCode::InvalidEmptyBinding]);
+        assert!(codes(text, &lenient).is_empty());
+        let rl = RainlangDocument::create(text.to_owned(), &HashMap::new(), None, &lenient);
+        assert_eq!(rl.ast.len(), 1);
+
+        // only the trailing ones are dropped
+        let text = "_: 1,,\n_: 2;";
+        assert_eq!(codes(text, &lenient), vec![ErrorCode::InvalidEmptyLine]);
+        assert_eq!(codes(";", &lenient), vec![ErrorCode::InvalidEmptyBinding]);
+        assert_eq!(codes(",;", &lenient), vec![ErrorCode::InvalidEmptyLine]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_tabs() -> anyhow::Result<()> {
+        let text = "_:\tadd(1\t2);";
+        let rl = RainlangDocument::create(
+            text.to_owned(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+        assert!(rl.problems.is_empty());
+
+        let options = ParseOptions::default().with_lint_tabs(true);
+        let rl = RainlangDocument::create(text.to_owned(), &HashMap::new(), None, &options);
+        let expected = vec![
+            ErrorCode::TabCharacter.to_problem(vec![], [2, 3]),
+            ErrorCode::TabCharacter.to_problem(vec![], [8, 9]),
+        ];
+        assert_eq!(rl.problems, expected);
+        assert!(rl.problems.iter().all(|v| v.code.is_warning()));
+        assert_eq!(rl.ast[0].lines[0].nodes.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_operand_arg_at() -> anyhow::Result<()> {
+        let text = "_: add<12 0x3f>(1 2);";
+        let rl = RainlangDocument::create(
+            text.to_owned(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+        let Node::Opcode(op) = &rl.ast[0].lines[0].nodes[0] else {
+            panic!("expected an opcode");
+        };
+        let first = text.find("12").unwrap();
+        let second = text.find("0x3f").unwrap();
+        assert_eq!(op.operand_arg_at(first).map(|v| v.position[0]), Some(first));
+        assert_eq!(
+            op.operand_arg_at(first + 1).map(|v| v.position[0]),
+            Some(first)
+        );
+        assert_eq!(
+            op.operand_arg_at(second + 3).map(|v| v.position[0]),
+            Some(second)
+        );
+        assert!(op.operand_arg_at(first + 2).is_none());
+        assert!(op.operand_arg_at(text.find('(').unwrap()).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_placeholder_aliases() -> anyhow::Result<()> {
+        let rl = RainlangDocument::create(
+            "_ a: 1 2,\n_ b: 3 a,\n_: b;".to_owned(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+        assert!(rl.problems.is_empty());
+        let placeholders: Vec<_> = rl.ast[0]
+            .lines
+            .iter()
+            .flat_map(|v| v.aliases.iter().map(|alias| alias.is_placeholder()))
+            .collect();
+        assert_eq!(placeholders, vec![true, false, true, false, true]);
+        // placeholders are neither duplicates nor unused
+        assert!(rl.validate_unused_aliases().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quotes() -> anyhow::Result<()> {
+        let text = "_: call<'a>(add<'b.c 1>(1) call<'d>()),\n_: sub<2 e>(1);";
+        let rl = RainlangDocument::create(
+            text.to_owned(),
+            &HashMap::new(),
+            None,
+            &ParseOptions::default(),
+        );
+        let quotes: Vec<_> = rl
+            .quotes()
+            .into_iter()
+            .map(|(name, position)| (name, &text[position[0]..position[1]]))
+            .collect();
+        assert_eq!(
+            quotes,
+            vec![
+                ("a".to_owned(), "'a"),
+                ("b.c".to_owned(), "'b.c"),
+                ("d".to_owned(), "'d"),
+            ]
+        );
+
+        Ok(())
+    }
 }
@@ -1,9 +1,13 @@
 //! All data types of RainDocument/RainlangDocument parse tree
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use rain_metadata::types::authoring::v1::AuthoringMeta;
 use super::super::error::ErrorCode;
 use serde::{Serialize, Deserialize};
-use super::super::parser::{rainlangdocument::RainlangDocument, raindocument::RainDocument};
+use super::super::parser::{
+    line_offsets, rainlangdocument::RainlangDocument, raindocument::RainDocument,
+    ParseOptionsSnapshot,
+};
 
 #[cfg(feature = "js-api")]
 use tsify::Tsify;
@@ -26,6 +30,105 @@ pub struct Problem {
     pub code: ErrorCode,
 }
 
+impl Problem {
+    /// Max number of lines of a multi-line span that are rendered, longer spans are truncated
+    const MAX_RENDERED_LINES: usize = 4;
+
+    /// Renders this problem as an annotated snippet of the given text (the text this problem
+    /// was reported for) in a compiler errors style, i.e. the message and location header
+    /// followed by the spanned source lines with their covered parts underlined by carets,
+    /// spans that cover too many lines are truncated to their first and last lines
+    pub fn render(&self, text: &str, uri: &str) -> String {
+        let clamp = |offset: usize| {
+            let mut offset = offset.min(text.len());
+            while !text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+            offset
+        };
+        let start = clamp(self.position[0]);
+        let end = clamp(self.position[1]).max(start);
+
+        // byte offsets of the lines' starts, including the empty last line of a text that is
+        // empty or ends with a line break, which the span may still point at
+        let mut line_starts = line_offsets(text);
+        if text.is_empty() || text.ends_with('\n') {
+            line_starts.push(text.len());
+        }
+        let line_of = |offset: usize| line_starts.partition_point(|v| *v <= offset) - 1;
+        let line_text = |line: usize| {
+            let line_end = line_starts.get(line + 1).map_or(text.len(), |v| v - 1);
+            text[line_starts[line]..line_end].trim_end_matches('\r')
+        };
+        let column = |line: usize, offset: usize| text[line_starts[line]..offset].chars().count();
+
+        let start_line = line_of(start);
+        // a span that ends right at a line start does not cover that line
+        let end_line = if end > start {
+            line_of(end - 1)
+        } else {
+            start_line
+        };
+
+        let level = if self.code.is_warning() {
+            "warning"
+        } else {
+            "error"
+        };
+        let width = (end_line + 1).to_string().len();
+        let pad = " ".repeat(width);
+        let mut rendered = vec![
+            format!("{}[{:?}]: {}", level, self.code, self.msg),
+            format!(
+                "{}--> {}:{}:{}",
+                pad,
+                uri,
+                start_line + 1,
+                column(start_line, start) + 1
+            ),
+            format!("{} |", pad),
+        ];
+
+        let mut lines: Vec<Option<usize>> = (start_line..=end_line).map(Some).collect();
+        if lines.len() > Self::MAX_RENDERED_LINES {
+            let half = Self::MAX_RENDERED_LINES / 2;
+            lines.splice(half..lines.len() - half, [None]);
+        }
+        for line in lines {
+            let Some(line) = line else {
+                rendered.push("...".to_owned());
+                continue;
+            };
+            let content = line_text(line);
+            let content_len = content.chars().count();
+            let from = if line == start_line {
+                column(line, start).min(content_len)
+            } else {
+                0
+            };
+            let to = if line == end_line {
+                column(line, end).min(content_len)
+            } else {
+                content_len
+            };
+            // keep the tabs so the carets stay aligned with the content
+            let indent: String = content
+                .chars()
+                .take(from)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            rendered.push(format!("{:>width$} | {}", line + 1, content, width = width));
+            rendered.push(format!(
+                "{} | {}{}",
+                pad,
+                indent,
+                "^".repeat(to.saturating_sub(from).max(1))
+            ));
+        }
+        rendered.join("\n")
+    }
+}
+
 /// Type for AST Value node
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -48,6 +151,10 @@ pub struct OpcodeDetails {
     pub name: String,
     pub description: String,
     pub position: Offsets,
+    /// the canonical word of the opcode if it is referenced by one of its aliases
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "js-api", tsify(optional))]
+    pub canonical: Option<String>,
 }
 
 /// Type of an individual opcode's operand arguments
@@ -69,6 +176,31 @@ pub struct OperandArg {
     pub args: Vec<OperandArgItem>,
 }
 
+/// Type of the operand args schema of a word, i.e. the names of the operand args it takes in
+/// order and how many of them (from the start) are required, the rest have default values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperandArgsSchema {
+    pub names: &'static [&'static str],
+    pub required: usize,
+}
+
+impl OperandArgsSchema {
+    /// The schema of the given operand parser offset of an authoring meta word, i.e. one of the
+    /// native parser's operand parsers (disallowed, single full, double per byte without default,
+    /// m1 m1 and 8 m1 m1), None for unknown offsets as their args cannot be validated
+    pub fn of_operand_parser(operand_parser_offset: u8) -> Option<OperandArgsSchema> {
+        let (names, required): (&'static [&'static str], usize) = match operand_parser_offset {
+            0x00 => (&[], 0),
+            0x10 => (&["value"], 0),
+            0x20 => (&["first-byte", "second-byte"], 2),
+            0x30 => (&["first-bit", "second-bit"], 0),
+            0x40 => (&["byte", "first-bit", "second-bit"], 1),
+            _ => return None,
+        };
+        Some(OperandArgsSchema { names, required })
+    }
+}
+
 /// Type for AST Opcode node
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -88,6 +220,51 @@ pub struct Opcode {
     pub operand_args: Option<OperandArg>,
 }
 
+impl Opcode {
+    /// The operand value of this opcode, None if it has not been resolved
+    pub fn resolved_operand(&self) -> Option<u8> {
+        self.operand
+    }
+
+    /// The operand arg of this opcode whose position contains the given offset, i.e. the
+    /// offset is at or after its start and before its end, None if there is no such arg
+    pub fn operand_arg_at(&self, offset: usize) -> Option<&OperandArgItem> {
+        self.operand_args
+            .as_ref()?
+            .args
+            .iter()
+            .find(|arg| arg.position[0] <= offset && offset < arg.position[1])
+    }
+}
+
+/// Type of an opcode's accepted number of inputs, i.e. the min and the max (unbounded if
+/// not specified, for variadic opcodes) number of inputs
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct InputsRange {
+    pub min: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "js-api", tsify(optional))]
+    pub max: Option<usize>,
+}
+
+impl InputsRange {
+    /// Checks if the given number of inputs is within this range
+    pub fn contains(&self, count: usize) -> bool {
+        count >= self.min && count <= self.max.unwrap_or(usize::MAX)
+    }
+}
+
+impl std::fmt::Display for InputsRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{}", max),
+            Some(max) => write!(f, "{} to {}", self.min, max),
+            None => write!(f, "at least {}", self.min),
+        }
+    }
+}
+
 /// Type for AST Alias node
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -100,6 +277,14 @@ pub struct Alias {
     pub lhs_alias: Option<Vec<Alias>>,
 }
 
+impl Alias {
+    /// Determines if this alias is the `_` placeholder, i.e. it only takes up a stack item
+    /// without naming it, so it cannot be referenced and is not checked for duplicates
+    pub fn is_placeholder(&self) -> bool {
+        self.name == "_"
+    }
+}
+
 /// Type of a parsed comment
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
@@ -108,7 +293,36 @@ pub struct Comment {
     pub position: Offsets,
 }
 
-/// Type of an import configurations (renames/rebindings)
+/// Kinds of lexical tokens of a rainlang text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum TokenKind {
+    /// words, aliases, quotes and namespace paths
+    Word,
+    /// numeric literals
+    Number,
+    /// string literals
+    StringLiteral,
+    /// sub parser literals
+    SubParserLiteral,
+    OpenParen,
+    CloseParen,
+    /// ":", ",", ";", "<" and ">"
+    Operator,
+    Comment,
+    /// any other non-whitespace character sequence
+    Unknown,
+}
+
+/// Type of a lexical token of a rainlang text
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Token {
+    pub kind: TokenKind,
+    pub position: Offsets,
+}
+
+/// Type of an import configurations (renames/rebindings/elisions/re-exports)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
 pub struct ImportConfiguration {
@@ -116,13 +330,48 @@ pub struct ImportConfiguration {
     pub groups: Vec<(ParsedItem, Option<ParsedItem>)>,
 }
 
-/// Type of an import meta sequence
+/// Where an import's meta was resolved from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum MetaSource {
+    /// the meta was already cached in the Store
+    #[default]
+    Cache,
+    /// the meta was found by remote meta search
+    Remote,
+}
+
+/// Type of an import meta sequence, it is only built by parsing, so it is non exhaustive for
+/// more details of the resolved meta to be added without breaking the dependents
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+#[non_exhaustive]
 pub struct ImportSequence {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "js-api", tsify(type = "IRainDocument", optional))]
     pub dotrain: Option<RainDocument>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "serde_bytes")]
+    #[cfg_attr(feature = "js-api", tsify(type = "Uint8Array", optional))]
+    pub meta: Option<Vec<u8>>,
+    #[serde(default)]
+    pub source: MetaSource,
+}
+
+/// Type of an entry of a RainDocument's dependency manifest, i.e. an import's declared name
+/// and hash, the magic numbers (by their names, such as `dotrain-v1`) of the meta it resolved
+/// to and where the meta was resolved from, the magics and source are empty/None if the import
+/// was not resolved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct DependencyEntry {
+    pub name: String,
+    pub hash: String,
+    pub magics: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "js-api", tsify(optional))]
+    pub source: Option<MetaSource>,
 }
 
 /// Type of import statements specified in a RainDocument
@@ -182,6 +431,14 @@ pub struct RainlangLine {
     pub aliases: Vec<Alias>,
 }
 
+impl RainlangLine {
+    /// Slices this line's text out of the given content that this line was parsed from
+    /// (i.e. the binding content), returns None if the position is out of the content bounds
+    pub fn text<'a>(&self, content: &'a str) -> Option<&'a str> {
+        content.get(self.position[0]..self.position[1])
+    }
+}
+
 /// Type of a Rainlang Source (delimited by ";")
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
@@ -190,6 +447,14 @@ pub struct RainlangSource {
     pub position: Offsets,
 }
 
+impl RainlangSource {
+    /// Slices this source's text out of the given content that this source was parsed from
+    /// (i.e. the binding content), returns None if the position is out of the content bounds
+    pub fn text<'a>(&self, content: &'a str) -> Option<&'a str> {
+        content.get(self.position[0]..self.position[1])
+    }
+}
+
 /// Type of a Rainlang parse tree
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
@@ -227,6 +492,19 @@ pub enum BindingItem {
     Quote(QuoteBindingItem),
 }
 
+impl BindingItem {
+    /// The name of this binding item's type, i.e. one of "elided", "constant", "expression"
+    /// or "quote"
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            BindingItem::Elided(_) => "elided",
+            BindingItem::Literal(_) => "constant",
+            BindingItem::Exp(_) => "expression",
+            BindingItem::Quote(_) => "quote",
+        }
+    }
+}
+
 /// Type for a binding (named expressions)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -241,6 +519,17 @@ pub struct Binding {
     pub item: BindingItem,
 }
 
+impl Binding {
+    /// Maps the given offsets that are relative to this binding's content (such as
+    /// positions of an expression binding's parsed nodes) to the parent document offsets
+    pub fn map_to_document(&self, inner: Offsets) -> Offsets {
+        [
+            inner[0] + self.content_position[0],
+            inner[1] + self.content_position[0],
+        ]
+    }
+}
+
 /// Type for a namespace leaf
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -480,3 +769,56 @@ impl NamespaceItem {
 /// Type for a namespace in dotrain
 #[cfg_attr(feature = "js-api", tsify::declare)]
 pub type Namespace = HashMap<String, NamespaceItem>;
+
+/// Type of the semantic differences between two RainDocuments, bindings and imports are
+/// identified by their names and namespace items by their dotted paths, pure position shifts
+/// are not considered as changes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct DocumentDiff {
+    pub added_bindings: Vec<String>,
+    pub removed_bindings: Vec<String>,
+    pub modified_bindings: Vec<String>,
+    pub added_imports: Vec<String>,
+    pub removed_imports: Vec<String>,
+    pub modified_imports: Vec<String>,
+    pub added_namespace: Vec<String>,
+    pub removed_namespace: Vec<String>,
+    pub modified_namespace: Vec<String>,
+}
+
+impl DocumentDiff {
+    /// Checks if there are no differences at all
+    pub fn is_empty(&self) -> bool {
+        self.added_bindings.is_empty()
+            && self.removed_bindings.is_empty()
+            && self.modified_bindings.is_empty()
+            && self.added_imports.is_empty()
+            && self.removed_imports.is_empty()
+            && self.modified_imports.is_empty()
+            && self.added_namespace.is_empty()
+            && self.removed_namespace.is_empty()
+            && self.modified_namespace.is_empty()
+    }
+}
+
+/// Type of a fully resolved snapshot of a consumable RainDocument, i.e. its text, resolved
+/// namespace, bindings and imports alongside the meta bytes of all of its imports and their nested
+/// imports (keyed by their hashes), its authoring meta and the parse options it was parsed with,
+/// it can be composed without any further meta search, see [RainDocument::freeze]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub struct FrozenDocument {
+    pub text: String,
+    pub namespace: Namespace,
+    pub bindings: Vec<Binding>,
+    pub imports: Vec<Import>,
+    pub metas: BTreeMap<String, Vec<u8>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "js-api", tsify(optional, type = "IAuthoringMeta"))]
+    pub known_words: Option<AuthoringMeta>,
+    #[serde(default)]
+    pub options: ParseOptionsSnapshot,
+}
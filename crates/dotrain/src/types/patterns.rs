@@ -1,7 +1,12 @@
 //! All Regular Expression patterns used for parsing texts
 
 use regex::Regex;
+use crate::error::Error;
 use once_cell::sync::Lazy;
+use serde::{Serialize, Deserialize};
+
+#[cfg(feature = "js-api")]
+use tsify::Tsify;
 
 /// pragma keyword in rainlang
 pub const PRAGMA_KEYWORD: &str = "using-words-from";
@@ -9,6 +14,18 @@ pub const PRAGMA_KEYWORD: &str = "using-words-from";
 /// reserved keywords in rainlang
 pub const KEYWORDS: [&str; 1] = [PRAGMA_KEYWORD];
 
+/// word set pragma keyword in dotrain, pins the authoring meta that the expressions of a
+/// document are parsed with, this is distinct from [PRAGMA_KEYWORD] which is a rainlang pragma
+pub const WORD_SET_PRAGMA_KEYWORD: &str = "word-set";
+
+/// version pragma keyword in dotrain, states the version of the dotrain format a document is
+/// written in
+pub const VERSION_PRAGMA_KEYWORD: &str = "version";
+
+/// known pragma keywords in dotrain, pragma statements with any other keyword are handled by
+/// the [UnknownPragmaPolicy]
+pub const DOTRAIN_PRAGMA_KEYWORDS: [&str; 2] = [WORD_SET_PRAGMA_KEYWORD, VERSION_PRAGMA_KEYWORD];
+
 /// front matter separator
 pub const FRONTMATTER_SEPARATOR: &str = "---";
 
@@ -18,6 +35,10 @@ pub static ILLEGAL_CHAR: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^ -~\s]+").unw
 /// word pattern
 pub static WORD_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-z][0-9a-z-]*$").unwrap());
 
+/// version pragma value pattern, a major version optionally followed by minor and patch versions
+pub static VERSION_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[0-9]+(\.[0-9]+){0,2}$").unwrap());
+
 /// Import hash pattern
 pub static HASH_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^0x[0-9a-fA-F]{64}$").unwrap());
 
@@ -54,6 +75,9 @@ pub static LITERAL_PATTERN: Lazy<Regex> = Lazy::new(|| {
 /// Hex pattern
 pub static HEX_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^0x[0-9a-fA-F]+$").unwrap());
 
+/// Binary pattern
+pub static BINARY_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^0b[01]+$").unwrap());
+
 /// e numberic pattern
 pub static E_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^[1-9][0-9]*(\.[0-9]+)?e-?[0-9]+$").unwrap());
@@ -85,10 +109,12 @@ pub static BINDING_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new("#").unwrap())
 /// non-empty text pattern
 pub static NON_EMPTY_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^\s]").unwrap());
 
-/// operand arguments pattern (literal + namespace + quoted namespace)
+/// operand arguments pattern (literal + binary + namespace + quoted namespace)
 pub static OPERAND_ARG_PATTERN: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
         (HEX_PATTERN.as_str().to_string()
+            + "|"
+            + BINARY_PATTERN.as_str()
             + "|"
             + INT_PATTERN.as_str()
             + "|"
@@ -129,10 +155,113 @@ pub static LHS_PATTERN: Lazy<Regex> = Lazy::new(|| {
     Regex::new((LHS_WORD_PATTERN.as_str().to_string() + "|" + "^_$").as_str()).unwrap()
 });
 
+/// authoring meta opcode alias pattern, i.e. an opcode description whose first sentence is
+/// `alias of:` followed by the canonical word, authoring meta has no dedicated field for aliases
+pub static META_ALIAS_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*(?i:alias of):?\s*`?([a-z][0-9a-z-]*)`?\s*(?:[.,;\n]|$)").unwrap()
+});
+
 /// pragma pattern (keyword + ws + hex)
 pub static PRAGMA_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(:?(^|\s))using-words-from(:?($|\s))").unwrap());
 
+/// A comment syntax of a dialect
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum CommentStyle {
+    /// comments that span from the open delimiter up until the close delimiter, such as `/* */`
+    Block { open: String, close: String },
+    /// comments that span from the prefix up until the end of the line, such as `//`
+    Line { prefix: String },
+}
+
+impl CommentStyle {
+    /// the regex pattern of this comment style, not ended block comments span up until the end
+    pub(crate) fn pattern(&self) -> String {
+        match self {
+            CommentStyle::Block { open, close } => format!(
+                r"{}[\s\S]*?(?:{}|$)",
+                regex::escape(open),
+                regex::escape(close)
+            ),
+            CommentStyle::Line { prefix } => format!(r"{}[^\n]*", regex::escape(prefix)),
+        }
+    }
+}
+
+/// How the pragma statements with unknown keywords at top of a document (before its first
+/// import or binding) are handled, known pragmas (see [DOTRAIN_PRAGMA_KEYWORDS]) are always validated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "js-api", derive(Tsify), tsify(into_wasm_abi, from_wasm_abi))]
+pub enum UnknownPragmaPolicy {
+    /// reported as [ErrorCode::UndefinedPragma](crate::error::ErrorCode::UndefinedPragma) errors
+    #[default]
+    Error,
+    /// reported as [ErrorCode::UnknownPragma](crate::error::ErrorCode::UnknownPragma) warnings
+    Warn,
+    /// taken out of the text without being reported
+    Ignore,
+}
+
+/// Identifier pattern used for validating binding names and lhs aliases, defaults
+/// to [WORD_PATTERN] and can be overriden for dialects with different identifier rules
+#[derive(Debug, Clone)]
+pub struct WordPattern {
+    regex: Regex,
+}
+
+impl WordPattern {
+    /// Compiles the given regex as a word pattern, the pattern is anchored to match
+    /// the whole identifier, so it should not include `^` and `$` itself
+    pub fn new(pattern: &str) -> Result<WordPattern, Error> {
+        Regex::new(&format!("^(?:{})$", pattern))
+            .map(|regex| WordPattern { regex })
+            .map_err(Error::InvalidWordPatternRegex)
+    }
+
+    /// Wraps the given already anchored regex as a word pattern
+    pub(crate) fn from_regex(regex: Regex) -> WordPattern {
+        WordPattern { regex }
+    }
+
+    /// The compiled regex of this word pattern
+    pub fn as_regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    /// Checks if the given text is a valid identifier
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+
+    /// Checks if the given text is a valid lhs alias, i.e. an identifier
+    /// optionally prefixed with `_`, or a single `_`
+    pub fn is_lhs_match(&self, text: &str) -> bool {
+        text == "_" || self.is_lhs_word_match(text)
+    }
+
+    /// Checks if the given text is an identifier optionally prefixed with `_`
+    pub fn is_lhs_word_match(&self, text: &str) -> bool {
+        text != "_" && self.is_match(text.strip_prefix('_').unwrap_or(text))
+    }
+}
+
+impl Default for WordPattern {
+    fn default() -> Self {
+        WordPattern {
+            regex: WORD_PATTERN.clone(),
+        }
+    }
+}
+
+impl PartialEq for WordPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+    }
+}
+
 /// the default elided binding msg
 pub static DEFAULT_ELISION: &str = "elided binding, requires rebinding";
 
@@ -339,6 +468,7 @@ mod tests {
             "'.asd12-wer.jh45-iu78.lk9",
             "123.123",
             "123.12e2",
+            "0b101",
         ] {
             assert!(
                 OPERAND_ARG_PATTERN.is_match(i),
@@ -424,4 +554,31 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_word_pattern() -> anyhow::Result<()> {
+        let default = WordPattern::default();
+        assert!(default.is_match("some-word"));
+        assert!(!default.is_match("$word"));
+        assert!(default.is_lhs_match("_"));
+        assert!(default.is_lhs_match("_some-word"));
+        assert!(!default.is_lhs_word_match("_"));
+        assert!(!default.is_lhs_match("__word"));
+
+        let custom = WordPattern::new(r"[a-z$][0-9a-z$-]*")?;
+        assert!(custom.is_match("$word"));
+        assert!(custom.is_match("some$word"));
+        assert!(!custom.is_match("some word"));
+        assert!(custom.is_lhs_match("_$word"));
+        assert_ne!(custom, default);
+
+        let result = WordPattern::new(r"[a-z");
+        assert!(matches!(result, Err(Error::InvalidWordPatternRegex(_))));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("invalid word pattern: "));
+
+        Ok(())
+    }
 }
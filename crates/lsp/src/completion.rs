@@ -1,5 +1,6 @@
 use regex::Regex;
-use super::OffsetAt;
+use serde_json::json;
+use super::{OffsetAt, PositionAt};
 use once_cell::sync::Lazy;
 use alloy_primitives::hex;
 use std::collections::VecDeque;
@@ -19,22 +20,37 @@ use dotrain::{
 
 static META_COMPLETION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^0?x").unwrap());
 static TRIGGERS: Lazy<Regex> = Lazy::new(|| Regex::new(r"[a-zA-Z0-9-.']").unwrap());
-static TRIGGERS_PATH: Lazy<Regex> = Lazy::new(|| Regex::new(r"[a-zA-Z0-9-.'\/]").unwrap());
 
-/// Provides completion items for the given RainDocument at the given Position
+/// kinds of completion items whose documentation is deferred to the resolve step, carried in
+/// the items' data along with the value and format to render the documentation from
+const OPCODE_DATA: &str = "opcode";
+const EXPRESSION_BINDING_DATA: &str = "expression-binding";
+
+/// completion items groups in order of their ranking, used as sort text prefixes
+const ALIAS_GROUP: u8 = 0;
+const OPCODE_GROUP: u8 = 1;
+const BINDING_GROUP: u8 = 2;
+const NAMESPACE_GROUP: u8 = 3;
+const META_GROUP: u8 = 4;
+
+/// Provides completion items for the given RainDocument at the given Position ranked by their
+/// sort text (stack aliases, opcodes, local bindings, namespace members and then imported metas),
+/// capped at `max_completions` items if specified
 pub fn get_completion(
     rain_document: &RainDocument,
     uri: &Url,
     position: Position,
     documentation_format: MarkupKind,
+    max_completions: Option<usize>,
 ) -> Option<Vec<CompletionItem>> {
-    let target_offset = rain_document.text().offset_at(&position);
-    if target_offset < rain_document.front_matter_offset() + 3 {
+    let target_offset = rain_document.offset_at(&position);
+    if target_offset < rain_document.front_matter_offset() + 3
+        || rain_document.is_in_comment(target_offset)
+    {
         return None;
     }
     let lookahead = rain_document
-        .text()
-        .get(target_offset..target_offset + 1)
+        .span_text([target_offset, target_offset + 1])
         .unwrap_or("");
 
     let mut result = VecDeque::new();
@@ -45,11 +61,30 @@ pub fn get_completion(
             .find(|v| v.position[0] <= target_offset && v.position[1] >= target_offset)
         {
             let pretext = rain_document
-                .text()
-                .get(import.position[0]..rain_document.text().offset_at(&position))?;
-            let chunks = exclusive_parse(pretext, &WS_PATTERN, 0, false);
+                .span_text([import.position[0], rain_document.offset_at(&position)])?;
+            let mut chunks = exclusive_parse(pretext, &WS_PATTERN, 0, false);
+            // a whitespace right before the cursor means a new chunk is being started
+            if pretext.ends_with(char::is_whitespace) {
+                chunks.push(ParsedItem(String::new(), [pretext.len(), pretext.len()]));
+            }
             if let Some(configurations) = &import.configuration {
-                if configurations
+                // the cursor is on the value of a rebinding pair, i.e. at the end of a typed
+                // value or after the whitespace that follows a member name with no value yet
+                let on_rebinding_value = configurations.groups.iter().any(|v| {
+                    !v.0 .0.starts_with('\'')
+                        && match &v.1 {
+                            Some(value) => value.1[1] == target_offset,
+                            None => {
+                                v.0 .1[1] < target_offset && pretext.ends_with(char::is_whitespace)
+                            }
+                        }
+                });
+                if on_rebinding_value {
+                    result.extend(get_constant_completions(
+                        rain_document,
+                        documentation_format.clone(),
+                    ));
+                } else if configurations
                     .groups
                     .iter()
                     .any(|v| v.0 .1[1] == target_offset)
@@ -57,18 +92,20 @@ pub fn get_completion(
                     let prefix = get_prefix(pretext, &TRIGGERS);
                     if let Some(ImportSequence {
                         dotrain: Some(raindoc),
+                        ..
                     }) = &import.sequence
                     {
                         if WORD_PATTERN.is_match(&prefix) {
                             result.extend(get_namespace_completions(
                                 raindoc.namespace(),
                                 documentation_format.clone(),
+                                false,
                             ));
                         }
                     }
                 }
             } else if chunks.len() < 4 {
-                let mut last = if chunks.len() == 1 {
+                let last = if chunks.len() == 1 {
                     &chunks[0]
                 } else if chunks.len() == 2 {
                     if HEX_PATTERN.is_match(&chunks[0].0) {
@@ -83,97 +120,55 @@ pub fn get_completion(
                 } else {
                     return None;
                 };
-
-                let temp = ParsedItem(last.0.split_at(1).1.to_owned(), last.1);
-                if last.0.starts_with('@') {
-                    last = &temp
-                }
-                let _prefix = get_prefix(pretext, &TRIGGERS_PATH);
-                // completion items from local path with its equivelant hash that is stored in CAS
-                {
-                    rain_document
-                        .store()
-                        .read()
-                        .unwrap()
-                        .dotrain_cache()
-                        .iter()
-                        .for_each(|v| {
-                            if uri.to_string() != *v.0 {
-                                result.push_front(CompletionItem {
-                                    label: v.0.clone(),
-                                    label_details: Some(CompletionItemLabelDetails {
-                                        description: Some("rain document".to_owned()),
-                                        detail: None,
-                                    }),
-                                    kind: Some(CompletionItemKind::FILE),
-                                    detail: Some(format!("rain document at: {}", v.0,)),
-                                    insert_text: Some(hex::encode_prefixed(v.1)),
-                                    documentation: Some(Documentation::MarkupContent(
-                                        MarkupContent {
-                                            kind: documentation_format.clone(),
-                                            value: hex::encode_prefixed(v.1),
-                                        },
-                                    )),
-                                    text_edit: Some(CompletionTextEdit::Edit(TextEdit {
-                                        new_text: hex::encode_prefixed(v.1),
-                                        range: Range::new(
-                                            Position {
-                                                line: position.line,
-                                                character: position.character
-                                                    - _prefix.len() as u32,
-                                            },
-                                            position,
-                                        ),
-                                    })),
-                                    ..Default::default()
-                                })
-                            }
-                        })
-                }
-                // all cached meta hash in CAS
-                if META_COMPLETION.is_match(&last.0) {
-                    rain_document
-                        .store()
-                        .read()
-                        .unwrap()
-                        .cache()
-                        .iter()
-                        .for_each(|v| {
-                            result.push_front(CompletionItem {
-                                label: "x".to_owned() + &hex::encode(v.0),
-                                label_details: Some(CompletionItemLabelDetails {
-                                    description: Some("meta".to_owned()),
-                                    detail: None,
-                                }),
-                                kind: Some(CompletionItemKind::MODULE),
-                                detail: Some(format!("meta hash: {}", hex::encode_prefixed(v.0))),
-                                insert_text: Some(hex::encode_prefixed(v.0)),
-                                ..Default::default()
-                            })
-                        })
-                }
+                // the text typed so far for the embed, excluding the `@` so it is kept
+                // out of the edit range and is not duplicated or removed by the edit
+                let (typed, typed_offset) = match last.0.strip_prefix('@') {
+                    Some(rest) => (rest, last.1[0] + 1),
+                    None => (last.0.as_str(), last.1[0]),
+                };
+                let range = Range::new(
+                    rain_document.position_at(import.position[0] + typed_offset),
+                    position,
+                );
+                result.extend(get_embed_completions(
+                    rain_document,
+                    uri,
+                    typed,
+                    range,
+                    documentation_format.clone(),
+                ));
+            }
+            result
+                .make_contiguous()
+                .sort_by(|a, b| a.sort_text.cmp(&b.sort_text));
+            if let Some(max) = max_completions {
+                result.truncate(max);
             }
             Some(Vec::from(result))
         } else {
-            let pretext = rain_document.text().get(
-                rain_document.text().offset_at(&Position {
+            let pretext = rain_document.span_text([
+                rain_document.offset_at(&Position {
                     line: position.line,
                     character: 0,
-                })..rain_document.text().offset_at(&position),
-            )?;
+                }),
+                rain_document.offset_at(&position),
+            ])?;
             let mut prefix = get_prefix(pretext, &TRIGGERS);
             let is_quote = prefix.starts_with('\'');
             if is_quote {
                 prefix = prefix.split_at(1).1.to_owned();
             }
             if NAMESPACE_PATTERN.is_match(&prefix) {
-                let offset = rain_document.text().offset_at(&position);
-                if let Some(namespace_node) = search_namespace(&prefix, rain_document.namespace()) {
-                    result.extend(get_namespace_completions(
-                        namespace_node,
-                        documentation_format.clone(),
-                    ));
-                }
+                let offset = rain_document.offset_at(&position);
+                let namespace_completions = search_namespace(&prefix, rain_document.namespace())
+                    .map(|namespace_node| {
+                        get_namespace_completions(
+                            namespace_node,
+                            documentation_format.clone(),
+                            is_quote,
+                        )
+                    })
+                    .unwrap_or_default();
                 if !is_quote {
                     if let Some(am) = &rain_document.known_words() {
                         for v in &am.0 {
@@ -184,21 +179,21 @@ pub fn get_completion(
                                     detail: None,
                                 }),
                                 kind: Some(CompletionItemKind::FUNCTION),
+                                sort_text: Some(sort_text(OPCODE_GROUP, &v.word)),
                                 detail: Some(format!("opcode: {}", v.word)),
                                 insert_text: Some(v.word.clone()),
-                                documentation: Some(Documentation::MarkupContent(MarkupContent {
-                                    kind: documentation_format.clone(),
-                                    value: v.description.clone(),
+                                data: Some(json!({
+                                    "kind": OPCODE_DATA,
+                                    "value": v.description,
+                                    "format": documentation_format,
                                 })),
                                 ..Default::default()
                             })
                         }
                     }
-                    if let Some(binding) = rain_document
-                        .bindings()
-                        .iter()
-                        .find(|v| v.content_position[0] <= offset && v.content_position[1] > offset)
-                    {
+                    if let Some(binding) = rain_document.binding_at(offset).filter(|v| {
+                        v.content_position[0] <= offset && v.content_position[1] > offset
+                    }) {
                         if let BindingItem::Exp(rainlang_doc) = &binding.item {
                             result.push_front(CompletionItem {
                                 label: "using-words-from".to_owned(),
@@ -207,6 +202,7 @@ pub fn get_completion(
                                     detail: None,
                                 }),
                                 kind: Some(CompletionItemKind::KEYWORD),
+                                sort_text: Some(sort_text(OPCODE_GROUP, "using-words-from")),
                                 detail: Some("using-words-from".to_owned()),
                                 insert_text: Some("using-words-from".to_owned()),
                                 documentation: Some(Documentation::MarkupContent(MarkupContent {
@@ -221,12 +217,19 @@ pub fn get_completion(
                                 offset,
                                 rainlang_doc,
                                 binding,
-                                rain_document.text(),
+                                rain_document,
                                 documentation_format.clone(),
                             ));
                         }
                     }
                 }
+                result.extend(namespace_completions);
+                result
+                    .make_contiguous()
+                    .sort_by(|a, b| a.sort_text.cmp(&b.sort_text));
+                if let Some(max) = max_completions {
+                    result.truncate(max);
+                }
                 Some(Vec::from(result))
             } else {
                 None
@@ -237,7 +240,10 @@ pub fn get_completion(
     }
 }
 
-/// Search in a Namespace for a given name
+/// Search in a Namespace for a given name, i.e. the node of the path preceding the name's
+/// final (partially typed) segment, so a name ending with a "." (such as `foo.`) resolves to
+/// the node whose members are to be completed, malformed names (such as empty segments or
+/// paths deeper than 32 segments) are not found
 fn search_namespace<'a>(name: &str, namespace: &'a Namespace) -> Option<&'a Namespace> {
     let mut segments = VecDeque::from(exclusive_parse(name, &NAMESPACE_SEGMENT_PATTERN, 0, true));
     if name.starts_with('.') {
@@ -257,29 +263,92 @@ fn search_namespace<'a>(name: &str, namespace: &'a Namespace) -> Option<&'a Name
     if segments.iter().any(|v| !WORD_PATTERN.is_match(&v.0)) {
         return None;
     }
-    if !segments.is_empty() {
-        if let Some(ns_item) = namespace.get(&segments[0].0) {
-            let mut result = ns_item;
-            for segment in segments.range(1..) {
-                match result {
-                    NamespaceItem::Node(node) => {
-                        result = node.get(&segment.0)?;
-                    }
-                    NamespaceItem::Leaf(_leaf) => {
-                        return None;
-                    }
-                }
+    // a lone "." (or an empty name) is the root namespace
+    let Some(first) = segments.pop_front() else {
+        return Some(namespace);
+    };
+    let mut result = namespace.get(&first.0)?;
+    for segment in &segments {
+        match result {
+            NamespaceItem::Node(node) => {
+                result = node.get(&segment.0)?;
             }
-            match result {
-                NamespaceItem::Leaf(_) => None,
-                NamespaceItem::Node(node) => Some(node),
+            NamespaceItem::Leaf(_leaf) => {
+                return None;
             }
-        } else {
-            None
         }
-    } else {
-        Some(namespace)
     }
+    match result {
+        NamespaceItem::Leaf(_) => None,
+        NamespaceItem::Node(node) => Some(node),
+    }
+}
+
+/// get the completions of an import's embed, i.e. the rain documents cached by their path
+/// (embed by path) whose hash is inserted, unless the typed text is a hash, and the cached
+/// metas (embed by hash) if the typed text is empty or is a hash, all replacing the given range
+fn get_embed_completions(
+    rain_document: &RainDocument,
+    uri: &Url,
+    typed: &str,
+    range: Range,
+    documentation_format: MarkupKind,
+) -> Vec<CompletionItem> {
+    let mut result = vec![];
+    let is_hash = META_COMPLETION.is_match(typed);
+    let store = rain_document.store();
+    let store = store.read().unwrap();
+    if !is_hash {
+        // completion items from local path with its equivelant hash that is stored in CAS
+        store.dotrain_cache().iter().for_each(|v| {
+            if uri.to_string() != *v.0 {
+                let hash = hex::encode_prefixed(v.1);
+                result.push(CompletionItem {
+                    label: v.0.clone(),
+                    label_details: Some(CompletionItemLabelDetails {
+                        description: Some("embed by path".to_owned()),
+                        detail: None,
+                    }),
+                    kind: Some(CompletionItemKind::FILE),
+                    sort_text: Some(sort_text(META_GROUP, v.0)),
+                    detail: Some(format!("rain document at: {}", v.0)),
+                    insert_text: Some(hash.clone()),
+                    documentation: Some(Documentation::MarkupContent(MarkupContent {
+                        kind: documentation_format.clone(),
+                        value: hash.clone(),
+                    })),
+                    text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+                        new_text: hash,
+                        range,
+                    })),
+                    ..Default::default()
+                })
+            }
+        });
+    }
+    if is_hash || typed.is_empty() {
+        // all cached meta hash in CAS
+        store.cache().iter().for_each(|v| {
+            let hash = hex::encode_prefixed(v.0);
+            result.push(CompletionItem {
+                label: hash.clone(),
+                label_details: Some(CompletionItemLabelDetails {
+                    description: Some("embed by hash".to_owned()),
+                    detail: None,
+                }),
+                kind: Some(CompletionItemKind::MODULE),
+                sort_text: Some(sort_text(META_GROUP, &hash)),
+                detail: Some(format!("meta hash: {}", hash)),
+                insert_text: Some(hash.clone()),
+                text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+                    new_text: hash,
+                    range,
+                })),
+                ..Default::default()
+            })
+        });
+    }
+    result
 }
 
 /// Method to get the last set of chars that matches the given pattern
@@ -301,21 +370,21 @@ fn get_rainlang_src_alias_completions(
     offset: usize,
     rainlang_doc: &RainlangDocument,
     binding: &Binding,
-    dotrain_text: &str,
+    rain_document: &RainDocument,
     documentation_format: MarkupKind,
 ) -> Vec<CompletionItem> {
     let mut result = vec![];
     if let Some(src) = rainlang_doc.ast().iter().find(|v| {
-        v.position[0] + binding.content_position[0] <= offset
-            && v.position[1] + binding.content_position[0] > offset
+        let position = binding.map_to_document(v.position);
+        position[0] <= offset && position[1] > offset
     }) {
         if let Some(last_line) = &src.lines.last() {
-            if let Some(item_str) = dotrain_text.get(last_line.position[0]..offset) {
+            if let Some(item_str) = rain_document.span_text([last_line.position[0], offset]) {
                 if item_str.contains(':') {
                     for line in &src.lines {
-                        if line.position[1] + binding.content_position[0] + 1 < offset {
+                        if binding.map_to_document(line.position)[1] + 1 < offset {
                             for alias in &line.aliases {
-                                if alias.name != "_" {
+                                if !alias.is_placeholder() {
                                     result.push(CompletionItem {
                                         label: alias.name.clone(),
                                         label_details: Some(CompletionItemLabelDetails {
@@ -323,6 +392,7 @@ fn get_rainlang_src_alias_completions(
                                             detail: None,
                                         }),
                                         kind: Some(CompletionItemKind::VARIABLE),
+                                        sort_text: Some(sort_text(ALIAS_GROUP, &alias.name)),
                                         detail: Some(format!("stack alias: {}", alias.name)),
                                         insert_text: Some(alias.name.clone()),
                                         documentation: Some(Documentation::MarkupContent(
@@ -344,13 +414,92 @@ fn get_rainlang_src_alias_completions(
     result
 }
 
-// get completion items of a namespace root items
+/// Resolves a completion item provided by [get_completion] by filling in its documentation
+/// that was deferred to this step (opcodes and expression bindings), items that are already
+/// resolved or were not provided by [get_completion] are returned as they are
+pub fn resolve_completion(mut item: CompletionItem) -> CompletionItem {
+    if item.documentation.is_some() {
+        return item;
+    }
+    let Some(data) = &item.data else {
+        return item;
+    };
+    let (Some(kind), Some(value)) = (
+        data.get("kind").and_then(|v| v.as_str()),
+        data.get("value").and_then(|v| v.as_str()),
+    ) else {
+        return item;
+    };
+    let format = data
+        .get("format")
+        .and_then(|v| serde_json::from_value::<MarkupKind>(v.clone()).ok())
+        .unwrap_or(MarkupKind::PlainText);
+    let value = match kind {
+        OPCODE_DATA => super::opcode_documentation(value, &format),
+        EXPRESSION_BINDING_DATA => match format {
+            MarkupKind::Markdown => ["```rainlang", value.trim(), "```"].join("\n"),
+            MarkupKind::PlainText => value.trim().to_string(),
+        },
+        _ => return item,
+    };
+    item.documentation = Some(Documentation::MarkupContent(MarkupContent {
+        kind: format,
+        value,
+    }));
+    item.data = None;
+    item
+}
+
+/// get completion items of the local and imported constant bindings as rebinding values, they
+/// are labeled by their namespace paths and insert their values
+fn get_constant_completions(
+    rain_document: &RainDocument,
+    documentation_format: MarkupKind,
+) -> Vec<CompletionItem> {
+    rain_document
+        .flat_namespace()
+        .into_iter()
+        .filter_map(|(path, leaf)| {
+            let BindingItem::Literal(c) = &leaf.element.item else {
+                return None;
+            };
+            let group = if leaf.import_index == -1 {
+                BINDING_GROUP
+            } else {
+                NAMESPACE_GROUP
+            };
+            Some(CompletionItem {
+                label: path.clone(),
+                label_details: Some(CompletionItemLabelDetails {
+                    description: Some(c.value.clone()),
+                    detail: None,
+                }),
+                kind: Some(CompletionItemKind::CONSTANT),
+                sort_text: Some(sort_text(group, &path)),
+                detail: Some(format!("constant binding: {}", path)),
+                insert_text: Some(c.value.clone()),
+                documentation: Some(Documentation::MarkupContent(MarkupContent {
+                    kind: documentation_format.clone(),
+                    value: c.value.clone(),
+                })),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+// get completion items of a namespace root items, if `is_quote` is true only the quotable
+// bindings (expression and quote bindings) and the namespaces containing them are included
 fn get_namespace_completions(
     namespace_node: &Namespace,
     documentation_format: MarkupKind,
+    is_quote: bool,
 ) -> Vec<CompletionItem> {
     let mut result = vec![];
     for (key, ns_item) in namespace_node {
+        if is_quote && !is_quotable(ns_item) {
+            continue;
+        }
         match ns_item {
             NamespaceItem::Node(_node) => result.push(CompletionItem {
                 label: key.clone(),
@@ -359,80 +508,104 @@ fn get_namespace_completions(
                     detail: None,
                 }),
                 kind: Some(CompletionItemKind::FIELD),
+                sort_text: Some(sort_text(NAMESPACE_GROUP, key)),
                 detail: Some(format!("namespace: {}", key)),
                 insert_text: Some(key.clone()),
                 ..Default::default()
             }),
-            NamespaceItem::Leaf(leaf) => match &leaf.element.item {
-                BindingItem::Literal(c) => result.push(CompletionItem {
-                    label: key.clone(),
-                    label_details: Some(CompletionItemLabelDetails {
-                        description: Some("binding".to_owned()),
-                        detail: None,
+            NamespaceItem::Leaf(leaf) => {
+                let group = if leaf.import_index == -1 {
+                    BINDING_GROUP
+                } else {
+                    NAMESPACE_GROUP
+                };
+                match &leaf.element.item {
+                    BindingItem::Literal(c) => result.push(CompletionItem {
+                        label: key.clone(),
+                        label_details: Some(CompletionItemLabelDetails {
+                            description: Some("binding".to_owned()),
+                            detail: None,
+                        }),
+                        kind: Some(CompletionItemKind::CLASS),
+                        sort_text: Some(sort_text(group, key)),
+                        detail: Some(format!("constant binding: {}", key)),
+                        insert_text: Some(key.clone()),
+                        documentation: Some(Documentation::MarkupContent(MarkupContent {
+                            kind: documentation_format.clone(),
+                            value: c.value.clone(),
+                        })),
+                        ..Default::default()
                     }),
-                    kind: Some(CompletionItemKind::CLASS),
-                    detail: Some(format!("constant binding: {}", key)),
-                    insert_text: Some(key.clone()),
-                    documentation: Some(Documentation::MarkupContent(MarkupContent {
-                        kind: documentation_format.clone(),
-                        value: c.value.clone(),
-                    })),
-                    ..Default::default()
-                }),
-                BindingItem::Elided(e) => result.push(CompletionItem {
-                    label: key.clone(),
-                    label_details: Some(CompletionItemLabelDetails {
-                        description: Some("binding".to_owned()),
-                        detail: None,
+                    BindingItem::Elided(e) => result.push(CompletionItem {
+                        label: key.clone(),
+                        label_details: Some(CompletionItemLabelDetails {
+                            description: Some("binding".to_owned()),
+                            detail: None,
+                        }),
+                        kind: Some(CompletionItemKind::CLASS),
+                        sort_text: Some(sort_text(group, key)),
+                        detail: Some(format!("elided binding: {}", key)),
+                        insert_text: Some(key.clone()),
+                        documentation: Some(Documentation::MarkupContent(MarkupContent {
+                            kind: documentation_format.clone(),
+                            value: e.msg.clone(),
+                        })),
+                        ..Default::default()
                     }),
-                    kind: Some(CompletionItemKind::CLASS),
-                    detail: Some(format!("elided binding: {}", key)),
-                    insert_text: Some(key.clone()),
-                    documentation: Some(Documentation::MarkupContent(MarkupContent {
-                        kind: documentation_format.clone(),
-                        value: e.msg.clone(),
-                    })),
-                    ..Default::default()
-                }),
-                BindingItem::Exp(_e) => result.push(CompletionItem {
-                    label: key.clone(),
-                    label_details: Some(CompletionItemLabelDetails {
-                        description: Some("binding".to_owned()),
-                        detail: None,
+                    BindingItem::Exp(_e) => result.push(CompletionItem {
+                        label: key.clone(),
+                        label_details: Some(CompletionItemLabelDetails {
+                            description: Some("binding".to_owned()),
+                            detail: None,
+                        }),
+                        kind: Some(CompletionItemKind::CLASS),
+                        sort_text: Some(sort_text(group, key)),
+                        detail: Some(format!("expression binding: {}", key)),
+                        insert_text: Some(key.clone()),
+                        data: Some(json!({
+                            "kind": EXPRESSION_BINDING_DATA,
+                            "value": leaf.element.content,
+                            "format": documentation_format,
+                        })),
+                        ..Default::default()
                     }),
-                    kind: Some(CompletionItemKind::CLASS),
-                    detail: Some(format!("expression binding: {}", key)),
-                    insert_text: Some(key.clone()),
-                    documentation: Some(Documentation::MarkupContent(MarkupContent {
-                        kind: documentation_format.clone(),
-                        value: match documentation_format {
-                            MarkupKind::Markdown => {
-                                ["```rainlang", leaf.element.content.trim(), "```"]
-                                    .join("\n")
-                                    .to_string()
-                            }
-                            MarkupKind::PlainText => leaf.element.content.trim().to_string(),
-                        },
-                    })),
-                    ..Default::default()
-                }),
-                BindingItem::Quote(q) => result.push(CompletionItem {
-                    label: key.clone(),
-                    label_details: Some(CompletionItemLabelDetails {
-                        description: Some("binding".to_owned()),
-                        detail: None,
+                    BindingItem::Quote(q) => result.push(CompletionItem {
+                        label: key.clone(),
+                        label_details: Some(CompletionItemLabelDetails {
+                            description: Some("binding".to_owned()),
+                            detail: None,
+                        }),
+                        kind: Some(CompletionItemKind::CLASS),
+                        sort_text: Some(sort_text(group, key)),
+                        detail: Some(format!("quote binding: {}", key)),
+                        insert_text: Some(key.clone()),
+                        documentation: Some(Documentation::MarkupContent(MarkupContent {
+                            kind: documentation_format.clone(),
+                            value: q.quote.clone(),
+                        })),
+                        ..Default::default()
                     }),
-                    kind: Some(CompletionItemKind::CLASS),
-                    detail: Some(format!("quote binding: {}", key)),
-                    insert_text: Some(key.clone()),
-                    documentation: Some(Documentation::MarkupContent(MarkupContent {
-                        kind: documentation_format.clone(),
-                        value: q.quote.clone(),
-                    })),
-                    ..Default::default()
-                }),
-            },
+                }
+            }
         }
     }
     result
 }
+
+/// checks if the given namespace item is a quotable binding, i.e. an expression binding or
+/// a quote binding (which is resolved to the expression binding it quotes), or a namespace
+/// that contains any quotable binding
+fn is_quotable(ns_item: &NamespaceItem) -> bool {
+    match ns_item {
+        NamespaceItem::Leaf(leaf) => matches!(
+            leaf.element.item,
+            BindingItem::Exp(_) | BindingItem::Quote(_)
+        ),
+        NamespaceItem::Node(node) => node.values().any(is_quotable),
+    }
+}
+
+/// builds the sort text of a completion item from its group and label
+fn sort_text(group: u8, label: &str) -> String {
+    format!("{:02}_{}", group, label)
+}
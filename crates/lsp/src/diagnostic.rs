@@ -1,57 +1,476 @@
-use super::PositionAt;
+use super::{PositionAt, DiagnosticOptions};
 use once_cell::sync::Lazy;
-use dotrain::RainDocument;
+use dotrain::{
+    RainDocument, Rebind,
+    error::ErrorCode,
+    types::ast::{Binding, BindingItem, NamespaceItem, Node, Offsets, Opcode, Problem},
+};
 use regex::{Regex, Captures};
 use lsp_types::{
-    Diagnostic, Range, DiagnosticSeverity, DiagnosticRelatedInformation, Location, Url,
-    NumberOrString,
+    Diagnostic, DiagnosticSeverity, DiagnosticRelatedInformation, Location, Url, NumberOrString,
 };
 
 /// pattern for uppercase letters
 static UPPERCASE_LETTERS: Lazy<Regex> = Lazy::new(|| Regex::new(r"[A-Z]+").unwrap());
 
-/// Provides diagnostics for the given RainDocument by converting all problems to LSP diagnostics
+/// Provides diagnostics for the given RainDocument by converting all problems to LSP diagnostics,
+/// extended with the results of the analyses that are enabled by the given options, see [DiagnosticOptions]
 pub fn get_diagnostics(
     rain_document: &RainDocument,
     uri: &Url,
     related_information: bool,
+    options: &DiagnosticOptions,
 ) -> Vec<Diagnostic> {
-    let replacement =
-        |caps: &Captures| -> String { " ".to_owned() + &caps[0].to_ascii_lowercase() };
+    let mut analysis_problems: Vec<Problem> = vec![];
+    for binding in rain_document.bindings() {
+        analysis_problems.extend(get_analysis_problems(binding, options));
+    }
     rain_document
         .all_problems()
+        .into_iter()
+        .chain(analysis_problems.iter())
+        .map(|v| to_diagnostic(v, rain_document, uri, related_information))
+        .collect()
+}
+
+/// Provides diagnostics for only the binding with the given name of the given RainDocument,
+/// i.e. its problems which are resolved in the context of the whole document's namespace,
+/// extended with the results of the analyses of its expression that are enabled by the given
+/// options, see [DiagnosticOptions]
+pub fn get_binding_diagnostics(
+    rain_document: &RainDocument,
+    binding_name: &str,
+    uri: &Url,
+    related_information: bool,
+    options: &DiagnosticOptions,
+) -> Vec<Diagnostic> {
+    if let Some(binding) = rain_document
+        .bindings()
         .iter()
-        .map(|v| {
-            let range = Range::new(
-                rain_document.text().position_at(v.position[0]),
-                rain_document.text().position_at(v.position[1]),
-            );
-            Diagnostic::new(
-                range,
-                Some(DiagnosticSeverity::ERROR),
-                Some(NumberOrString::Number(v.code.to_i32())),
-                Some("rainlang".to_owned()),
-                if related_information {
-                    UPPERCASE_LETTERS
-                        .replace_all(&format!("{:?}", v.code), &replacement)
-                        .trim()
-                        .to_string()
-                } else {
-                    v.msg.clone()
-                },
-                if related_information {
-                    Some(vec![DiagnosticRelatedInformation {
-                        message: v.msg.to_owned(),
-                        location: Location {
-                            uri: uri.clone(),
-                            range,
-                        },
-                    }])
-                } else {
-                    None
-                },
-                None,
+        .find(|b| b.name == binding_name)
+    {
+        let analysis_problems = get_analysis_problems(binding, options);
+        binding
+            .problems
+            .iter()
+            .chain(analysis_problems.iter())
+            .map(|v| to_diagnostic(v, rain_document, uri, related_information))
+            .collect()
+    } else {
+        vec![]
+    }
+}
+
+/// Streams the diagnostics of a text to the given sink one by one as soon as they are available,
+/// the syntactic diagnostics of the given syntax only parsed RainDocument (see
+/// [RainDocumentBuilder::build_syntax_only](dotrain::RainDocumentBuilder::build_syntax_only))
+/// are streamed first, then `resolve` is called for the fully parsed RainDocument (i.e. with its
+/// imports resolved and the given rebinds applied) and its syntactic diagnostics that are not
+/// streamed yet, its semantic diagnostics (including the specified analyses which are computed
+/// per binding as they are streamed) and lastly its import resolution ones are streamed, the
+/// streamed diagnostics are the same as [get_diagnostics] results of the fully parsed RainDocument
+///
+/// The syntactic problems do not depend on imports, but rebinds change the bindings and an
+/// invalid rebind fails the whole parse, so when any rebinds are given nothing is streamed before
+/// the full parse, as the syntax only parse is not guaranteed to be reproduced
+pub fn stream_diagnostics(
+    syntax_document: &RainDocument,
+    resolve: impl FnOnce() -> RainDocument,
+    rebinds: &[Rebind],
+    uri: &Url,
+    related_information: bool,
+    options: &DiagnosticOptions,
+    mut sink: impl FnMut(Diagnostic),
+) {
+    let mut streamed = vec![];
+    let early_problems = if rebinds.is_empty() {
+        syntax_document.all_problems()
+    } else {
+        vec![]
+    };
+    for problem in early_problems
+        .into_iter()
+        .filter(|v| ValidationPhase::of(&v.code) == ValidationPhase::Syntactic)
+    {
+        sink(to_diagnostic(
+            problem,
+            syntax_document,
+            uri,
+            related_information,
+        ));
+        streamed.push(problem.clone());
+    }
+
+    let rain_document = resolve();
+    let problems = rain_document.all_problems();
+    for phase in [
+        ValidationPhase::Syntactic,
+        ValidationPhase::Semantic,
+        ValidationPhase::ImportResolution,
+    ] {
+        for problem in problems
+            .iter()
+            .filter(|v| ValidationPhase::of(&v.code) == phase)
+        {
+            // syntactic problems do not depend on imports, so they are already streamed
+            if phase == ValidationPhase::Syntactic {
+                if let Some(i) = streamed.iter().position(|v| v == *problem) {
+                    streamed.swap_remove(i);
+                    continue;
+                }
+            }
+            sink(to_diagnostic(
+                problem,
+                &rain_document,
+                uri,
+                related_information,
+            ));
+        }
+        if phase == ValidationPhase::Semantic {
+            for binding in rain_document.bindings() {
+                for problem in get_analysis_problems(binding, options) {
+                    sink(to_diagnostic(
+                        &problem,
+                        &rain_document,
+                        uri,
+                        related_information,
+                    ));
+                }
+            }
+        }
+    }
+    debug_assert!(
+        streamed.is_empty(),
+        "streamed syntactic problems that the full parse does not reproduce: {:?}",
+        streamed
+    );
+}
+
+/// the validation phase that problems are streamed at, see [stream_diagnostics]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValidationPhase {
+    Syntactic,
+    Semantic,
+    ImportResolution,
+}
+
+impl ValidationPhase {
+    /// determines the validation phase of the problems of the given error code
+    fn of(code: &ErrorCode) -> Self {
+        match code {
+            ErrorCode::IllegalChar
+            | ErrorCode::RuntimeError
+            | ErrorCode::NoFrontMatterSplitter
+            | ErrorCode::OddLenHex
+            | ErrorCode::TabCharacter
+            | ErrorCode::InvalidExpression
+            | ErrorCode::InvalidEmptyLine
+            | ErrorCode::InvalidEmptyBinding
+            | ErrorCode::UnexpectedToken
+            | ErrorCode::UnexpectedClosingParen
+            | ErrorCode::UnexpectedNamespacePath
+            | ErrorCode::UnexpectedRebinding
+            | ErrorCode::UnexpectedClosingAngleParen
+            | ErrorCode::UnexpectedEndOfComment
+            | ErrorCode::UnexpectedComment
+            | ErrorCode::UnexpectedPragma
+            | ErrorCode::UndefinedPragma
+            | ErrorCode::UnknownPragma
+            | ErrorCode::InvalidVersion
+            | ErrorCode::UnexpectedRename
+            | ErrorCode::UnexpectedStringLiteralEnd
+            | ErrorCode::UnexpectedSubParserEnd
+            | ErrorCode::ExpectedOpcode
+            | ErrorCode::ExpectedRename
+            | ErrorCode::ExpectedElisionOrRebinding
+            | ErrorCode::ExpectedClosingParen
+            | ErrorCode::ExpectedOpeningParen
+            | ErrorCode::ExpectedClosingAngleBracket
+            | ErrorCode::ExpectedHexLiteral
+            | ErrorCode::ExpectedSemi
+            | ErrorCode::ExpectedLiteral => Self::Syntactic,
+            ErrorCode::CircularDependency
+            | ErrorCode::DeepImport
+            | ErrorCode::NoneTopLevelImport
+            | ErrorCode::InconsumableMeta
+            | ErrorCode::OccupiedNamespace
+            | ErrorCode::CollidingNamespaceNodes
+            | ErrorCode::CollidingBindingTypes
+            | ErrorCode::DepsResolvingFailed
+            | ErrorCode::CorruptMeta
+            | ErrorCode::UndefinedImport
+            | ErrorCode::UndefinedAuthoringMeta
+            | ErrorCode::InvalidHash
+            | ErrorCode::InvalidImport
+            | ErrorCode::InvalidSelfImport
+            | ErrorCode::MismatchImportHash
+            | ErrorCode::DuplicateImportStatement
+            | ErrorCode::DuplicateImport
+            | ErrorCode::DuplicateImportHash => Self::ImportResolution,
+            ErrorCode::CollidingOpcodeName
+            | ErrorCode::DeepNamespace
+            | ErrorCode::DeepNesting
+            | ErrorCode::DeepQuote
+            | ErrorCode::DuplicateAlias
+            | ErrorCode::DuplicateIdentifier
+            | ErrorCode::ElidedBinding
+            | ErrorCode::ExpectedOperandArgs
+            | ErrorCode::InvalidLiteralQuote
+            | ErrorCode::InvalidNamespaceReference
+            | ErrorCode::InvalidOperandArg
+            | ErrorCode::InvalidRainDocument
+            | ErrorCode::InvalidReferenceAll
+            | ErrorCode::InvalidReferenceLiteral
+            | ErrorCode::InvalidSuppliedRebindings
+            | ErrorCode::InvalidWordPattern
+            | ErrorCode::MismatchLHS
+            | ErrorCode::MismatchOperandArgs
+            | ErrorCode::MismatchRHS
+            | ErrorCode::NativeParserError
+            | ErrorCode::OutOfRangeInputs
+            | ErrorCode::OutOfRangeOperandArgs
+            | ErrorCode::OutOfRangeValue
+            | ErrorCode::UndefinedIdentifier
+            | ErrorCode::UndefinedNamespaceMember
+            | ErrorCode::UndefinedOpcode
+            | ErrorCode::UndefinedQuote
+            | ErrorCode::UndefinedWord
+            | ErrorCode::UnexpectedOperandArgs
+            | ErrorCode::UnreachableLine
+            | ErrorCode::UnusedAlias
+            | ErrorCode::ZeroOutputs => Self::Semantic,
+        }
+    }
+}
+
+/// runs the specified analyses (nesting depth, unreachable lines, zero outputs and unused aliases) on the
+/// given binding's expression and maps the resulting problems to the parent document offsets
+fn get_analysis_problems(binding: &Binding, options: &DiagnosticOptions) -> Vec<Problem> {
+    if let BindingItem::Exp(exp) = &binding.item {
+        let mut problems = vec![];
+        if let Some(max_depth) = options.max_nesting_depth {
+            problems.extend(exp.validate_nesting_depth(max_depth));
+        }
+        if let Some(config) = &options.unreachable_lines {
+            problems
+                .extend(exp.validate_unreachable_lines(config.outputs, &config.side_effect_words));
+        }
+        if let Some(config) = &options.zero_outputs {
+            problems.extend(exp.validate_zero_outputs(&config.side_effect_words));
+        }
+        if options.unused_aliases {
+            problems.extend(exp.validate_unused_aliases());
+        }
+        problems
+            .into_iter()
+            .map(|mut problem| {
+                problem.position = binding.map_to_document(problem.position);
+                problem
+            })
+            .collect()
+    } else {
+        vec![]
+    }
+}
+
+/// finds the position of the first occurrence of the item that the given duplicate problem
+/// (duplicate identifier, alias, import or import of the same meta) is reporting, if any
+fn get_original_position(problem: &Problem, rain_document: &RainDocument) -> Option<Offsets> {
+    let dup = rain_document.span_text(problem.position)?;
+    match problem.code {
+        ErrorCode::DuplicateIdentifier => rain_document
+            .imports()
+            .iter()
+            .filter(|v| v.name == dup)
+            .map(|v| v.name_position)
+            .chain(
+                rain_document
+                    .bindings()
+                    .iter()
+                    .filter(|v| v.name == dup)
+                    .map(|v| v.name_position),
             )
-        })
-        .collect()
+            .filter(|v| v[0] < problem.position[0])
+            .min_by_key(|v| v[0]),
+        ErrorCode::DuplicateImport => rain_document
+            .imports()
+            .iter()
+            .find(|v| v.hash == dup && v.hash_position[0] < problem.position[0])
+            .map(|v| v.hash_position),
+        ErrorCode::DuplicateAlias => {
+            let binding = rain_document.bindings().iter().find(|v| {
+                v.content_position[0] <= problem.position[0]
+                    && problem.position[1] <= v.content_position[1]
+            })?;
+            let BindingItem::Exp(exp) = &binding.item else {
+                return None;
+            };
+            exp.ast()
+                .iter()
+                .find(|src| {
+                    let src_position = binding.map_to_document(src.position);
+                    src_position[0] <= problem.position[0] && problem.position[1] <= src_position[1]
+                })?
+                .lines
+                .iter()
+                .flat_map(|line| line.aliases.iter())
+                .find(|alias| alias.name == dup)
+                .map(|alias| binding.map_to_document(alias.position))
+                .filter(|v| v[0] < problem.position[0])
+        }
+        ErrorCode::DuplicateImportHash => {
+            let hash = &rain_document
+                .imports()
+                .iter()
+                .find(|v| v.position == problem.position)?
+                .hash;
+            rain_document
+                .imports()
+                .iter()
+                .find(|v| v.hash == *hash && v.position[0] < problem.position[0])
+                .map(|v| v.position)
+        }
+        _ => None,
+    }
+}
+
+/// finds the position of the imported namespace or member that the given occupied namespace
+/// problem's binding collides with, i.e. the import's name for a namespace or the import's hash
+/// for a member, along with the related information message
+fn get_occupying_position(
+    problem: &Problem,
+    rain_document: &RainDocument,
+) -> Option<(&'static str, Offsets)> {
+    if problem.code != ErrorCode::OccupiedNamespace {
+        return None;
+    }
+    let name = rain_document.span_text(problem.position)?;
+    match rain_document.namespace().get(name)? {
+        NamespaceItem::Leaf(leaf) if leaf.import_index >= 0 => rain_document
+            .imports()
+            .get(leaf.import_index as usize)
+            .map(|v| ("import of the colliding member", v.hash_position)),
+        NamespaceItem::Node(_) => rain_document
+            .imports()
+            .iter()
+            .find(|v| v.name == name)
+            .map(|v| ("colliding import", v.name_position)),
+        _ => None,
+    }
+}
+
+/// finds the position related to the given paren problem, i.e. the word of the opcode whose "("
+/// is not closed for an expected ")", or the "(" matched by the last ")" before an unexpected
+/// ")", along with the related information message
+fn get_paren_position(
+    problem: &Problem,
+    rain_document: &RainDocument,
+) -> Option<(&'static str, Offsets)> {
+    let binding = rain_document.bindings().iter().find(|v| {
+        v.content_position[0] <= problem.position[0] && problem.position[1] <= v.content_position[1]
+    })?;
+    let BindingItem::Exp(exp) = &binding.item else {
+        return None;
+    };
+    let src = exp.ast().iter().find(|src| {
+        let src_position = binding.map_to_document(src.position);
+        src_position[0] <= problem.position[0] && problem.position[0] <= src_position[1]
+    })?;
+    let mut opcodes = vec![];
+    for line in &src.lines {
+        collect_opcodes(&line.nodes, &mut opcodes);
+    }
+    match problem.code {
+        ErrorCode::ExpectedClosingParen => opcodes
+            .iter()
+            .find(|v| binding.map_to_document(v.parens)[0] == problem.position[0])
+            .map(|v| {
+                (
+                    "opcode of the unclosed \"(\"",
+                    binding.map_to_document(v.opcode.position),
+                )
+            }),
+        ErrorCode::UnexpectedClosingParen => opcodes
+            .iter()
+            .map(|v| binding.map_to_document(v.parens))
+            .filter(|v| v[1] > v[0] && v[1] <= problem.position[0])
+            .max_by_key(|v| v[1])
+            .map(|v| ("last matched \"(\"", [v[0], v[0] + 1])),
+        _ => None,
+    }
+}
+
+/// collects the opcodes of the given nodes and of their nested inputs
+fn collect_opcodes<'a>(nodes: &'a [Node], opcodes: &mut Vec<&'a Opcode>) {
+    for node in nodes {
+        if let Node::Opcode(op) = node {
+            opcodes.push(op);
+            collect_opcodes(&op.inputs, opcodes);
+        }
+    }
+}
+
+/// converts a problem to a LSP diagnostic
+fn to_diagnostic(
+    problem: &Problem,
+    rain_document: &RainDocument,
+    uri: &Url,
+    related_information: bool,
+) -> Diagnostic {
+    let replacement =
+        |caps: &Captures| -> String { " ".to_owned() + &caps[0].to_ascii_lowercase() };
+    let range = rain_document.range_of(problem.position);
+    Diagnostic::new(
+        range,
+        Some(if problem.code.is_hint() {
+            DiagnosticSeverity::HINT
+        } else if problem.code.is_warning() {
+            DiagnosticSeverity::WARNING
+        } else {
+            DiagnosticSeverity::ERROR
+        }),
+        Some(NumberOrString::Number(problem.code.to_i32())),
+        Some("rainlang".to_owned()),
+        if related_information {
+            UPPERCASE_LETTERS
+                .replace_all(&format!("{:?}", problem.code), &replacement)
+                .trim()
+                .to_string()
+        } else {
+            problem.msg.clone()
+        },
+        if related_information {
+            let mut info = vec![DiagnosticRelatedInformation {
+                message: problem.msg.to_owned(),
+                location: Location {
+                    uri: uri.clone(),
+                    range,
+                },
+            }];
+            if let Some(original) = get_original_position(problem, rain_document) {
+                info.push(DiagnosticRelatedInformation {
+                    message: "first occurrence".to_owned(),
+                    location: Location {
+                        uri: uri.clone(),
+                        range: rain_document.range_of(original),
+                    },
+                });
+            }
+            if let Some((message, position)) = get_paren_position(problem, rain_document)
+                .or_else(|| get_occupying_position(problem, rain_document))
+            {
+                info.push(DiagnosticRelatedInformation {
+                    message: message.to_owned(),
+                    location: Location {
+                        uri: uri.clone(),
+                        range: rain_document.range_of(position),
+                    },
+                });
+            }
+            Some(info)
+        } else {
+            None
+        },
+        None,
+    )
 }
@@ -1,18 +1,86 @@
-use super::{OffsetAt, PositionAt};
+use super::{OffsetAt, PositionAt, HoverOptions};
 use dotrain::{
     RainDocument,
     types::{ast::*, patterns::*},
-    exclusive_parse,
+    exclusive_parse, to_u256,
 };
 use lsp_types::{Position, MarkupKind, Hover, HoverContents, Range, MarkupContent};
 
-/// Provides hover item for the given RainDocument at the given Position
+/// Provides hover item for the given RainDocument at the given Position, its content is
+/// truncated with an ellipsis to the maximum width and lines of the given options if specified,
+/// see [truncate_hover_value]
 pub fn get_hover(
     rain_document: &RainDocument,
     position: Position,
     content_type: MarkupKind,
+    options: &HoverOptions,
 ) -> Option<Hover> {
-    let target_offset = rain_document.text().offset_at(&position);
+    let mut hover = get_hover_at(rain_document, position, content_type)?;
+    if let HoverContents::Markup(content) = &mut hover.contents {
+        content.value = truncate_hover_value(
+            &content.value,
+            &content.kind,
+            options.max_width,
+            options.max_lines,
+        );
+    }
+    Some(hover)
+}
+
+/// Truncates the given hover content so its lines are at most `max_width` characters and it is
+/// at most `max_lines` lines, truncated lines and the truncated content end with an ellipsis,
+/// for Markdown the code fence delimiters are never truncated and a code fence that is cut is
+/// closed so the rest of the content is not rendered as code
+pub fn truncate_hover_value(
+    value: &str,
+    kind: &MarkupKind,
+    max_width: Option<usize>,
+    max_lines: Option<usize>,
+) -> String {
+    let is_fence = |line: &str| *kind == MarkupKind::Markdown && line.starts_with("```");
+    let mut lines: Vec<String> = value
+        .lines()
+        .map(|line| match max_width {
+            Some(width) if !is_fence(line) && line.chars().count() > width => {
+                let mut truncated: String = line.chars().take(width.saturating_sub(1)).collect();
+                truncated.push('…');
+                truncated
+            }
+            _ => line.to_owned(),
+        })
+        .collect();
+    if let Some(max) = max_lines.filter(|v| lines.len() > *v) {
+        // whether a code fence is open after the first n lines
+        let in_fence =
+            |lines: &[String], n: usize| lines[..n].iter().filter(|v| is_fence(v)).count() % 2 == 1;
+        // leave room for the ellipsis and for closing a cut code fence
+        let mut n = max.saturating_sub(1);
+        if in_fence(&lines, n) {
+            n = max.saturating_sub(2);
+            // drop the opening delimiter of a fence with no content left
+            if n > 0 && is_fence(&lines[n - 1]) && in_fence(&lines, n) {
+                n -= 1;
+            }
+        }
+        let close_fence = in_fence(&lines, n);
+        lines.truncate(n);
+        lines.push("…".to_owned());
+        if close_fence {
+            lines.push("```".to_owned());
+        }
+    }
+    lines.join("\n")
+}
+
+fn get_hover_at(
+    rain_document: &RainDocument,
+    position: Position,
+    content_type: MarkupKind,
+) -> Option<Hover> {
+    let target_offset = rain_document.offset_at(&position);
+    if rain_document.is_in_comment(target_offset) {
+        return None;
+    }
     if let Some(import) = rain_document
         .imports()
         .iter()
@@ -28,16 +96,13 @@ pub fn get_hover(
                         String::new()
                     },
                 }),
-                range: Some(Range::new(
-                    rain_document.text().position_at(import.position[0]),
-                    rain_document.text().position_at(import.position[1]),
-                )),
+                range: Some(rain_document.range_of(import.position)),
             })
         } else {
             None
         }
     } else {
-        for binding in rain_document.bindings() {
+        if let Some(binding) = rain_document.binding_at(target_offset) {
             if binding.name_position[0] <= target_offset && binding.name_position[1] > target_offset
             {
                 return Some(Hover {
@@ -57,10 +122,7 @@ pub fn get_hover(
                         }
                         .to_owned(),
                     }),
-                    range: Some(Range::new(
-                        rain_document.text().position_at(binding.name_position[0]),
-                        rain_document.text().position_at(binding.name_position[1]),
-                    )),
+                    range: Some(rain_document.range_of(binding.name_position)),
                 });
             } else if binding.content_position[0] <= target_offset
                 && binding.content_position[1] > target_offset
@@ -88,7 +150,6 @@ pub fn get_hover(
                             binding.content_position[0],
                             target_offset - binding.content_position[0],
                             content_type,
-                            rain_document.text(),
                         );
                     }
                     BindingItem::Literal(_) => {
@@ -97,14 +158,7 @@ pub fn get_hover(
                                 kind: content_type,
                                 value: "literal value".to_owned(),
                             }),
-                            range: Some(Range::new(
-                                rain_document
-                                    .text()
-                                    .position_at(binding.content_position[0]),
-                                rain_document
-                                    .text()
-                                    .position_at(binding.content_position[1]),
-                            )),
+                            range: Some(rain_document.range_of(binding.content_position)),
                         })
                     }
                     BindingItem::Elided(_) => {
@@ -113,14 +167,7 @@ pub fn get_hover(
                                 kind: content_type,
                                 value: "elision msg".to_owned(),
                             }),
-                            range: Some(Range::new(
-                                rain_document
-                                    .text()
-                                    .position_at(binding.content_position[0]),
-                                rain_document
-                                    .text()
-                                    .position_at(binding.content_position[1]),
-                            )),
+                            range: Some(rain_document.range_of(binding.content_position)),
                         })
                     }
                     BindingItem::Quote(_) => {
@@ -129,14 +176,7 @@ pub fn get_hover(
                                 kind: content_type,
                                 value: "quote binding".to_owned(),
                             }),
-                            range: Some(Range::new(
-                                rain_document
-                                    .text()
-                                    .position_at(binding.content_position[0]),
-                                rain_document
-                                    .text()
-                                    .position_at(binding.content_position[1]),
-                            )),
+                            range: Some(rain_document.range_of(binding.content_position)),
                         })
                     }
                 }
@@ -152,7 +192,6 @@ fn search(
     offset: usize,
     target_offset: usize,
     kind: MarkupKind,
-    text: &str,
 ) -> Option<Hover> {
     for node in nodes {
         let node_pos = node.position();
@@ -166,66 +205,64 @@ fn search(
                             offset,
                             target_offset,
                             kind,
-                            text,
                         );
                     } else if let Some(og) = &op.operand_args {
                         if og.position[0] < target_offset && og.position[1] > target_offset {
-                            for arg in &og.args {
-                                if arg.position[0] <= target_offset
-                                    && arg.position[1] > target_offset
-                                {
-                                    let header = if arg.description.is_empty() {
-                                        arg.name.clone()
-                                    } else {
-                                        [arg.name.clone(), arg.description.clone()].join("\n")
-                                    };
-                                    let value = if let Some((id, _)) = &arg.binding_id {
-                                        match search_binding_ref(id.strip_prefix('\'').unwrap_or(id.as_str()), rain_document.namespace()) {
-                                            None => header,
-                                            Some(binding) => match &binding.item {
-                                                BindingItem::Elided(e) => format!("{}\n\n---\n\nelided binding\n\n---\n\nmessage:\n{}", header, get_value(&e.msg, &kind)),
-                                                BindingItem::Literal(l) => format!("{}\n\n---\n\nliteral binding\n\n---\n\n{}", header, get_value(&l.value, &kind)),
-                                                BindingItem::Quote(q) => format!("{}\n\n---\n\nquote binding\n\n---\n\n{}", header, get_value(&q.quote, &kind)),
-                                                BindingItem::Exp(_) => format!("{}\n\n---\n\nrainlang expression binding\n\n---\n\n{}", header, get_value(&binding.content, &kind)),
-                                            }
+                            if let Some(arg) = op.operand_arg_at(target_offset) {
+                                let header = if arg.description.is_empty() {
+                                    arg.name.clone()
+                                } else {
+                                    [arg.name.clone(), arg.description.clone()].join("\n")
+                                };
+                                let value = if let Some((id, _)) = &arg.binding_id {
+                                    match search_binding_ref(id.strip_prefix('\'').unwrap_or(id.as_str()), rain_document.namespace()) {
+                                        None => header,
+                                        Some(binding) => match &binding.item {
+                                            BindingItem::Elided(e) => format!("{}\n\n---\n\nelided binding\n\n---\n\nmessage:\n{}", header, get_value(&e.msg, &kind)),
+                                            BindingItem::Literal(l) => format!("{}\n\n---\n\nliteral binding\n\n---\n\n{}", header, get_literal_value(&l.value, &kind)),
+                                            BindingItem::Quote(q) => format!("{}\n\n---\n\nquote binding\n\n---\n\n{}", header, get_value(&q.quote, &kind)),
+                                            BindingItem::Exp(_) => format!("{}\n\n---\n\nrainlang expression binding\n\n---\n\n{}", header, get_value(&binding.content, &kind)),
                                         }
-                                    } else {
-                                        header
-                                    };
-                                    return Some(Hover {
-                                        contents: HoverContents::Markup(MarkupContent {
-                                            kind,
-                                            value,
-                                        }),
-                                        range: Some(Range::new(
-                                            text.position_at(arg.position[0] + offset),
-                                            text.position_at(arg.position[1] + offset),
-                                        )),
-                                    });
-                                }
+                                    }
+                                } else if let Some(value) = &arg.value {
+                                    format!(
+                                        "{}\n\n---\n\n{}",
+                                        header,
+                                        get_literal_value(value, &kind)
+                                    )
+                                } else {
+                                    header
+                                };
+                                return Some(Hover {
+                                    contents: HoverContents::Markup(MarkupContent { kind, value }),
+                                    range: Some(Range::new(
+                                        rain_document.position_at(arg.position[0] + offset),
+                                        rain_document.position_at(arg.position[1] + offset),
+                                    )),
+                                });
                             }
                             return None;
                         } else {
                             return Some(Hover {
                                 contents: HoverContents::Markup(MarkupContent {
+                                    value: get_opcode_value(op, &kind),
                                     kind,
-                                    value: op.opcode.description.clone(),
                                 }),
                                 range: Some(Range::new(
-                                    text.position_at(op.opcode.position[0] + offset),
-                                    text.position_at(op.parens[1] + offset),
+                                    rain_document.position_at(op.opcode.position[0] + offset),
+                                    rain_document.position_at(op.parens[1] + offset),
                                 )),
                             });
                         }
                     } else {
                         return Some(Hover {
                             contents: HoverContents::Markup(MarkupContent {
+                                value: get_opcode_value(op, &kind),
                                 kind,
-                                value: op.opcode.description.clone(),
                             }),
                             range: Some(Range::new(
-                                text.position_at(op.opcode.position[0] + offset),
-                                text.position_at(op.parens[1] + offset),
+                                rain_document.position_at(op.opcode.position[0] + offset),
+                                rain_document.position_at(op.parens[1] + offset),
                             )),
                         });
                     }
@@ -234,20 +271,20 @@ fn search(
                     return Some(Hover {
                         contents: HoverContents::Markup(MarkupContent {
                             value: if literal.id.is_some() {
-                                get_value(&literal.value, &kind)
+                                get_literal_value(&literal.value, &kind)
                             } else {
                                 "literal value".to_owned()
                             },
                             kind,
                         }),
                         range: Some(Range::new(
-                            text.position_at(literal.position[0] + offset),
-                            text.position_at(literal.position[1] + offset),
+                            rain_document.position_at(literal.position[0] + offset),
+                            rain_document.position_at(literal.position[1] + offset),
                         )),
                     });
                 }
                 Node::Alias(alias) => {
-                    let value = if alias.name == "_" {
+                    let value = if alias.is_placeholder() {
                         "Stack Alias Placeholder".to_owned()
                     } else {
                         match search_binding_ref(&alias.name, rain_document.namespace()) {
@@ -259,7 +296,7 @@ fn search(
                                 ),
                                 BindingItem::Literal(l) => format!(
                                     "literal binding\n\n---\n\n{}",
-                                    get_value(&l.value, &kind)
+                                    get_literal_value(&l.value, &kind)
                                 ),
                                 BindingItem::Quote(q) => format!(
                                     "quote binding\n\n---\n\n{}",
@@ -275,8 +312,8 @@ fn search(
                     return Some(Hover {
                         contents: HoverContents::Markup(MarkupContent { kind, value }),
                         range: Some(Range::new(
-                            text.position_at(alias.position[0] + offset),
-                            text.position_at(alias.position[1] + offset),
+                            rain_document.position_at(alias.position[0] + offset),
+                            rain_document.position_at(alias.position[1] + offset),
                         )),
                     });
                 }
@@ -348,3 +385,68 @@ fn get_value(text: &str, kind: &MarkupKind) -> String {
         limited_text
     }
 }
+
+/// builds the hover value of a literal binding's value, i.e. its text followed by its resolved
+/// U256 value in decimal if it is numeric and not already written as such
+fn get_literal_value(value: &str, kind: &MarkupKind) -> String {
+    let text = get_value(value, kind);
+    match to_u256(value) {
+        Ok(resolved) if resolved.to_string() != value => {
+            format!("{}\n\nresolved value: {}", text, resolved)
+        }
+        _ => text,
+    }
+}
+
+/// builds the opcode hover value, i.e. its canonical word if referenced by an alias and its
+/// description (with its documentation link if any) followed by its operand args as a table
+/// for markdown or as plain lines for plaintext
+fn get_opcode_value(op: &Opcode, kind: &MarkupKind) -> String {
+    let mut value = super::opcode_documentation(&op.opcode.description, kind);
+    if let Some(canonical) = &op.opcode.canonical {
+        value = if value.is_empty() {
+            format!("alias of: {}", canonical)
+        } else {
+            format!("alias of: {}\n\n{}", canonical, value)
+        };
+    }
+    let args = match &op.operand_args {
+        Some(operand_args) if !operand_args.args.is_empty() => &operand_args.args,
+        _ => return value,
+    };
+    let arg_value = |arg: &OperandArgItem| -> String {
+        match (&arg.value, &arg.binding_id) {
+            (Some(v), _) => v.clone(),
+            (None, Some((id, _))) => id.clone(),
+            (None, None) => String::new(),
+        }
+    };
+    if !value.is_empty() {
+        value.push_str("\n\n");
+    }
+    if *kind == MarkupKind::Markdown {
+        let escape = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+        value.push_str("| name | value | description |\n| --- | --- | --- |");
+        for arg in args {
+            value.push_str(&format!(
+                "\n| {} | {} | {} |",
+                escape(&arg.name),
+                escape(&arg_value(arg)),
+                escape(&arg.description)
+            ));
+        }
+    } else {
+        let lines: Vec<String> = args
+            .iter()
+            .map(|arg| {
+                if arg.description.is_empty() {
+                    format!("{}: {}", arg.name, arg_value(arg))
+                } else {
+                    format!("{}: {} - {}", arg.name, arg_value(arg), arg.description)
+                }
+            })
+            .collect();
+        value.push_str(&lines.join("\n"));
+    }
+    value
+}
@@ -1,5 +1,10 @@
 use wasm_bindgen::prelude::*;
-use lsp_types::{MarkupKind as MK, Position as Pos, TextDocumentItem as TDI, Url};
+use wasm_bindgen_futures::JsFuture;
+use js_sys::{Function, Promise, Uint8Array};
+use lsp_types::{
+    MarkupKind as MK, Position as Pos, Range as Rng, TextDocumentItem as TDI, Url,
+    WorkspaceEdit as WE,
+};
 use serde_wasm_bindgen::{to_value as to_js_value, from_value as from_js_value};
 use dotrain::{js_api::MetaStore, RainDocument, Rebind};
 use super::{RainLanguageServices, LanguageServiceParams};
@@ -34,12 +39,20 @@ extern "C" {
     /// it can be deserialized to rust [mod@lsp_types::SemanticTokensPartialResult] using `serde-wasm-bindgen`
     #[wasm_bindgen(typescript_type = "SemanticTokensPartialResult")]
     pub type SemanticTokensPartialResult;
+    /// A wrapped JsValue representing typescript LSP WorkspaceEdit interface in rust,
+    /// it can be deserialized to rust [mod@lsp_types::WorkspaceEdit] using `serde-wasm-bindgen`
+    #[wasm_bindgen(typescript_type = "WorkspaceEdit")]
+    pub type WorkspaceEdit;
+    /// A wrapped JsValue representing typescript LSP Range interface in rust,
+    /// it can be deserialized to rust [mod@lsp_types::Range] using `serde-wasm-bindgen`
+    #[wasm_bindgen(typescript_type = "Range")]
+    pub type Range;
 }
 
 #[wasm_bindgen(typescript_custom_section)]
 const LSP_TS_IMPORTS: &'static str = r#"
 import { SemanticTokensPartialResult } from "vscode-languageserver-protocol";
-import { Hover, Position, MarkupKind, Diagnostic, CompletionItem, TextDocumentItem } from "vscode-languageserver-types";
+import { Hover, Position, MarkupKind, Diagnostic, CompletionItem, TextDocumentItem, WorkspaceEdit, Range } from "vscode-languageserver-types";
 "#;
 
 #[wasm_bindgen]
@@ -50,12 +63,25 @@ impl RainLanguageServices {
         self.meta_store.clone().into()
     }
 
-    /// Instantiates with the given MetaStore
+    /// Instantiates with the given MetaStore and optionally an async meta resolver, i.e.
+    /// `(hash: string) => Promise<Uint8Array | undefined>`, that if provided is used for
+    /// resolving the imports' metas in async methods instead of remote meta search, and
+    /// optionally a word pattern regex that binding names and aliases are validated with
+    /// instead of the default identifier pattern, throws if the word pattern is invalid
     #[wasm_bindgen(constructor)]
-    pub fn js_new(meta_store: &MetaStore) -> RainLanguageServices {
-        RainLanguageServices::new(&LanguageServiceParams {
+    pub fn js_new(
+        meta_store: &MetaStore,
+        meta_resolver: Option<Function>,
+        word_pattern: Option<String>,
+    ) -> Result<RainLanguageServices, JsError> {
+        let mut lang_services = RainLanguageServices::try_new(&LanguageServiceParams {
             meta_store: Some(meta_store.into()),
+            word_pattern,
+            ..Default::default()
         })
+        .map_err(|e| JsError::new(&e.to_string()))?;
+        lang_services.meta_resolver = meta_resolver;
+        Ok(lang_services)
     }
 
     /// Instantiates a RainDocument with remote meta search disabled when parsing from the given TextDocumentItem
@@ -69,7 +95,8 @@ impl RainLanguageServices {
         self.new_rain_document(&tdi, rebinds)
     }
 
-    /// Instantiates a RainDocument with remote meta search enabled when parsing from the given TextDocumentItem
+    /// Instantiates a RainDocument with remote meta search enabled (or by the meta resolver of
+    /// this instance if it is set) when parsing from the given TextDocumentItem
     #[wasm_bindgen(js_name = "newRainDocumentAsync")]
     pub async fn js_new_rain_document_async(
         &self,
@@ -77,7 +104,7 @@ impl RainLanguageServices {
         rebinds: Option<Vec<Rebind>>,
     ) -> RainDocument {
         let tdi = from_js_value::<TDI>(text_document.obj).unwrap_throw();
-        self.new_rain_document_async(&tdi, rebinds).await
+        self.resolve_rain_document(&tdi, rebinds).await
     }
 
     /// Validates the document with remote meta search disabled when parsing and reports LSP diagnostics
@@ -97,6 +124,35 @@ impl RainLanguageServices {
             .collect()
     }
 
+    /// Validates the document with remote meta search disabled when parsing and calls the given
+    /// sink function with each of the LSP diagnostics as soon as it is available, syntactic ones
+    /// first, then semantic and lastly import resolution ones
+    #[wasm_bindgen(js_name = "validateStreaming")]
+    pub fn js_validate_streaming(
+        &self,
+        text_document: TextDocumentItem,
+        related_information: bool,
+        sink: &Function,
+        rebinds: Option<Vec<Rebind>>,
+    ) {
+        let tdi = from_js_value::<TDI>(text_document.obj).unwrap_throw();
+        self.validate_streaming(&tdi, related_information, rebinds, |v| {
+            let _ = sink.call1(&JsValue::NULL, &to_js_value(&v).unwrap_or(JsValue::NULL));
+        })
+    }
+
+    /// Prunes the meta Store by removing the dotrain records of all uris other than the given ones,
+    /// throws if any of the given uris is invalid
+    #[wasm_bindgen(js_name = "pruneStore")]
+    pub fn js_prune_store(&self, keep_uris: Vec<String>) -> Result<(), JsError> {
+        let keep_uris = keep_uris
+            .iter()
+            .map(|v| Url::parse(v).map_err(|e| JsError::new(&format!("invalid uri: {}, {}", v, e))))
+            .collect::<Result<Vec<Url>, JsError>>()?;
+        self.prune_store(&keep_uris);
+        Ok(())
+    }
+
     /// Reports LSP diagnostics from RainDocument's all problems
     #[wasm_bindgen(js_name = "doValidateRainDocument")]
     pub fn js_do_validate_rain_document(
@@ -117,7 +173,30 @@ impl RainLanguageServices {
         .collect()
     }
 
-    /// Validates the document with remote meta search enabled when parsing and reports LSP diagnostics
+    /// Reports LSP diagnostics of only the binding with the given name in the given RainDocument
+    #[wasm_bindgen(js_name = "validateBinding")]
+    pub fn js_validate_binding(
+        &self,
+        rain_document: &RainDocument,
+        binding_name: &str,
+        uri: &str,
+        related_information: bool,
+    ) -> Vec<Diagnostic> {
+        self.validate_binding(
+            rain_document,
+            binding_name,
+            &Url::parse(uri).unwrap_throw(),
+            related_information,
+        )
+        .iter()
+        .map(|v| Diagnostic {
+            obj: to_js_value(v).unwrap_or(JsValue::NULL),
+        })
+        .collect()
+    }
+
+    /// Validates the document with remote meta search enabled (or by the meta resolver of
+    /// this instance if it is set) when parsing and reports LSP diagnostics
     #[wasm_bindgen(js_name = "doValidateAsync")]
     pub async fn js_do_validate_async(
         &self,
@@ -126,12 +205,9 @@ impl RainLanguageServices {
         rebinds: Option<Vec<Rebind>>,
     ) -> JsValue {
         let tdi = from_js_value::<TDI>(text_document.obj).unwrap_throw();
-        to_js_value(
-            &self
-                .do_validate_async(&tdi, related_information, rebinds)
-                .await,
-        )
-        .unwrap_or(JsValue::NULL)
+        let rain_document = self.resolve_rain_document(&tdi, rebinds).await;
+        to_js_value(&self.do_validate_rain_document(&rain_document, &tdi.uri, related_information))
+            .unwrap_or(JsValue::NULL)
     }
 
     /// Provides completion items at the given position
@@ -185,6 +261,15 @@ impl RainLanguageServices {
         })
     }
 
+    /// Resolves a completion item provided by this instance by filling in its documentation
+    #[wasm_bindgen(js_name = "resolveCompletion")]
+    pub fn js_resolve_completion(&self, item: CompletionItem) -> CompletionItem {
+        let item = from_js_value::<lsp_types::CompletionItem>(item.obj).unwrap_throw();
+        CompletionItem {
+            obj: to_js_value(&self.resolve_completion(item)).unwrap_or(JsValue::NULL),
+        }
+    }
+
     /// Provides hover for a fragment at the given position
     #[wasm_bindgen(js_name = "doHover")]
     pub fn js_do_hover(
@@ -226,6 +311,47 @@ impl RainLanguageServices {
         })
     }
 
+    /// Provides the workspace edit that organizes the imports of the document
+    #[wasm_bindgen(js_name = "organizeImports")]
+    pub fn js_organize_imports(
+        &self,
+        text_document: TextDocumentItem,
+        rebinds: Option<Vec<Rebind>>,
+    ) -> Option<WorkspaceEdit> {
+        let tdi = from_js_value::<TDI>(text_document.obj).unwrap_throw();
+        self.organize_imports(&tdi, rebinds).map(|v| WorkspaceEdit {
+            obj: to_js_value(&v).unwrap_or(JsValue::NULL),
+        })
+    }
+
+    /// Provides the workspace edit that organizes the imports of the RainDocument
+    #[wasm_bindgen(js_name = "organizeImportsRainDocument")]
+    pub fn js_organize_imports_rain_document(
+        &self,
+        rain_document: &RainDocument,
+        uri: &str,
+    ) -> Option<WorkspaceEdit> {
+        self.organize_imports_rain_document(rain_document, &Url::parse(uri).unwrap_throw())
+            .map(|v| WorkspaceEdit {
+                obj: to_js_value(&v).unwrap_or(JsValue::NULL),
+            })
+    }
+
+    /// Verifies the given workspace edit by applying its text edits that target the document
+    /// and parsing the result, returns the edited RainDocument if the edit introduces no new
+    /// problems, otherwise throws the diagnostics of the newly introduced problems
+    #[wasm_bindgen(js_name = "verifyEdit")]
+    pub fn js_verify_edit(
+        &self,
+        text_document: TextDocumentItem,
+        edit: WorkspaceEdit,
+    ) -> Result<RainDocument, JsValue> {
+        let tdi = from_js_value::<TDI>(text_document.obj).unwrap_throw();
+        let edit = from_js_value::<WE>(edit.obj).unwrap_throw();
+        self.verify_edit(&tdi, &edit)
+            .map_err(|e| to_js_value(&e).unwrap_or(JsValue::NULL))
+    }
+
     /// Provides semantic tokens for elided fragments
     #[wasm_bindgen(js_name = "semanticTokens")]
     pub fn js_semantic_tokens(
@@ -264,4 +390,72 @@ impl RainLanguageServices {
             .unwrap_or(JsValue::NULL),
         }
     }
+
+    /// Provides semantic tokens for elided fragments that intersect the given range
+    #[wasm_bindgen(js_name = "semanticTokensRange")]
+    pub fn js_semantic_tokens_range(
+        &self,
+        text_document: TextDocumentItem,
+        range: Range,
+        semantic_token_types_index: u32,
+        semantic_token_modifiers_len: usize,
+        rebinds: Option<Vec<Rebind>>,
+    ) -> SemanticTokensPartialResult {
+        let tdi = from_js_value::<TDI>(text_document.obj).unwrap_throw();
+        let rng = from_js_value::<Rng>(range.obj).unwrap_throw();
+        SemanticTokensPartialResult {
+            obj: to_js_value(&self.semantic_tokens_range(
+                &tdi,
+                rng,
+                semantic_token_types_index,
+                semantic_token_modifiers_len,
+                rebinds,
+            ))
+            .unwrap_or(JsValue::NULL),
+        }
+    }
+
+    /// Provides semantic tokens for RainDocument's elided fragments that intersect the given range
+    #[wasm_bindgen(js_name = "rainDocumentSemanticTokensRange")]
+    pub fn js_rain_document_semantic_tokens_range(
+        &self,
+        rain_document: &RainDocument,
+        range: Range,
+        semantic_token_types_index: u32,
+        semantic_token_modifiers_len: usize,
+    ) -> SemanticTokensPartialResult {
+        let rng = from_js_value::<Rng>(range.obj).unwrap_throw();
+        SemanticTokensPartialResult {
+            obj: to_js_value(&self.rain_document_semantic_tokens_range(
+                rain_document,
+                rng,
+                semantic_token_types_index,
+                semantic_token_modifiers_len,
+            ))
+            .unwrap_or(JsValue::NULL),
+        }
+    }
+}
+
+impl RainLanguageServices {
+    /// Instantiates a RainDocument by the meta resolver of this instance if it is set or else
+    /// with remote meta search enabled when parsing
+    async fn resolve_rain_document(&self, tdi: &TDI, rebinds: Option<Vec<Rebind>>) -> RainDocument {
+        if let Some(meta_resolver) = &self.meta_resolver {
+            self.new_rain_document_with_resolver(tdi, rebinds, |hash| async move {
+                let value = meta_resolver
+                    .call1(&JsValue::NULL, &JsValue::from_str(&hash))
+                    .ok()?;
+                let bytes = JsFuture::from(Promise::resolve(&value)).await.ok()?;
+                if bytes.is_instance_of::<Uint8Array>() {
+                    Some(Uint8Array::from(bytes).to_vec())
+                } else {
+                    None
+                }
+            })
+            .await
+        } else {
+            self.new_rain_document_async(tdi, rebinds).await
+        }
+    }
 }
@@ -7,36 +7,151 @@
 //! - Dotrain lsp services are used for vscode and codemirror, see [rainlang-vscode](https://github.com/rainprotocol/rainlang-vscode) and [rainlang-codemirror](https://github.com/rainprotocol/rainlang-codemirror) repositories for more details.
 //! - Dotrain vscode extension can be found [here](https://marketplace.visualstudio.com/items?itemName=rainprotocol.rainlang-vscode).
 
-use std::sync::{Arc, RwLock};
-use dotrain::{RainDocument, Store, Rebind};
+use std::{
+    future::Future,
+    collections::{HashMap, HashSet},
+    sync::{atomic::AtomicBool, Arc, RwLock},
+};
+use dotrain::{
+    RainDocument, Store, Rebind, ParseOptions, line_offsets,
+    error::ErrorCode,
+    types::{
+        ast::Offsets,
+        patterns::{CommentStyle, UnknownPragmaPolicy, WordPattern},
+    },
+    rain_metadata::{types::authoring::v1::AuthoringMeta, KnownMagic, RainMetaDocumentV1Item},
+};
 use lsp_types::{
     Hover, Position, Diagnostic, MarkupKind, CompletionItem, TextDocumentItem,
-    SemanticTokensPartialResult, Url,
+    SemanticTokensPartialResult, Url, PositionEncodingKind, WorkspaceEdit, Range, TextEdit,
+    TextDocumentEdit, DocumentChanges, DocumentChangeOperation, OneOf,
 };
 
+use regex::Regex;
+use once_cell::sync::Lazy;
+use futures::future::join_all;
+
 #[cfg(feature = "js-api")]
 use wasm_bindgen::prelude::*;
 
 pub use dotrain;
 pub use lsp_types;
-pub use hover::get_hover;
-pub use completion::get_completion;
-pub use diagnostic::get_diagnostics;
-pub use semantic_token::get_semantic_token;
+pub use hover::{get_hover, truncate_hover_value};
+pub use completion::{get_completion, resolve_completion};
+pub use diagnostic::{get_diagnostics, get_binding_diagnostics, stream_diagnostics};
+pub use semantic_token::{get_semantic_token, get_semantic_token_range};
+pub use organize_imports::get_organize_imports;
+pub use sarif::diagnostics_to_sarif;
+
+/// pattern for documentation urls in opcodes' descriptions
+static DOCS_URL: Lazy<Regex> = Lazy::new(|| Regex::new(r"https?://[^\s<>()\[\]`]+").unwrap());
 
 mod hover;
 mod completion;
 mod diagnostic;
 mod semantic_token;
+mod organize_imports;
+mod sarif;
 
 #[cfg(feature = "js-api")]
 pub mod js_api;
 
 /// Parameters for initiating Language Services
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct LanguageServiceParams {
     /// The meta Store (CAS) instance used for all parsings of the RainLanguageServices
     pub meta_store: Option<Arc<RwLock<Store>>>,
+    /// The maximum number of completion items to provide, unlimited if not specified
+    pub max_completions: Option<usize>,
+    /// The opcodes nesting depth that expressions exceeding it get a warning diagnostic, disabled if not specified
+    pub max_nesting_depth: Option<usize>,
+    /// The regex that binding names and aliases are validated with instead of the default
+    /// identifier pattern, it is matched against whole identifiers, see [WordPattern::new]
+    pub word_pattern: Option<String>,
+    /// The configuration of the unreachable lines analysis that warns about expression lines coming
+    /// after the lines that already satisfy the expression outputs, disabled if not specified
+    pub unreachable_lines: Option<UnreachableLinesConfig>,
+    /// The configuration of the zero outputs analysis that warns about expression sources that
+    /// produce zero outputs, disabled if not specified, see
+    /// [RainlangDocument::validate_zero_outputs](dotrain::RainlangDocument::validate_zero_outputs)
+    pub zero_outputs: Option<ZeroOutputsConfig>,
+    /// The dialect's keyword literals (such as `true` and `false`) mapped to their numeric values,
+    /// they are parsed as literals instead of being resolved as identifiers, see
+    /// [ParseOptions::with_keyword_literals]
+    pub keyword_literals: Option<HashMap<String, String>>,
+    /// The dialect's opcode aliases mapped to their canonical opcode words, they override the
+    /// aliases declared by the authoring meta, see [ParseOptions::with_opcode_aliases]
+    pub opcode_aliases: Option<HashMap<String, String>>,
+    /// The dialect's comment styles that replace the default `/* */` block comments, see
+    /// [ParseOptions::with_comment_styles]
+    pub comment_styles: Option<Vec<CommentStyle>>,
+    /// Whether expressions are parsed leniently, i.e. their trailing empty lines and sources are
+    /// dropped instead of being reported, strict if not specified, see [ParseOptions::with_lenient]
+    pub lenient: Option<bool>,
+    /// Whether tab characters in expressions are reported as warning diagnostics, disabled if not
+    /// specified, see [ParseOptions::with_lint_tabs]
+    pub lint_tabs: Option<bool>,
+    /// The maximum size (in bytes) of documents, larger documents are not parsed and only get a
+    /// runtime error diagnostic, unlimited if not specified, see [ParseOptions::with_max_document_bytes]
+    pub max_document_bytes: Option<usize>,
+    /// Whether the expressions' aliases that are never referenced by their following lines are
+    /// reported as hint diagnostics, disabled if not specified, see
+    /// [RainlangDocument::validate_unused_aliases](dotrain::RainlangDocument::validate_unused_aliases)
+    pub unused_aliases: Option<bool>,
+    /// The maximum number of characters of each line of hover contents, longer lines are
+    /// truncated with an ellipsis, unlimited if not specified
+    pub max_hover_width: Option<usize>,
+    /// The maximum number of lines of hover contents, longer contents are truncated with an
+    /// ellipsis while keeping Markdown code fences closed, unlimited if not specified
+    pub max_hover_lines: Option<usize>,
+    /// A preloaded read-only authoring meta that is used directly as the known words of all
+    /// documents parsed by this instance, without requiring an import or a [Store] lookup
+    pub known_words: Option<AuthoringMeta>,
+    /// How the pragma statements with unknown keywords at top of documents are handled, reported
+    /// as errors if not specified, see [ParseOptions::with_unknown_pragmas]
+    pub unknown_pragmas: Option<UnknownPragmaPolicy>,
+}
+
+/// Options of the analyses that diagnostics are extended with, all disabled by default
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DiagnosticOptions {
+    /// The opcodes nesting depth that expressions exceeding it get a warning diagnostic
+    pub max_nesting_depth: Option<usize>,
+    /// The configuration of the unreachable lines analysis, see [UnreachableLinesConfig]
+    pub unreachable_lines: Option<UnreachableLinesConfig>,
+    /// The configuration of the zero outputs analysis, see [ZeroOutputsConfig]
+    pub zero_outputs: Option<ZeroOutputsConfig>,
+    /// Whether the expressions' aliases that are never referenced by their following lines are
+    /// reported as hint diagnostics
+    pub unused_aliases: bool,
+}
+
+/// Options of hover contents, unlimited by default
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HoverOptions {
+    /// The maximum number of characters of each line, longer lines are truncated with an ellipsis
+    pub max_width: Option<usize>,
+    /// The maximum number of lines, longer contents are truncated with an ellipsis while keeping
+    /// Markdown code fences closed
+    pub max_lines: Option<usize>,
+}
+
+/// Configuration of the unreachable lines analysis
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UnreachableLinesConfig {
+    /// The number of outputs that each expression source is expected to have
+    pub outputs: usize,
+    /// The words of the opcodes that have side effects, lines containing any of them are never
+    /// reported, as authoring meta does not provide such information it should be provided here
+    pub side_effect_words: HashSet<String>,
+}
+
+/// Configuration of the zero outputs analysis
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ZeroOutputsConfig {
+    /// The words of the opcodes that have side effects, sources containing any of them are
+    /// never reported, as authoring meta does not provide such information it should be provided here
+    pub side_effect_words: HashSet<String>,
 }
 
 #[cfg_attr(
@@ -49,6 +164,8 @@ are required during parsing a text.
 Position encodings provided by the client are irrevelant as RainDocument/Rainlang supports
 only ASCII characters (parsing will stop at very first encountered non-ASCII character), so any
 position encodings will result in the same LSP provided Position value which is 1 for each char.
+For texts that contain non-ASCII characters, [PositionAt::position_at_encoded] and
+[OffsetAt::offset_at_encoded] can be used to convert positions in the client's encoding.
 
 ## Example
 
@@ -66,7 +183,8 @@ let meta_store = Arc::new(RwLock::new(Store::default()));
 
 // create instatiation params
 let params = LanguageServiceParams {
-    meta_store: Some(meta_store)
+    meta_store: Some(meta_store),
+    ..Default::default()
 };
 
 // create a new instane with a shared locked Store that is used for all
@@ -134,12 +252,28 @@ let hover = lang_services.do_hover(&text_document, position, content_format, Non
 #[cfg_attr(feature = "js-api", wasm_bindgen)]
 pub struct RainLanguageServices {
     pub(crate) meta_store: Arc<RwLock<Store>>,
+    pub(crate) max_completions: Option<usize>,
+    pub(crate) parse_options: ParseOptions,
+    pub(crate) diagnostic_options: DiagnosticOptions,
+    pub(crate) hover_options: HoverOptions,
+    pub(crate) known_words: Option<AuthoringMeta>,
+    #[cfg(feature = "js-api")]
+    pub(crate) meta_resolver: Option<js_sys::Function>,
 }
 
 impl Default for RainLanguageServices {
     fn default() -> Self {
         let meta_store = Arc::new(RwLock::new(Store::default()));
-        RainLanguageServices { meta_store }
+        RainLanguageServices {
+            meta_store,
+            max_completions: None,
+            parse_options: ParseOptions::default(),
+            diagnostic_options: DiagnosticOptions::default(),
+            hover_options: HoverOptions::default(),
+            known_words: None,
+            #[cfg(feature = "js-api")]
+            meta_resolver: None,
+        }
     }
 }
 
@@ -148,14 +282,77 @@ impl RainLanguageServices {
     pub fn meta_store(&self) -> Arc<RwLock<Store>> {
         self.meta_store.clone()
     }
+    /// The word pattern that binding names and aliases are validated with
+    pub fn word_pattern(&self) -> &WordPattern {
+        self.parse_options.word_pattern()
+    }
+    /// The options that all documents of this instance are parsed with
+    pub fn parse_options(&self) -> &ParseOptions {
+        &self.parse_options
+    }
     /// Instantiates from the given params
+    ///
+    /// # Panics
+    /// If the given word pattern is not a valid regex, any of the given keyword literals' values
+    /// is not a valid numeric value or the given opcode aliases or comment styles are invalid,
+    /// use [RainLanguageServices::try_new] for handling it as an error
     pub fn new(language_params: &LanguageServiceParams) -> RainLanguageServices {
-        RainLanguageServices {
+        match Self::try_new(language_params) {
+            Ok(lang_services) => lang_services,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    /// Instantiates from the given params, rejecting an invalid word pattern regex, keyword
+    /// literal value, opcode alias or comment style
+    pub fn try_new(
+        language_params: &LanguageServiceParams,
+    ) -> Result<RainLanguageServices, dotrain::error::Error> {
+        let mut parse_options = ParseOptions::default();
+        if let Some(pattern) = &language_params.word_pattern {
+            parse_options = parse_options.with_word_pattern(WordPattern::new(pattern)?);
+        }
+        if let Some(keyword_literals) = &language_params.keyword_literals {
+            parse_options = parse_options.with_keyword_literals(keyword_literals)?;
+        }
+        if let Some(opcode_aliases) = &language_params.opcode_aliases {
+            parse_options = parse_options.with_opcode_aliases(opcode_aliases)?;
+        }
+        if let Some(comment_styles) = &language_params.comment_styles {
+            parse_options = parse_options.with_comment_styles(comment_styles.clone())?;
+        }
+        if let Some(lenient) = language_params.lenient {
+            parse_options = parse_options.with_lenient(lenient);
+        }
+        if let Some(lint_tabs) = language_params.lint_tabs {
+            parse_options = parse_options.with_lint_tabs(lint_tabs);
+        }
+        if let Some(max_document_bytes) = language_params.max_document_bytes {
+            parse_options = parse_options.with_max_document_bytes(Some(max_document_bytes));
+        }
+        if let Some(unknown_pragmas) = language_params.unknown_pragmas {
+            parse_options = parse_options.with_unknown_pragmas(unknown_pragmas);
+        }
+        Ok(RainLanguageServices {
             meta_store: language_params
                 .meta_store
                 .as_ref()
                 .map_or(Arc::new(RwLock::new(Store::default())), |s| s.clone()),
-        }
+            max_completions: language_params.max_completions,
+            parse_options,
+            diagnostic_options: DiagnosticOptions {
+                max_nesting_depth: language_params.max_nesting_depth,
+                unreachable_lines: language_params.unreachable_lines.clone(),
+                zero_outputs: language_params.zero_outputs.clone(),
+                unused_aliases: language_params.unused_aliases.unwrap_or(false),
+            },
+            hover_options: HoverOptions {
+                max_width: language_params.max_hover_width,
+                max_lines: language_params.max_hover_lines,
+            },
+            known_words: language_params.known_words.clone(),
+            #[cfg(feature = "js-api")]
+            meta_resolver: None,
+        })
     }
 
     /// Instantiates a RainDocument with remote meta search disabled when parsing from the given TextDocumentItem
@@ -164,12 +361,13 @@ impl RainLanguageServices {
         text_document: &TextDocumentItem,
         rebinds: Option<Vec<Rebind>>,
     ) -> RainDocument {
-        RainDocument::create(
-            text_document.text.clone(),
-            Some(self.meta_store.clone()),
-            None,
-            rebinds,
-        )
+        RainDocument::builder(text_document.text.clone())
+            .with_meta_store(Some(self.meta_store.clone()))
+            .with_words(self.known_words.clone())
+            .with_rebinds(rebinds)
+            .with_options(self.parse_options.clone())
+            .with_uri(text_document.uri.to_string())
+            .build()
     }
     /// Instantiates a RainDocument with remote meta search enabled when parsing from the given TextDocumentItem
     pub async fn new_rain_document_async(
@@ -177,13 +375,53 @@ impl RainLanguageServices {
         text_document: &TextDocumentItem,
         rebinds: Option<Vec<Rebind>>,
     ) -> RainDocument {
-        RainDocument::create_async(
-            text_document.text.clone(),
-            Some(self.meta_store.clone()),
-            None,
-            rebinds,
-        )
-        .await
+        RainDocument::builder(text_document.text.clone())
+            .with_meta_store(Some(self.meta_store.clone()))
+            .with_words(self.known_words.clone())
+            .with_rebinds(rebinds)
+            .with_options(self.parse_options.clone())
+            .with_uri(text_document.uri.to_string())
+            .build_async()
+            .await
+    }
+    /// Instantiates a RainDocument from the given TextDocumentItem by resolving its unresolved imports
+    /// (including the nested ones) through the given resolver that provides a meta hash's bytes, instead
+    /// of remote meta search, the resolved metas are cached in this instance's meta Store
+    pub async fn new_rain_document_with_resolver<F, Fut>(
+        &self,
+        text_document: &TextDocumentItem,
+        rebinds: Option<Vec<Rebind>>,
+        resolver: F,
+    ) -> RainDocument
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Option<Vec<u8>>>,
+    {
+        let mut tried_hashes = HashSet::new();
+        loop {
+            let rain_document = self.new_rain_document(text_document, rebinds.clone());
+            let mut hashes = vec![];
+            unresolved_imports(&rain_document, &mut hashes);
+            hashes.retain(|hash| tried_hashes.insert(hash.clone()));
+
+            // reparse only if at least one of the imports got resolved
+            let mut resolved = false;
+            for hash in hashes {
+                if let Some(bytes) = resolver(hash.clone()).await {
+                    if let Ok(hash_bytes) = alloy_primitives::hex::decode(&hash) {
+                        resolved |= self
+                            .meta_store
+                            .write()
+                            .unwrap()
+                            .update_with(&hash_bytes, &bytes)
+                            .is_some();
+                    }
+                }
+            }
+            if !resolved {
+                return rain_document;
+            }
+        }
     }
 
     /// Validates the document with remote meta search disabled when parsing and reports LSP diagnostics
@@ -193,13 +431,40 @@ impl RainLanguageServices {
         related_information: bool,
         rebinds: Option<Vec<Rebind>>,
     ) -> Vec<Diagnostic> {
-        let rain_document = RainDocument::create(
-            text_document.text.clone(),
-            Some(self.meta_store.clone()),
-            None,
-            rebinds,
-        );
-        diagnostic::get_diagnostics(&rain_document, &text_document.uri, related_information)
+        let rain_document = self.new_rain_document(text_document, rebinds);
+        diagnostic::get_diagnostics(
+            &rain_document,
+            &text_document.uri,
+            related_information,
+            &self.diagnostic_options,
+        )
+    }
+    /// Validates the document with remote meta search disabled when parsing and passes each of the
+    /// LSP diagnostics to the given sink as soon as it is available, the syntactic ones are passed
+    /// right after a syntax only parse and before the imports are resolved (unless any rebinds are
+    /// given), then the semantic and lastly the import resolution ones, the streamed diagnostics are the same as
+    /// [RainLanguageServices::do_validate] results, see [stream_diagnostics]
+    pub fn validate_streaming(
+        &self,
+        text_document: &TextDocumentItem,
+        related_information: bool,
+        rebinds: Option<Vec<Rebind>>,
+        sink: impl FnMut(Diagnostic),
+    ) {
+        let syntax_document = RainDocument::builder(text_document.text.clone())
+            .with_options(self.parse_options.clone())
+            .with_uri(text_document.uri.to_string())
+            .build_syntax_only();
+        let rebound = rebinds.clone().unwrap_or_default();
+        diagnostic::stream_diagnostics(
+            &syntax_document,
+            || self.new_rain_document(text_document, rebinds),
+            &rebound,
+            &text_document.uri,
+            related_information,
+            &self.diagnostic_options,
+            sink,
+        )
     }
     /// Validates the document with remote meta search enabled when parsing and reports LSP diagnostics
     pub async fn do_validate_async(
@@ -208,14 +473,101 @@ impl RainLanguageServices {
         related_information: bool,
         rebinds: Option<Vec<Rebind>>,
     ) -> Vec<Diagnostic> {
-        let rain_document = RainDocument::create_async(
-            text_document.text.clone(),
-            Some(self.meta_store.clone()),
-            None,
-            rebinds,
+        let rain_document = self.new_rain_document_async(text_document, rebinds).await;
+        diagnostic::get_diagnostics(
+            &rain_document,
+            &text_document.uri,
+            related_information,
+            &self.diagnostic_options,
+        )
+    }
+    /// Validates the document with remote meta search enabled when parsing and reports LSP diagnostics,
+    /// setting the given cancellation flag stops any further remote meta searches, in which case the
+    /// diagnostics are partial, i.e. the imports that were not resolved yet are reported as undefined,
+    /// see [RainDocumentBuilder::with_cancellation](dotrain::RainDocumentBuilder::with_cancellation)
+    pub async fn do_validate_async_with_cancellation(
+        &self,
+        text_document: &TextDocumentItem,
+        related_information: bool,
+        rebinds: Option<Vec<Rebind>>,
+        cancellation: Arc<AtomicBool>,
+    ) -> Vec<Diagnostic> {
+        let rain_document = RainDocument::builder(text_document.text.clone())
+            .with_meta_store(Some(self.meta_store.clone()))
+            .with_words(self.known_words.clone())
+            .with_rebinds(rebinds)
+            .with_options(self.parse_options.clone())
+            .with_cancellation(cancellation)
+            .build_async()
+            .await;
+        diagnostic::get_diagnostics(
+            &rain_document,
+            &text_document.uri,
+            related_information,
+            &self.diagnostic_options,
         )
-        .await;
-        diagnostic::get_diagnostics(&rain_document, &text_document.uri, related_information)
+    }
+    /// Validates the given documents with remote meta search enabled and reports their LSP
+    /// diagnostics keyed by their uris in the given order, the unresolved imports of all the
+    /// documents (including the nested ones) are searched concurrently and each only once, i.e.
+    /// the imports shared among the documents are not searched for each of them, the found metas
+    /// are cached in this instance's meta Store
+    pub async fn validate_many_async(
+        &self,
+        text_documents: &[TextDocumentItem],
+        related_information: bool,
+    ) -> Vec<(Url, Vec<Diagnostic>)> {
+        let subgraphs = self.meta_store.read().unwrap().subgraphs().clone();
+        let mut tried_hashes = HashSet::new();
+        loop {
+            let rain_documents: Vec<RainDocument> = text_documents
+                .iter()
+                .map(|v| self.new_rain_document(v, None))
+                .collect();
+            let mut hashes = vec![];
+            for rain_document in &rain_documents {
+                unresolved_imports(rain_document, &mut hashes);
+            }
+            hashes.retain(|hash| tried_hashes.insert(hash.clone()));
+            // there is nowhere to search in without any subgraphs
+            if subgraphs.is_empty() {
+                hashes.clear();
+            }
+
+            // reparse only if at least one of the imports got resolved
+            let searches = hashes
+                .iter()
+                .filter_map(|hash| alloy_primitives::hex::decode(hash).ok())
+                .map(|hash_bytes| {
+                    let mut store = Store::new();
+                    store.add_subgraphs(&subgraphs);
+                    async move {
+                        store.update(&hash_bytes).await;
+                        store
+                    }
+                });
+            let mut resolved = false;
+            for store in join_all(searches).await {
+                resolved |= !store.cache().is_empty();
+                self.meta_store.write().unwrap().merge(&store);
+            }
+            if !resolved {
+                return text_documents
+                    .iter()
+                    .zip(&rain_documents)
+                    .map(|(text_document, rain_document)| {
+                        (
+                            text_document.uri.clone(),
+                            self.do_validate_rain_document(
+                                rain_document,
+                                &text_document.uri,
+                                related_information,
+                            ),
+                        )
+                    })
+                    .collect();
+            }
+        }
     }
     /// Reports LSP diagnostics from RainDocument's all problems
     pub fn do_validate_rain_document(
@@ -224,7 +576,29 @@ impl RainLanguageServices {
         uri: &Url,
         related_information: bool,
     ) -> Vec<Diagnostic> {
-        diagnostic::get_diagnostics(rain_document, uri, related_information)
+        diagnostic::get_diagnostics(
+            rain_document,
+            uri,
+            related_information,
+            &self.diagnostic_options,
+        )
+    }
+    /// Reports LSP diagnostics of only the binding with the given name in the given RainDocument,
+    /// its problems are resolved in the context of the whole document's namespace
+    pub fn validate_binding(
+        &self,
+        rain_document: &RainDocument,
+        binding_name: &str,
+        uri: &Url,
+        related_information: bool,
+    ) -> Vec<Diagnostic> {
+        diagnostic::get_binding_diagnostics(
+            rain_document,
+            binding_name,
+            uri,
+            related_information,
+            &self.diagnostic_options,
+        )
     }
 
     /// Provides completion items at the given position
@@ -235,17 +609,13 @@ impl RainLanguageServices {
         documentation_format: Option<MarkupKind>,
         rebinds: Option<Vec<Rebind>>,
     ) -> Option<Vec<CompletionItem>> {
-        let rain_document = RainDocument::create(
-            text_document.text.clone(),
-            Some(self.meta_store.clone()),
-            None,
-            rebinds,
-        );
+        let rain_document = self.new_rain_document(text_document, rebinds);
         completion::get_completion(
             &rain_document,
             &text_document.uri,
             position,
             documentation_format.unwrap_or(MarkupKind::PlainText),
+            self.max_completions,
         )
     }
     /// Provides completion items at the given position
@@ -261,9 +631,17 @@ impl RainLanguageServices {
             uri,
             position,
             documentation_format.unwrap_or(MarkupKind::PlainText),
+            self.max_completions,
         )
     }
 
+    /// Resolves a completion item provided by this instance by filling in its documentation,
+    /// which is left out of the initial completion items of opcodes and expression bindings
+    /// to keep them lightweight
+    pub fn resolve_completion(&self, item: CompletionItem) -> CompletionItem {
+        completion::resolve_completion(item)
+    }
+
     /// Provides hover for a fragment at the given position
     pub fn do_hover(
         &self,
@@ -272,16 +650,12 @@ impl RainLanguageServices {
         content_format: Option<MarkupKind>,
         rebinds: Option<Vec<Rebind>>,
     ) -> Option<Hover> {
-        let rain_document = RainDocument::create(
-            text_document.text.clone(),
-            Some(self.meta_store.clone()),
-            None,
-            rebinds,
-        );
+        let rain_document = self.new_rain_document(text_document, rebinds);
         hover::get_hover(
             &rain_document,
             position,
             content_format.unwrap_or(MarkupKind::PlainText),
+            &self.hover_options,
         )
     }
     /// Provides hover for a RainDocument fragment at the given position
@@ -295,7 +669,112 @@ impl RainLanguageServices {
             rain_document,
             position,
             content_format.unwrap_or(MarkupKind::PlainText),
+            &self.hover_options,
+        )
+    }
+
+    /// Provides the workspace edit that organizes the imports of the document, i.e. sorts them,
+    /// removes the repeated ones and drops the unused ones
+    pub fn organize_imports(
+        &self,
+        text_document: &TextDocumentItem,
+        rebinds: Option<Vec<Rebind>>,
+    ) -> Option<WorkspaceEdit> {
+        let rain_document = self.new_rain_document(text_document, rebinds);
+        organize_imports::get_organize_imports(&rain_document, &text_document.uri)
+    }
+    /// Provides the workspace edit that organizes the imports of the RainDocument
+    pub fn organize_imports_rain_document(
+        &self,
+        rain_document: &RainDocument,
+        uri: &Url,
+    ) -> Option<WorkspaceEdit> {
+        organize_imports::get_organize_imports(rain_document, uri)
+    }
+
+    /// Verifies the given workspace edit (such as one produced by a code action) by applying its
+    /// text edits that target the given document and parsing the result with remote meta search
+    /// disabled, the edits are applied in reverse offset order so their ranges stay valid against
+    /// the original text
+    ///
+    /// Returns the edited RainDocument if the edit introduces no new problems, otherwise the
+    /// diagnostics of the problems that the edited text has and the original one does not
+    pub fn verify_edit(
+        &self,
+        text_document: &TextDocumentItem,
+        edit: &WorkspaceEdit,
+    ) -> Result<RainDocument, Vec<Diagnostic>> {
+        let mut original = self.do_validate(text_document, false, None);
+        let edited_text_document = TextDocumentItem {
+            text: apply_text_edits(
+                &text_document.text,
+                workspace_text_edits(edit, &text_document.uri),
+            ),
+            ..text_document.clone()
+        };
+        let rain_document = self.new_rain_document(&edited_text_document, None);
+        let introduced: Vec<Diagnostic> = diagnostic::get_diagnostics(
+            &rain_document,
+            &text_document.uri,
+            false,
+            &self.diagnostic_options,
         )
+        .into_iter()
+        .filter(|diagnostic| {
+            // positions are shifted by the edit, so only codes and messages are compared
+            match original
+                .iter()
+                .position(|v| v.code == diagnostic.code && v.message == diagnostic.message)
+            {
+                Some(i) => {
+                    original.remove(i);
+                    false
+                }
+                None => true,
+            }
+        })
+        .collect();
+        if introduced.is_empty() {
+            Ok(rain_document)
+        } else {
+            Err(introduced)
+        }
+    }
+
+    /// Prunes this instance's meta Store by removing the dotrain records of all uris other than
+    /// the given ones, which are usually the currently open documents, the meta of a removed
+    /// dotrain is kept only if one of the kept dotrains imports it (directly or nested)
+    pub fn prune_store(&self, keep_uris: &[Url]) {
+        let keep_uris: Vec<String> = keep_uris.iter().map(|v| v.to_string()).collect();
+        let mut pruned_uris = vec![];
+        let mut kept_texts = vec![];
+        {
+            let store = self.meta_store.read().unwrap();
+            for (uri, hash) in store.dotrain_cache() {
+                if keep_uris.contains(uri) {
+                    if let Some(text) = store.get_meta(hash).and_then(|v| dotrain_text(v)) {
+                        kept_texts.push(text);
+                    }
+                } else {
+                    pruned_uris.push(uri.clone());
+                }
+            }
+        }
+
+        let mut imported_hashes = HashSet::new();
+        for text in kept_texts {
+            let rain_document =
+                RainDocument::create(text, Some(self.meta_store.clone()), None, None);
+            resolved_imports(&rain_document, &mut imported_hashes);
+        }
+
+        let mut store = self.meta_store.write().unwrap();
+        for uri in pruned_uris {
+            let is_imported = store.get_dotrain_hash(&uri).is_some_and(|hash| {
+                imported_hashes.contains(&alloy_primitives::hex::encode_prefixed(hash))
+            });
+            store.delete_dotrain(&uri, is_imported);
+        }
     }
 
     /// Provides semantic tokens for elided fragments
@@ -306,12 +785,7 @@ impl RainLanguageServices {
         semantic_token_modifiers_len: usize,
         rebinds: Option<Vec<Rebind>>,
     ) -> SemanticTokensPartialResult {
-        let rain_document = RainDocument::create(
-            text_document.text.clone(),
-            Some(self.meta_store.clone()),
-            None,
-            rebinds,
-        );
+        let rain_document = self.new_rain_document(text_document, rebinds);
         get_semantic_token(
             &rain_document,
             semantic_token_types_index,
@@ -331,127 +805,332 @@ impl RainLanguageServices {
             semantic_token_modifiers_len,
         )
     }
+
+    /// Provides semantic tokens for elided fragments that intersect the given range
+    pub fn semantic_tokens_range(
+        &self,
+        text_document: &TextDocumentItem,
+        range: Range,
+        semantic_token_types_index: u32,
+        semantic_token_modifiers_len: usize,
+        rebinds: Option<Vec<Rebind>>,
+    ) -> SemanticTokensPartialResult {
+        let rain_document = self.new_rain_document(text_document, rebinds);
+        get_semantic_token_range(
+            &rain_document,
+            range,
+            semantic_token_types_index,
+            semantic_token_modifiers_len,
+        )
+    }
+    /// Provides semantic tokens for RainDocument's elided fragments that intersect the given range
+    pub fn rain_document_semantic_tokens_range(
+        &self,
+        rain_document: &RainDocument,
+        range: Range,
+        semantic_token_types_index: u32,
+        semantic_token_modifiers_len: usize,
+    ) -> SemanticTokensPartialResult {
+        get_semantic_token_range(
+            rain_document,
+            range,
+            semantic_token_types_index,
+            semantic_token_modifiers_len,
+        )
+    }
+}
+
+/// collects the hashes of the given RainDocument's imports that are not found, recursively
+fn unresolved_imports(rain_document: &RainDocument, hashes: &mut Vec<String>) {
+    for (i, imp) in rain_document.imports().iter().enumerate() {
+        if imp
+            .problems
+            .iter()
+            .any(|p| p.code == ErrorCode::UndefinedImport)
+        {
+            if !hashes.contains(&imp.hash) {
+                hashes.push(imp.hash.clone());
+            }
+        } else if let Some(dotrain) = rain_document.imported_document(i) {
+            unresolved_imports(dotrain, hashes);
+        }
+    }
+}
+
+/// collects the hashes of the given RainDocument's imports that are resolved, recursively
+fn resolved_imports(rain_document: &RainDocument, hashes: &mut HashSet<String>) {
+    for imp in rain_document.imports() {
+        if let Some(seq) = &imp.sequence {
+            hashes.insert(imp.hash.clone());
+            if let Some(dotrain) = &seq.dotrain {
+                resolved_imports(dotrain, hashes);
+            }
+        }
+    }
+}
+
+/// collects the text edits of the given workspace edit that target the given uri
+fn workspace_text_edits(edit: &WorkspaceEdit, uri: &Url) -> Vec<TextEdit> {
+    let mut edits: Vec<TextEdit> = edit
+        .changes
+        .as_ref()
+        .and_then(|changes| changes.get(uri))
+        .cloned()
+        .unwrap_or_default();
+    let document_edits: Vec<&TextDocumentEdit> = match &edit.document_changes {
+        Some(DocumentChanges::Edits(v)) => v.iter().collect(),
+        Some(DocumentChanges::Operations(v)) => v
+            .iter()
+            .filter_map(|op| match op {
+                DocumentChangeOperation::Edit(e) => Some(e),
+                DocumentChangeOperation::Op(_) => None,
+            })
+            .collect(),
+        None => vec![],
+    };
+    for document_edit in document_edits {
+        if document_edit.text_document.uri == *uri {
+            edits.extend(document_edit.edits.iter().map(|e| match e {
+                OneOf::Left(text_edit) => text_edit.clone(),
+                OneOf::Right(annotated) => annotated.text_edit.clone(),
+            }));
+        }
+    }
+    edits
+}
+
+/// applies the given text edits, whose ranges are all relative to the given text, in reverse
+/// offset order, edits at the same offset are applied so that they end up in their given order
+fn apply_text_edits(text: &str, edits: Vec<TextEdit>) -> String {
+    let line_offsets = line_offsets(text);
+    let mut edits: Vec<(usize, usize, String)> = edits
+        .into_iter()
+        .rev()
+        .map(|e| {
+            let start = offset_at_line_offsets(text, &line_offsets, &e.range.start);
+            let end = offset_at_line_offsets(text, &line_offsets, &e.range.end).max(start);
+            (start, end, e.new_text)
+        })
+        .collect();
+    edits.sort_by_key(|v| std::cmp::Reverse(v.0));
+    let mut result = text.to_owned();
+    for (start, end, new_text) in edits {
+        // ranges that do not fall on char boundaries cannot be applied
+        if result.get(start..end).is_some() {
+            result.replace_range(start..end, &new_text);
+        }
+    }
+    result
+}
+
+/// reads the dotrain text out of the given dotrain meta bytes
+fn dotrain_text(meta_bytes: &[u8]) -> Option<String> {
+    RainMetaDocumentV1Item::cbor_decode(meta_bytes)
+        .ok()?
+        .into_iter()
+        .find(|v| v.magic == KnownMagic::DotrainV1)?
+        .unpack()
+        .ok()
+        .and_then(|v| String::from_utf8(v).ok())
+}
+
+/// builds an opcode's documentation from its description with its documentation link appended,
+/// authoring meta has no dedicated field for it, so the link is the first url in the description,
+/// the description is returned as is if it has no url
+fn opcode_documentation(description: &str, kind: &MarkupKind) -> String {
+    if let Some(url) = DOCS_URL.find(description) {
+        let url = url
+            .as_str()
+            .trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        if *kind == MarkupKind::Markdown {
+            format!("{}\n\n[docs]({})", description, url)
+        } else {
+            format!("{}\n\ndocs: {}", description, url)
+        }
+    } else {
+        description.to_owned()
+    }
 }
 
 /// Trait for converting offset to lsp position (implemented for `&str` and `String`)
 pub trait PositionAt {
     fn position_at(&self, offset: usize) -> Position;
+    /// Converts the offset to lsp position with its character counted in the code units
+    /// of the given encoding, the offset itself is always a byte offset of the text, by default
+    /// the character is counted in UTF-8 code units, i.e. the same as [PositionAt::position_at],
+    /// whatever the given encoding is, so implementors override it to support other encodings
+    fn position_at_encoded(&self, offset: usize, _encoding: &PositionEncodingKind) -> Position {
+        self.position_at(offset)
+    }
+    /// Converts the offsets (start and end) to lsp range
+    fn range_of(&self, offsets: Offsets) -> Range {
+        Range::new(self.position_at(offsets[0]), self.position_at(offsets[1]))
+    }
 }
 
 /// Trait for converting lsp position to offset (implemented for `&str` and `String`)
 pub trait OffsetAt {
     fn offset_at(&self, position: &Position) -> usize;
+    /// Converts the lsp position with its character counted in the code units of the given
+    /// encoding to offset, the resulting offset is always a byte offset of the text, by default
+    /// the character is taken as counted in UTF-8 code units, i.e. the same as [OffsetAt::offset_at],
+    /// whatever the given encoding is, so implementors override it to support other encodings
+    fn offset_at_encoded(&self, position: &Position, _encoding: &PositionEncodingKind) -> usize {
+        self.offset_at(position)
+    }
 }
 
 impl PositionAt for &str {
     fn position_at(&self, offset: usize) -> Position {
-        let effective_offset = 0.max(offset.min(self.len()));
-        let mut line_offsets = vec![];
-        let mut acc = 0;
-        self.split_inclusive('\n').for_each(|v| {
-            line_offsets.push(acc);
-            acc += v.len();
-        });
-        let mut low = 0;
-        let mut high = line_offsets.len();
-        if high == 0 {
-            return Position {
-                line: 0,
-                character: effective_offset as u32,
-            };
-        }
-        while low < high {
-            let mid = (low + high) / 2;
-            if line_offsets[mid] > effective_offset {
-                high = mid;
-            } else {
-                low = mid + 1;
-            }
-        }
-        // low is the least x for which the line offset is larger than the current offset
-        // or array.length if no line offset is larger than the current offset
-        let line = low - 1;
-        Position {
-            line: line as u32,
-            character: (effective_offset - line_offsets[line]) as u32,
-        }
+        position_at_line_offsets(self, &line_offsets(self), offset)
+    }
+
+    fn position_at_encoded(&self, offset: usize, encoding: &PositionEncodingKind) -> Position {
+        position_at_encoded_line_offsets(self, &line_offsets(self), offset, encoding)
     }
 }
 
 impl OffsetAt for &str {
     fn offset_at(&self, position: &Position) -> usize {
-        let mut line_offsets = vec![];
-        let mut acc = 0;
-        self.split_inclusive('\n').for_each(|v| {
-            line_offsets.push(acc);
-            acc += v.len();
-        });
-        if position.line >= line_offsets.len() as u32 {
-            return self.len();
-        }
-        let line_offset = line_offsets[position.line as usize];
-        let next_line_offset = if position.line + 1 < line_offsets.len() as u32 {
-            line_offsets[position.line as usize + 1]
-        } else {
-            self.len()
-        };
-        line_offset.max((line_offset + position.character as usize).min(next_line_offset))
+        offset_at_line_offsets(self, &line_offsets(self), position)
+    }
+
+    fn offset_at_encoded(&self, position: &Position, encoding: &PositionEncodingKind) -> usize {
+        offset_at_encoded_line_offsets(self, &line_offsets(self), position, encoding)
     }
 }
 
 impl PositionAt for String {
     fn position_at(&self, offset: usize) -> Position {
-        let effective_offset = 0.max(offset.min(self.len()));
-        let mut line_offsets = vec![];
-        let mut acc = 0;
-        self.split_inclusive('\n').for_each(|v| {
-            line_offsets.push(acc);
-            acc += v.len();
-        });
-        let mut low = 0;
-        let mut high = line_offsets.len();
-        if high == 0 {
-            return Position {
-                line: 0,
-                character: effective_offset as u32,
-            };
-        }
-        while low < high {
-            let mid = (low + high) / 2;
-            if line_offsets[mid] > effective_offset {
-                high = mid;
-            } else {
-                low = mid + 1;
-            }
-        }
-        // low is the least x for which the line offset is larger than the current offset
-        // or array.length if no line offset is larger than the current offset
-        let line = low - 1;
-        Position {
-            line: line as u32,
-            character: (effective_offset - line_offsets[line]) as u32,
-        }
+        self.as_str().position_at(offset)
+    }
+
+    fn position_at_encoded(&self, offset: usize, encoding: &PositionEncodingKind) -> Position {
+        self.as_str().position_at_encoded(offset, encoding)
     }
 }
 
 impl OffsetAt for String {
     fn offset_at(&self, position: &Position) -> usize {
-        let mut line_offsets = vec![];
-        let mut acc = 0;
-        self.split_inclusive('\n').for_each(|v| {
-            line_offsets.push(acc);
-            acc += v.len();
-        });
-        if position.line >= line_offsets.len() as u32 {
-            return self.len();
-        }
-        let line_offset = line_offsets[position.line as usize];
-        let next_line_offset = if position.line + 1 < line_offsets.len() as u32 {
-            line_offsets[position.line as usize + 1]
-        } else {
-            self.len()
+        self.as_str().offset_at(position)
+    }
+
+    fn offset_at_encoded(&self, position: &Position, encoding: &PositionEncodingKind) -> usize {
+        self.as_str().offset_at_encoded(position, encoding)
+    }
+}
+
+/// Uses the line offsets that are cached when the RainDocument is parsed instead of
+/// computing them for each conversion
+impl PositionAt for RainDocument {
+    fn position_at(&self, offset: usize) -> Position {
+        position_at_line_offsets(self.text(), self.line_offsets(), offset)
+    }
+
+    fn position_at_encoded(&self, offset: usize, encoding: &PositionEncodingKind) -> Position {
+        position_at_encoded_line_offsets(self.text(), self.line_offsets(), offset, encoding)
+    }
+}
+
+/// Uses the line offsets that are cached when the RainDocument is parsed instead of
+/// computing them for each conversion
+impl OffsetAt for RainDocument {
+    fn offset_at(&self, position: &Position) -> usize {
+        offset_at_line_offsets(self.text(), self.line_offsets(), position)
+    }
+
+    fn offset_at_encoded(&self, position: &Position, encoding: &PositionEncodingKind) -> usize {
+        offset_at_encoded_line_offsets(self.text(), self.line_offsets(), position, encoding)
+    }
+}
+
+/// converts the offset to lsp position by binary search over the given line offsets of the text
+fn position_at_line_offsets(text: &str, line_offsets: &[usize], offset: usize) -> Position {
+    let effective_offset = offset.min(text.len());
+    if line_offsets.is_empty() {
+        return Position {
+            line: 0,
+            character: effective_offset as u32,
         };
-        line_offset.max((line_offset + position.character as usize).min(next_line_offset))
+    }
+    // the least index for which the line offset is larger than the current offset
+    // or array.length if no line offset is larger than the current offset
+    let line = line_offsets.partition_point(|&v| v <= effective_offset) - 1;
+    Position {
+        line: line as u32,
+        character: (effective_offset - line_offsets[line]) as u32,
+    }
+}
+
+/// converts the offset to lsp position with its character counted in the code units of the
+/// given encoding, using the given line offsets of the text
+fn position_at_encoded_line_offsets(
+    text: &str,
+    line_offsets: &[usize],
+    offset: usize,
+    encoding: &PositionEncodingKind,
+) -> Position {
+    let mut effective_offset = offset.min(text.len());
+    while !text.is_char_boundary(effective_offset) {
+        effective_offset -= 1;
+    }
+    let position = position_at_line_offsets(text, line_offsets, effective_offset);
+    let line_offset = effective_offset - position.character as usize;
+    Position {
+        line: position.line,
+        character: code_units_len(&text[line_offset..effective_offset], encoding),
+    }
+}
+
+/// converts the lsp position to offset using the given line offsets of the text
+fn offset_at_line_offsets(text: &str, line_offsets: &[usize], position: &Position) -> usize {
+    if position.line >= line_offsets.len() as u32 {
+        return text.len();
+    }
+    let line_offset = line_offsets[position.line as usize];
+    let next_line_offset = if position.line + 1 < line_offsets.len() as u32 {
+        line_offsets[position.line as usize + 1]
+    } else {
+        text.len()
+    };
+    line_offset.max((line_offset + position.character as usize).min(next_line_offset))
+}
+
+/// converts the lsp position with its character counted in the code units of the given
+/// encoding to offset, using the given line offsets of the text
+fn offset_at_encoded_line_offsets(
+    text: &str,
+    line_offsets: &[usize],
+    position: &Position,
+    encoding: &PositionEncodingKind,
+) -> usize {
+    let line_offset = offset_at_line_offsets(text, line_offsets, &Position::new(position.line, 0));
+    if line_offset == text.len() {
+        return line_offset;
+    }
+    let line = text[line_offset..]
+        .split_inclusive('\n')
+        .next()
+        .unwrap_or("");
+    let mut units = 0;
+    for (i, c) in line.char_indices() {
+        if units >= position.character {
+            return line_offset + i;
+        }
+        units += code_units_len(c.encode_utf8(&mut [0; 4]), encoding);
+    }
+    line_offset + line.len()
+}
+
+/// length of the given text in code units of the given encoding, unknown encodings are
+/// treated as utf-8
+fn code_units_len(text: &str, encoding: &PositionEncodingKind) -> u32 {
+    if *encoding == PositionEncodingKind::UTF16 {
+        text.encode_utf16().count() as u32
+    } else if *encoding == PositionEncodingKind::UTF32 {
+        text.chars().count() as u32
+    } else {
+        text.len() as u32
     }
 }
 
@@ -504,4 +1183,1723 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_opcode_operand_args_hover() -> anyhow::Result<()> {
+        let text = "---\n#exp\n_: opcode-1<1 2>(3);";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let position = Position {
+            line: 2,
+            character: 4,
+        };
+
+        let hover = get_hover(
+            &rain_document,
+            position,
+            MarkupKind::Markdown,
+            &HoverOptions::default(),
+        );
+        let expected_value = "| name | value | description |
+| --- | --- | --- |
+| operand arg | 1 |  |
+| operand arg | 2 |  |";
+        assert!(matches!(
+            hover,
+            Some(Hover {
+                contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent { value, .. }),
+                ..
+            }) if value == expected_value
+        ));
+
+        let hover = get_hover(
+            &rain_document,
+            position,
+            MarkupKind::PlainText,
+            &HoverOptions::default(),
+        );
+        let expected_value = "operand arg: 1\noperand arg: 2";
+        assert!(matches!(
+            hover,
+            Some(Hover {
+                contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent { value, .. }),
+                ..
+            }) if value == expected_value
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_operand_arg_value_hover() -> anyhow::Result<()> {
+        let text = "---\n#b 0x10\n#exp\n_: opcode-1<0x0a 2 b>(3);";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let hover = |character| {
+            get_hover(
+                &rain_document,
+                Position::new(3, character),
+                MarkupKind::PlainText,
+                &HoverOptions::default(),
+            )
+        };
+
+        let result = hover(14).unwrap();
+        assert_eq!(
+            result.contents,
+            lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
+                kind: MarkupKind::PlainText,
+                value: "operand arg\n\n---\n\n0x0a\n\nresolved value: 10".to_owned(),
+            })
+        );
+        assert_eq!(
+            result.range,
+            Some(lsp_types::Range::new(
+                Position::new(3, 12),
+                Position::new(3, 16)
+            ))
+        );
+        assert!(matches!(
+            hover(17).map(|v| v.contents),
+            Some(lsp_types::HoverContents::Markup(lsp_types::MarkupContent { value, .. }))
+                if value == "operand arg\n\n---\n\n2"
+        ));
+        assert!(matches!(
+            hover(19).map(|v| v.contents),
+            Some(lsp_types::HoverContents::Markup(lsp_types::MarkupContent { value, .. }))
+                if value == "operand arg\n\n---\n\nliteral binding\n\n---\n\n0x10\n\nresolved value: 16"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_literal_binding_hover() -> anyhow::Result<()> {
+        let text = "---\n#hex 0x0a\n#e 1e18\n#int 12\n#str \"abcd\"\n#exp\n_ _ _ _: hex e int str;";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let hover_value = |character| match get_hover(
+            &rain_document,
+            Position::new(6, character),
+            MarkupKind::PlainText,
+            &HoverOptions::default(),
+        ) {
+            Some(Hover {
+                contents: lsp_types::HoverContents::Markup(v),
+                ..
+            }) => v.value,
+            _ => String::new(),
+        };
+        assert_eq!(hover_value(10), "0x0a\n\nresolved value: 10");
+        assert_eq!(
+            hover_value(13),
+            "1e18\n\nresolved value: 1000000000000000000"
+        );
+        // already decimal or not numeric
+        assert_eq!(hover_value(16), "12");
+        assert_eq!(hover_value(20), "\"abcd\"");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_completions() -> anyhow::Result<()> {
+        let text = "---\n#a 1\n#b 2\n#c 3\n#exp\n_: a;";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let uri = Url::parse("file:///example.rain")?;
+        let position = Position {
+            line: 5,
+            character: 4,
+        };
+
+        let all = get_completion(&rain_document, &uri, position, MarkupKind::PlainText, None)
+            .unwrap_or_default();
+        assert!(all.len() > 2);
+
+        let result = get_completion(
+            &rain_document,
+            &uri,
+            position,
+            MarkupKind::PlainText,
+            Some(2),
+        )
+        .unwrap_or_default();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[..], all[..2]);
+        assert_eq!(result[0].label, "using-words-from");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_nesting_depth_diagnostics() -> anyhow::Result<()> {
+        let text = "---\n#exp\n_: add(add(add(1 2) 3) 4);";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let uri = Url::parse("file:///example.rain")?;
+
+        let base = get_diagnostics(&rain_document, &uri, false, &DiagnosticOptions::default());
+        let result = get_diagnostics(
+            &rain_document,
+            &uri,
+            false,
+            &DiagnosticOptions {
+                max_nesting_depth: Some(3),
+                ..Default::default()
+            },
+        );
+        assert_eq!(result, base);
+
+        let result = get_diagnostics(
+            &rain_document,
+            &uri,
+            false,
+            &DiagnosticOptions {
+                max_nesting_depth: Some(2),
+                ..Default::default()
+            },
+        );
+        assert_eq!(result.len(), base.len() + 1);
+        let warning = &result[result.len() - 1];
+        assert_eq!(
+            warning.severity,
+            Some(lsp_types::DiagnosticSeverity::WARNING)
+        );
+        assert_eq!(
+            warning.range,
+            lsp_types::Range::new(Position::new(2, 11), Position::new(2, 14))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_rain_document_with_resolver() -> anyhow::Result<()> {
+        // metas only available to the resolver, the first one importing the second one
+        let mut remote_store = Store::default();
+        let (nested_hash, _) =
+            remote_store.set_dotrain("---\n#value 4e18", "nested.rain", false)?;
+        let nested_hash = alloy_primitives::hex::encode_prefixed(nested_hash);
+        let (hash, _) = remote_store.set_dotrain(
+            &format!("---\n@nested {}\n#value 2", nested_hash),
+            "some.rain",
+            false,
+        )?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash);
+
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: format!("---\n@some {}\n#exp\n_: some.value;", hash),
+            version: 0,
+            language_id: "rainlang".to_owned(),
+        };
+        let lang_services = RainLanguageServices::default();
+
+        let rain_document = lang_services.new_rain_document(&text_document, None);
+        assert!(!rain_document.all_problems().is_empty());
+
+        let requested = RwLock::new(vec![]);
+        // the resolver futures are always ready so the whole future resolves on first poll
+        let rain_document = futures::FutureExt::now_or_never(
+            lang_services.new_rain_document_with_resolver(&text_document, None, |h| {
+                requested.write().unwrap().push(h.clone());
+                let bytes = alloy_primitives::hex::decode(&h)
+                    .ok()
+                    .and_then(|v| remote_store.get_meta(&v).cloned());
+                async move { bytes }
+            }),
+        )
+        .unwrap();
+        assert!(rain_document.all_problems().is_empty());
+        assert_eq!(*requested.read().unwrap(), vec![hash.clone(), nested_hash]);
+        assert!(lang_services
+            .meta_store()
+            .read()
+            .unwrap()
+            .get_meta(&alloy_primitives::hex::decode(&hash)?)
+            .is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_binding() -> anyhow::Result<()> {
+        let text = "---\n#a 1\n#exp1\n_: a;\n#exp2\n_: b;\n#exp3\n_: add(add(1 2) 3);";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let uri = Url::parse("file:///example.rain")?;
+        let lang_services = RainLanguageServices::new(&LanguageServiceParams {
+            max_nesting_depth: Some(1),
+            ..Default::default()
+        });
+
+        // resolved in the context of the whole document namespace
+        let result = lang_services.validate_binding(&rain_document, "exp1", &uri, false);
+        assert!(result.is_empty());
+
+        let result = lang_services.validate_binding(&rain_document, "exp2", &uri, false);
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].range,
+            lsp_types::Range::new(Position::new(5, 3), Position::new(5, 4))
+        );
+        assert_eq!(result[0].message, "undefined word: b");
+
+        let result = lang_services.validate_binding(&rain_document, "exp3", &uri, false);
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].severity,
+            Some(lsp_types::DiagnosticSeverity::WARNING)
+        );
+
+        let result = lang_services.validate_binding(&rain_document, "c", &uri, false);
+        assert!(result.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encoded_position_offset() -> anyhow::Result<()> {
+        // "é" is 2 bytes and 1 utf-16 code unit, "𝄞" is 4 bytes and 2 utf-16 code units
+        let text = "ab\nxé𝄞y\nz";
+        let utf16 = PositionEncodingKind::UTF16;
+        let utf32 = PositionEncodingKind::UTF32;
+        let utf8 = PositionEncodingKind::UTF8;
+
+        let y_offset = text.find('y').unwrap();
+        assert_eq!(
+            text.position_at_encoded(y_offset, &utf16),
+            Position::new(1, 4)
+        );
+        assert_eq!(
+            text.position_at_encoded(y_offset, &utf32),
+            Position::new(1, 3)
+        );
+        assert_eq!(
+            text.position_at_encoded(y_offset, &utf8),
+            text.position_at(y_offset)
+        );
+        assert_eq!(
+            text.offset_at_encoded(&Position::new(1, 4), &utf16),
+            y_offset
+        );
+        assert_eq!(
+            text.offset_at_encoded(&Position::new(1, 3), &utf32),
+            y_offset
+        );
+        assert_eq!(
+            text.offset_at_encoded(&Position::new(1, 7), &utf8),
+            text.offset_at(&Position::new(1, 7))
+        );
+
+        // out of line bounds character and line
+        assert_eq!(
+            text.offset_at_encoded(&Position::new(1, 99), &utf16),
+            y_offset + 2
+        );
+        assert_eq!(
+            text.offset_at_encoded(&Position::new(9, 0), &utf16),
+            text.len()
+        );
+        assert_eq!(
+            text.to_owned().position_at_encoded(text.len(), &utf16),
+            Position::new(2, 1)
+        );
+
+        // implementors of only the required methods get the utf-8 conversions
+        struct Lines(&'static str);
+        impl PositionAt for Lines {
+            fn position_at(&self, offset: usize) -> Position {
+                self.0.position_at(offset)
+            }
+        }
+        impl OffsetAt for Lines {
+            fn offset_at(&self, position: &Position) -> usize {
+                self.0.offset_at(position)
+            }
+        }
+        let lines = Lines(text);
+        assert_eq!(
+            lines.position_at_encoded(y_offset, &utf8),
+            text.position_at_encoded(y_offset, &utf8)
+        );
+        assert_eq!(
+            lines.offset_at_encoded(&Position::new(1, 7), &utf8),
+            text.offset_at_encoded(&Position::new(1, 7), &utf8)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_completion_sort_text() -> anyhow::Result<()> {
+        let text = "---\n#b 1\n#exp\nx: 1,\n_ _: x 2;";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let uri = Url::parse("file:///example.rain")?;
+        let position = Position {
+            line: 4,
+            character: 6,
+        };
+
+        let result = get_completion(&rain_document, &uri, position, MarkupKind::PlainText, None)
+            .unwrap_or_default();
+        let sort_texts: Vec<&str> = result
+            .iter()
+            .filter_map(|v| v.sort_text.as_deref())
+            .collect();
+        let expected = vec!["00_x", "01_using-words-from", "02_b", "02_exp"];
+        assert_eq!(sort_texts, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_word_pattern_params() -> anyhow::Result<()> {
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: "---\n#$amount 12\n#calc\n_: $amount;".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let mut params = LanguageServiceParams {
+            ..Default::default()
+        };
+
+        let lang_services = RainLanguageServices::new(&params);
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert_eq!(result.len(), 2);
+        assert!(result
+            .iter()
+            .all(|d| d.message == "invalid word pattern: $amount"));
+
+        params.word_pattern = Some(r"[a-z$][0-9a-z$-]*".to_string());
+        let lang_services = RainLanguageServices::try_new(&params)?;
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert!(result.is_empty());
+
+        params.word_pattern = Some(r"[a-z".to_string());
+        let result = RainLanguageServices::try_new(&params);
+        assert!(result.is_err_and(|e| e.to_string().starts_with("invalid word pattern: ")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_do_validate_async_with_cancellation() -> anyhow::Result<()> {
+        let hash = "0x6518ec1930d8846b093dcff41a6ee6f6352c72b82e48584cce741a9e8a6d6184";
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: format!("---\n@lib {hash}\n#exp\n_: 1;"),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let lang_services = RainLanguageServices::default();
+
+        // an already set flag means no remote search is started, so it resolves right away
+        let cancellation = Arc::new(AtomicBool::new(true));
+        let result =
+            futures::FutureExt::now_or_never(lang_services.do_validate_async_with_cancellation(
+                &text_document,
+                false,
+                None,
+                cancellation,
+            ))
+            .expect("expected to resolve without remote search");
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].message,
+            format!("cannot find any settlement for import: {hash}")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreachable_lines_diagnostics() -> anyhow::Result<()> {
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: "---\n#exp\na: 1,\nb: 2;".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let mut params = LanguageServiceParams {
+            ..Default::default()
+        };
+
+        // disabled by default
+        let lang_services = RainLanguageServices::new(&params);
+        assert!(lang_services
+            .do_validate(&text_document, false, None)
+            .is_empty());
+
+        params.unreachable_lines = Some(UnreachableLinesConfig {
+            outputs: 1,
+            side_effect_words: HashSet::new(),
+        });
+        let lang_services = RainLanguageServices::new(&params);
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].range,
+            lsp_types::Range::new(Position::new(3, 0), Position::new(3, 4))
+        );
+        assert_eq!(
+            result[0].severity,
+            Some(lsp_types::DiagnosticSeverity::WARNING)
+        );
+
+        let rain_document = lang_services.new_rain_document(&text_document, None);
+        let result =
+            lang_services.validate_binding(&rain_document, "exp", &text_document.uri, false);
+        assert_eq!(result.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_outputs_diagnostics() -> anyhow::Result<()> {
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: "---\n#exp\na: 1,\n: ensure(a);\n: 2;".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let words = AuthoringMeta(vec![
+            dotrain::rain_metadata::types::authoring::v1::AuthoringMetaItem {
+                word: "ensure".to_owned(),
+                operand_parser_offset: 0,
+                description: String::new(),
+            },
+        ]);
+        let mut params = LanguageServiceParams {
+            known_words: Some(words),
+            ..Default::default()
+        };
+
+        // disabled by default
+        let lang_services = RainLanguageServices::new(&params);
+        assert!(lang_services
+            .do_validate(&text_document, false, None)
+            .is_empty());
+
+        // runs without the unreachable lines analysis
+        params.zero_outputs = Some(ZeroOutputsConfig {
+            side_effect_words: HashSet::from(["ensure".to_owned()]),
+        });
+        let lang_services = RainLanguageServices::new(&params);
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].range,
+            lsp_types::Range::new(Position::new(4, 0), Position::new(4, 3))
+        );
+        assert_eq!(
+            result[0].severity,
+            Some(lsp_types::DiagnosticSeverity::WARNING)
+        );
+        assert_eq!(result[0].message, "expression source produces zero outputs");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unused_aliases_diagnostics() -> anyhow::Result<()> {
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: "---\n#exp\na _: 1 2,\nb: 3,\nc: a;".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let mut params = LanguageServiceParams {
+            ..Default::default()
+        };
+
+        // disabled by default
+        let lang_services = RainLanguageServices::new(&params);
+        assert!(lang_services
+            .do_validate(&text_document, false, None)
+            .is_empty());
+
+        params.unused_aliases = Some(true);
+        let lang_services = RainLanguageServices::new(&params);
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].range,
+            lsp_types::Range::new(Position::new(3, 0), Position::new(3, 1))
+        );
+        assert_eq!(
+            result[0].severity,
+            Some(lsp_types::DiagnosticSeverity::HINT)
+        );
+        assert_eq!(result[0].message, "unused alias: b");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_configuration_diagnostics() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#value 4e18", "some.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let lang_services = RainLanguageServices::new(&LanguageServiceParams {
+            meta_store: Some(Arc::new(RwLock::new(store))),
+            ..Default::default()
+        });
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: format!("---\n@lib {hash} valeu 12\n#exp\n_: lib.value;"),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].message, "undefined identifier: valeu");
+        assert_eq!(
+            result[0].range,
+            lsp_types::Range::new(Position::new(1, 72), Position::new(1, 77))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_store() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (lib_hash, _) = store.set_dotrain("---\n#value 4e18", "file:///lib.rain", false)?;
+        let (other_hash, _) = store.set_dotrain("---\n#value 1", "file:///other.rain", false)?;
+        let main_text = format!(
+            "---\n@lib {}\n#exp\n_: lib.value;",
+            alloy_primitives::hex::encode_prefixed(&lib_hash)
+        );
+        store.set_dotrain(&main_text, "file:///main.rain", false)?;
+        let lang_services = RainLanguageServices::new(&LanguageServiceParams {
+            meta_store: Some(Arc::new(RwLock::new(store))),
+            ..Default::default()
+        });
+
+        lang_services.prune_store(&[Url::parse("file:///main.rain")?]);
+        let meta_store = lang_services.meta_store();
+        let store = meta_store.read().unwrap();
+        assert_eq!(
+            store.dotrain_cache().keys().collect::<Vec<_>>(),
+            vec!["file:///main.rain"]
+        );
+        // the meta of the imported dotrain is kept while the other one is removed
+        assert!(store.get_meta(&lib_hash).is_some());
+        assert!(store.get_meta(&other_hash).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_organize_imports() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#value 4e18", "some.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let lang_services = RainLanguageServices::new(&LanguageServiceParams {
+            meta_store: Some(Arc::new(RwLock::new(store))),
+            ..Default::default()
+        });
+        let uri = Url::parse("file:///example.rain")?;
+        let mut text_document = TextDocumentItem {
+            uri: uri.clone(),
+            text: format!("---\n@unused {hash}\n@lib {hash}\n#exp\n_: lib.value;"),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+
+        let result = lang_services.organize_imports(&text_document, None);
+        let expected = WorkspaceEdit {
+            changes: Some(std::collections::HashMap::from([(
+                uri,
+                vec![lsp_types::TextEdit {
+                    range: lsp_types::Range::new(Position::new(1, 0), Position::new(2, 71)),
+                    new_text: format!("@lib {hash}"),
+                }],
+            )])),
+            document_changes: None,
+            change_annotations: None,
+        };
+        assert_eq!(result, Some(expected));
+
+        // already organized
+        text_document.text = format!("---\n@lib {hash}\n#exp\n_: lib.value;");
+        assert_eq!(lang_services.organize_imports(&text_document, None), None);
+
+        // a re-exporting import is kept even if it is not referenced
+        text_document.text = format!("---\n@lib {hash} value ^\n#exp\n_: 1;");
+        assert_eq!(lang_services.organize_imports(&text_document, None), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_occupied_namespace_related_information() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#value 5", "lib.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(&hash_bytes);
+        let meta_store = Arc::new(RwLock::new(store));
+        let uri = Url::parse("file:///example.rain")?;
+        let related = |text: String| {
+            let rain_document = RainDocument::create(text, Some(meta_store.clone()), None, None);
+            get_diagnostics(&rain_document, &uri, true, &DiagnosticOptions::default())
+                .into_iter()
+                .find(|v| {
+                    v.code
+                        == Some(lsp_types::NumberOrString::Number(
+                            ErrorCode::OccupiedNamespace.to_i32(),
+                        ))
+                })
+                .and_then(|v| v.related_information)
+                .and_then(|v| v.get(1).map(|v| (v.message.clone(), v.location.range)))
+        };
+
+        assert_eq!(
+            related(format!("---\n@lib {hash}\n#lib 1")),
+            Some((
+                "colliding import".to_owned(),
+                lsp_types::Range::new(Position::new(1, 1), Position::new(1, 4))
+            ))
+        );
+        assert_eq!(
+            related(format!("---\n@{hash}\n#value 1")),
+            Some((
+                "import of the colliding member".to_owned(),
+                lsp_types::Range::new(Position::new(1, 1), Position::new(1, 67))
+            ))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_related_information() -> anyhow::Result<()> {
+        let text = "---\n#a\n1\n#b\nx: 1,\nx: 2;\n#a\n2";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let uri = Url::parse("file:///example.rain")?;
+
+        let result = get_diagnostics(&rain_document, &uri, true, &DiagnosticOptions::default());
+        let first_occurrence = |code: ErrorCode| {
+            result
+                .iter()
+                .find(|v| v.code == Some(lsp_types::NumberOrString::Number(code.to_i32())))
+                .and_then(|v| v.related_information.as_ref())
+                .and_then(|v| v.get(1))
+                .map(|v| v.location.range)
+        };
+        assert_eq!(
+            first_occurrence(ErrorCode::DuplicateIdentifier),
+            Some(lsp_types::Range::new(
+                Position::new(1, 1),
+                Position::new(1, 2)
+            ))
+        );
+        assert_eq!(
+            first_occurrence(ErrorCode::DuplicateAlias),
+            Some(lsp_types::Range::new(
+                Position::new(4, 0),
+                Position::new(4, 1)
+            ))
+        );
+
+        let result = get_diagnostics(&rain_document, &uri, false, &DiagnosticOptions::default());
+        assert!(result.iter().all(|v| v.related_information.is_none()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_import_hash_related_information() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (hash_bytes, _) = store.set_dotrain("---\n#some-value 4e18", "some.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash_bytes);
+        let text = format!("---\n@ns1 {hash}\n@ns2 {hash}\n#binding\n_: 1;\n");
+        let rain_document =
+            RainDocument::create(text, Some(Arc::new(RwLock::new(store))), None, None);
+        let uri = Url::parse("file:///example.rain")?;
+
+        let result = get_diagnostics(&rain_document, &uri, true, &DiagnosticOptions::default());
+        assert_eq!(result.len(), 1);
+        let info = result[0].related_information.as_ref().unwrap();
+        assert_eq!(info[0].message, "same meta is already imported as: ns1");
+        assert_eq!(info[1].message, "first occurrence");
+        let first_import = rain_document.imports()[0].position;
+        assert_eq!(info[1].location.range, rain_document.range_of(first_import));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_opcode_documentation() -> anyhow::Result<()> {
+        let description = "Adds the inputs, see https://docs.rainlang.xyz/opcodes/add.";
+        assert_eq!(
+            opcode_documentation(description, &MarkupKind::Markdown),
+            format!("{description}\n\n[docs](https://docs.rainlang.xyz/opcodes/add)")
+        );
+        assert_eq!(
+            opcode_documentation(description, &MarkupKind::PlainText),
+            format!("{description}\n\ndocs: https://docs.rainlang.xyz/opcodes/add")
+        );
+
+        let description = "Adds the inputs (https://docs.rainlang.xyz/opcodes/add)";
+        assert_eq!(
+            opcode_documentation(description, &MarkupKind::Markdown),
+            format!("{description}\n\n[docs](https://docs.rainlang.xyz/opcodes/add)")
+        );
+
+        let description = "Adds the inputs";
+        assert_eq!(
+            opcode_documentation(description, &MarkupKind::Markdown),
+            description
+        );
+        assert_eq!(opcode_documentation("", &MarkupKind::Markdown), "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_semantic_tokens_range() -> anyhow::Result<()> {
+        let text = "---\n#a ! first\n#b ! second\n#exp\n_: a;\n#c ! third";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+
+        let full = get_semantic_token(&rain_document, 0, 1);
+        let range = lsp_types::Range::new(Position::new(0, 0), Position::new(6, 0));
+        assert_eq!(get_semantic_token_range(&rain_document, range, 0, 1), full);
+
+        let range = lsp_types::Range::new(Position::new(2, 0), Position::new(2, 11));
+        let result = get_semantic_token_range(&rain_document, range, 0, 1);
+        assert_eq!(
+            result.data,
+            vec![lsp_types::SemanticToken {
+                delta_line: 2,
+                delta_start: 4,
+                length: 7,
+                token_type: 0,
+                token_modifiers_bitset: 1,
+            }]
+        );
+
+        // the reference to the elided binding and the last binding
+        let range = lsp_types::Range::new(Position::new(4, 0), Position::new(5, 11));
+        let result = get_semantic_token_range(&rain_document, range, 0, 1);
+        assert_eq!(result.data.len(), 2);
+        assert_eq!(result.data[0].delta_line, 4);
+        assert_eq!(result.data[1].delta_line, 1);
+
+        let range = lsp_types::Range::new(Position::new(3, 0), Position::new(3, 4));
+        let result = get_semantic_token_range(&rain_document, range, 0, 1);
+        assert!(result.data.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_keyword_literals_params() -> anyhow::Result<()> {
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: "---\n#calc\n_: true;".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let mut params = LanguageServiceParams {
+            ..Default::default()
+        };
+        let lang_services = RainLanguageServices::new(&params);
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].message, "undefined word: true");
+
+        params.keyword_literals = Some(HashMap::from([("true".to_owned(), "1".to_owned())]));
+        let lang_services = RainLanguageServices::try_new(&params)?;
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert!(result.is_empty());
+
+        params.keyword_literals = Some(HashMap::from([("true".to_owned(), "yes".to_owned())]));
+        assert!(RainLanguageServices::try_new(&params).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_namespace_completion() -> anyhow::Result<()> {
+        let uri = Url::parse("file:///example.rain")?;
+        let deep_path = vec!["a"; 33].join(".");
+        let labels = |prefix: &str| {
+            let text = format!("---\n#a 1\n#exp\n_: {prefix}");
+            let rain_document = RainDocument::create(text.clone(), None, None, None);
+            let position = text.position_at(text.len());
+            get_completion(&rain_document, &uri, position, MarkupKind::PlainText, None)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|v| v.label)
+                .collect::<Vec<_>>()
+        };
+
+        // the root namespace
+        assert!(labels(".").contains(&"a".to_owned()));
+        for prefix in ["..", "a..b", &deep_path] {
+            assert!(!labels(prefix).contains(&"a".to_owned()));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_comment_styles_params() -> anyhow::Result<()> {
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: "---\n#calc\n_: 1; // note".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let mut params = LanguageServiceParams {
+            ..Default::default()
+        };
+        let lang_services = RainLanguageServices::new(&params);
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert!(!result.is_empty());
+
+        params.comment_styles = Some(vec![
+            CommentStyle::Block {
+                open: "/*".to_owned(),
+                close: "*/".to_owned(),
+            },
+            CommentStyle::Line {
+                prefix: "//".to_owned(),
+            },
+        ]);
+        let lang_services = RainLanguageServices::try_new(&params)?;
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert!(result.is_empty());
+
+        params.comment_styles = Some(vec![]);
+        assert!(RainLanguageServices::try_new(&params).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rain_document_cached_positions() -> anyhow::Result<()> {
+        let text = "---\n#a\n_: 1;\n\n#b\n_: add(1 2);\n";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        for offset in 0..=text.len() + 1 {
+            let position = text.position_at(offset);
+            assert_eq!(rain_document.position_at(offset), position);
+            assert_eq!(
+                rain_document.offset_at(&position),
+                text.offset_at(&position)
+            );
+        }
+        assert_eq!(
+            rain_document.offset_at(&Position::new(9, 0)),
+            text.offset_at(&Position::new(9, 0))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_embed_completion() -> anyhow::Result<()> {
+        let mut store = Store::default();
+        let (hash, _) = store.set_dotrain("---\n#value 1", "other.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash);
+        let meta_store = Arc::new(RwLock::new(store));
+        let uri = Url::parse("file:///example.rain")?;
+        let completions = |text: &str, position: Position| {
+            let rain_document =
+                RainDocument::create(text.to_owned(), Some(meta_store.clone()), None, None);
+            get_completion(&rain_document, &uri, position, MarkupKind::PlainText, None)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|v| {
+                    let Some(lsp_types::CompletionTextEdit::Edit(edit)) = v.text_edit else {
+                        panic!("expected a text edit");
+                    };
+                    (
+                        v.label,
+                        v.label_details.and_then(|v| v.description).unwrap(),
+                        edit.range,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // right after `@` both embed by path and by hash are suggested, the `@` is not replaced
+        let range = lsp_types::Range::new(Position::new(1, 1), Position::new(1, 1));
+        assert_eq!(
+            completions("---\n@\n#a 1", Position::new(1, 1)),
+            vec![
+                (hash.clone(), "embed by hash".to_owned(), range),
+                ("other.rain".to_owned(), "embed by path".to_owned(), range),
+            ]
+        );
+
+        // typed path after `@` is replaced without the `@`
+        let range = lsp_types::Range::new(Position::new(1, 1), Position::new(1, 4));
+        assert_eq!(
+            completions("---\n@oth\n#a 1", Position::new(1, 4)),
+            vec![("other.rain".to_owned(), "embed by path".to_owned(), range)]
+        );
+
+        // typed hash after `@` only suggests embed by hash
+        let range = lsp_types::Range::new(Position::new(1, 1), Position::new(1, 3));
+        assert_eq!(
+            completions("---\n@0x\n#a 1", Position::new(1, 3)),
+            vec![(hash.clone(), "embed by hash".to_owned(), range)]
+        );
+
+        // a new chunk started after whitespace
+        let range = lsp_types::Range::new(Position::new(1, 2), Position::new(1, 2));
+        assert_eq!(
+            completions("---\n@ \n#a 1", Position::new(1, 2)),
+            vec![
+                (hash.clone(), "embed by hash".to_owned(), range),
+                ("other.rain".to_owned(), "embed by path".to_owned(), range),
+            ]
+        );
+
+        // after the import name
+        let range = lsp_types::Range::new(Position::new(1, 8), Position::new(1, 10));
+        assert_eq!(
+            completions("---\n@ other 0x\n#a 1", Position::new(1, 10)),
+            vec![(hash.clone(), "embed by hash".to_owned(), range)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_edit() -> anyhow::Result<()> {
+        let uri = Url::parse("file:///example.rain")?;
+        let text_document = TextDocumentItem {
+            uri: uri.clone(),
+            text: "---\n#a 1\n#exp\n_: b;".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let lang_services = RainLanguageServices::new(&LanguageServiceParams {
+            ..Default::default()
+        });
+        let text_edit = |range: [u32; 4], new_text: &str| lsp_types::TextEdit {
+            range: Range::new(
+                Position::new(range[0], range[1]),
+                Position::new(range[2], range[3]),
+            ),
+            new_text: new_text.to_owned(),
+        };
+
+        // fixes the undefined identifier
+        let edit = WorkspaceEdit {
+            changes: Some(HashMap::from([(
+                uri.clone(),
+                vec![text_edit([3, 3, 3, 4], "a")],
+            )])),
+            ..Default::default()
+        };
+        let rain_document = lang_services.verify_edit(&text_document, &edit).unwrap();
+        assert_eq!(rain_document.text(), "---\n#a 1\n#exp\n_: a;");
+        assert!(rain_document.all_problems().is_empty());
+
+        // edits are applied against the original text, inserts at the same offset keep their order
+        let edit = WorkspaceEdit {
+            document_changes: Some(lsp_types::DocumentChanges::Edits(vec![
+                lsp_types::TextDocumentEdit {
+                    text_document: lsp_types::OptionalVersionedTextDocumentIdentifier {
+                        uri: uri.clone(),
+                        version: None,
+                    },
+                    edits: vec![
+                        lsp_types::OneOf::Left(text_edit([1, 1, 1, 2], "x")),
+                        lsp_types::OneOf::Left(text_edit([3, 3, 3, 4], "x")),
+                        lsp_types::OneOf::Left(text_edit([1, 4, 1, 4], "2")),
+                        lsp_types::OneOf::Left(text_edit([1, 4, 1, 4], "3")),
+                    ],
+                },
+            ])),
+            ..Default::default()
+        };
+        let rain_document = lang_services.verify_edit(&text_document, &edit).unwrap();
+        assert_eq!(rain_document.text(), "---\n#x 123\n#exp\n_: x;");
+
+        // introduces a new problem, the already existing one is not reported
+        let edit = WorkspaceEdit {
+            changes: Some(HashMap::from([(
+                uri.clone(),
+                vec![text_edit([3, 0, 3, 0], "_: c,\n")],
+            )])),
+            ..Default::default()
+        };
+        let result = lang_services.verify_edit(&text_document, &edit);
+        let messages: Vec<_> = result.unwrap_err().into_iter().map(|v| v.message).collect();
+        assert_eq!(messages, vec!["undefined word: c"]);
+
+        // edits of other documents are ignored
+        let edit = WorkspaceEdit {
+            changes: Some(HashMap::from([(
+                Url::parse("file:///other.rain")?,
+                vec![text_edit([3, 3, 3, 4], "c")],
+            )])),
+            ..Default::default()
+        };
+        let rain_document = lang_services.verify_edit(&text_document, &edit).unwrap();
+        assert_eq!(rain_document.text(), text_document.text);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_opcode_aliases_hover() -> anyhow::Result<()> {
+        let words = dotrain::rain_metadata::types::authoring::v1::AuthoringMeta(vec![
+            dotrain::rain_metadata::types::authoring::v1::AuthoringMetaItem {
+                word: "add".to_owned(),
+                operand_parser_offset: 0,
+                description: "adds the inputs".to_owned(),
+            },
+        ]);
+        let mut params = LanguageServiceParams {
+            opcode_aliases: Some(HashMap::from([("plus".to_owned(), "add".to_owned())])),
+            ..Default::default()
+        };
+        let lang_services = RainLanguageServices::try_new(&params)?;
+        let rain_document = RainDocument::builder("---\n#exp\n_: plus(1 2);".to_owned())
+            .with_words(Some(words))
+            .with_options(lang_services.parse_options().clone())
+            .build();
+        let hover = lang_services
+            .do_hover_rain_document(&rain_document, Position::new(2, 4), None)
+            .unwrap();
+        let lsp_types::HoverContents::Markup(content) = hover.contents else {
+            panic!("expected markup content");
+        };
+        assert_eq!(content.value, "alias of: add\n\nadds the inputs");
+
+        params.opcode_aliases = Some(HashMap::from([("plus".to_owned(), "+".to_owned())]));
+        assert!(RainLanguageServices::try_new(&params).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diagnostics_to_sarif() -> anyhow::Result<()> {
+        let text = "---\n#a 1\n#exp\n_: b,\n_: c;";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let problems: Vec<_> = rain_document.all_problems().into_iter().cloned().collect();
+        assert_eq!(problems.len(), 2);
+
+        let sarif = diagnostics_to_sarif(&problems, "example.rain", text);
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "dotrain");
+        // both problems share a single rule
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 1);
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "UndefinedWord");
+
+        let result = &run["results"][1];
+        assert_eq!(result["ruleId"], "UndefinedWord");
+        assert_eq!(result["ruleIndex"], 0);
+        assert_eq!(result["level"], "error");
+        assert_eq!(result["message"]["text"], "undefined word: c");
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "example.rain");
+        assert_eq!(
+            location["region"],
+            serde_json::json!({
+                "startLine": 5,
+                "startColumn": 4,
+                "endLine": 5,
+                "endColumn": 5,
+            })
+        );
+
+        let warning = dotrain::error::ErrorCode::UnreachableLine.to_problem(vec![], [0, 3]);
+        let sarif = diagnostics_to_sarif(&[warning], "example.rain", text);
+        assert_eq!(sarif["runs"][0]["results"][0]["level"], "warning");
+
+        let hint = dotrain::error::ErrorCode::UnusedAlias.to_problem(vec!["a"], [0, 3]);
+        let sarif = diagnostics_to_sarif(&[hint], "example.rain", text);
+        assert_eq!(sarif["runs"][0]["results"][0]["level"], "note");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_completion() -> anyhow::Result<()> {
+        let text = "---\n#a 1\n#exp\n_: add(1 2);\n#b\n_: a;";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let uri = Url::parse("file:///example.rain")?;
+        let position = Position {
+            line: 5,
+            character: 4,
+        };
+        let items = get_completion(&rain_document, &uri, position, MarkupKind::Markdown, None)
+            .unwrap_or_default();
+
+        // expression binding documentation is deferred to the resolve step
+        let exp = items.iter().find(|v| v.label == "exp").unwrap().clone();
+        assert!(exp.documentation.is_none());
+        assert!(exp.data.is_some());
+        let resolved = resolve_completion(exp);
+        assert_eq!(
+            resolved.documentation,
+            Some(lsp_types::Documentation::MarkupContent(
+                lsp_types::MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: "```rainlang\n_: add(1 2);\n```".to_owned(),
+                }
+            ))
+        );
+        assert!(resolved.data.is_none());
+
+        // already resolved items are returned as they are
+        assert_eq!(resolve_completion(resolved.clone()), resolved);
+        let a = items.iter().find(|v| v.label == "a").unwrap().clone();
+        assert!(a.documentation.is_some());
+        assert_eq!(resolve_completion(a.clone()), a);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_paren_related_information() -> anyhow::Result<()> {
+        let text = "---\n#a\n_: add(1 sub(2 3);\n#b\n_: add(sub(1 2)));";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let uri = Url::parse("file:///example.rain")?;
+
+        let result = get_diagnostics(&rain_document, &uri, true, &DiagnosticOptions::default());
+        let ranges = |code: ErrorCode| {
+            result
+                .iter()
+                .find(|v| v.code == Some(lsp_types::NumberOrString::Number(code.to_i32())))
+                .map(|v| {
+                    (
+                        v.range,
+                        v.related_information
+                            .as_ref()
+                            .and_then(|v| v.get(1))
+                            .map(|v| (v.message.clone(), v.location.range)),
+                    )
+                })
+        };
+        assert_eq!(
+            ranges(ErrorCode::ExpectedClosingParen),
+            Some((
+                lsp_types::Range::new(Position::new(2, 6), Position::new(2, 7)),
+                Some((
+                    "opcode of the unclosed \"(\"".to_owned(),
+                    lsp_types::Range::new(Position::new(2, 3), Position::new(2, 6))
+                ))
+            ))
+        );
+        assert_eq!(
+            ranges(ErrorCode::UnexpectedClosingParen),
+            Some((
+                lsp_types::Range::new(Position::new(4, 16), Position::new(4, 17)),
+                Some((
+                    "last matched \"(\"".to_owned(),
+                    lsp_types::Range::new(Position::new(4, 6), Position::new(4, 7))
+                ))
+            ))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_params() -> anyhow::Result<()> {
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: "---\n#calc\n_: 1,\n_: 2,\n;".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let mut params = LanguageServiceParams {
+            ..Default::default()
+        };
+        let lang_services = RainLanguageServices::new(&params);
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert_eq!(
+            result.iter().map(|v| v.code.clone()).collect::<Vec<_>>(),
+            vec![Some(lsp_types::NumberOrString::Number(
+                ErrorCode::InvalidEmptyLine.to_i32()
+            ))]
+        );
+
+        params.lenient = Some(true);
+        let lang_services = RainLanguageServices::new(&params);
+        let result = lang_services.do_validate(&text_document, false, None);
+        assert!(result.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncate_hover_value() -> anyhow::Result<()> {
+        let value = "```rainlang\n_: add(1 2),\n_: mul(3 4),\n_: sub(5 6);\n```\nfooter";
+        let md = MarkupKind::Markdown;
+
+        // no limits or within the limits
+        assert_eq!(truncate_hover_value(value, &md, None, None), value);
+        assert_eq!(truncate_hover_value(value, &md, Some(20), Some(6)), value);
+
+        // lines are truncated with an ellipsis, code fence delimiters are kept whole
+        assert_eq!(
+            truncate_hover_value(value, &md, Some(6), None),
+            "```rainlang\n_: ad…\n_: mu…\n_: su…\n```\nfooter"
+        );
+
+        // a cut code fence is closed
+        assert_eq!(
+            truncate_hover_value(value, &md, None, Some(4)),
+            "```rainlang\n_: add(1 2),\n…\n```"
+        );
+        // a fence left with no content is dropped
+        assert_eq!(truncate_hover_value(value, &md, None, Some(3)), "…");
+        // cut after the fence is closed
+        assert_eq!(
+            truncate_hover_value(value, &md, None, Some(5)),
+            "```rainlang\n_: add(1 2),\n_: mul(3 4),\n…\n```"
+        );
+
+        // plaintext has no code fences
+        let value = "```\na\nb\nc";
+        assert_eq!(
+            truncate_hover_value(value, &MarkupKind::PlainText, Some(2), Some(3)),
+            "`…\na\n…"
+        );
+
+        // through params
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: "---\n#exp\n_: add(1 2),\n_: mul(3 4);\n#x\n_: exp;".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let lang_services = RainLanguageServices::new(&LanguageServiceParams {
+            max_hover_lines: Some(4),
+            ..Default::default()
+        });
+        let hover = lang_services.do_hover(
+            &text_document,
+            Position::new(5, 4),
+            Some(MarkupKind::Markdown),
+            None,
+        );
+        let Some(Hover {
+            contents: lsp_types::HoverContents::Markup(content),
+            ..
+        }) = hover
+        else {
+            panic!("expected markup hover");
+        };
+        assert_eq!(content.value, "rainlang expression binding\n\n---\n…");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rebinding_value_completion() -> anyhow::Result<()> {
+        let mut store = Store::default();
+        let (hash, _) = store.set_dotrain("---\n#value 4e18\n#exp\n_: 1;", "lib.rain", false)?;
+        let hash = alloy_primitives::hex::encode_prefixed(hash);
+        let meta_store = Arc::new(RwLock::new(store));
+        let uri = Url::parse("file:///example.rain")?;
+        let completions = |config: &str| {
+            let text = format!("---\n@lib {hash} {config}\n#c 0x05\n#exp\n_: 1;");
+            let position = Position::new(1, (6 + hash.len() + config.len()) as u32);
+            let rain_document = RainDocument::create(text, Some(meta_store.clone()), None, None);
+            get_completion(&rain_document, &uri, position, MarkupKind::PlainText, None)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|v| (v.label, v.kind, v.insert_text))
+                .collect::<Vec<_>>()
+        };
+        let constants = vec![
+            (
+                "c".to_owned(),
+                Some(lsp_types::CompletionItemKind::CONSTANT),
+                Some("0x05".to_owned()),
+            ),
+            (
+                "lib.value".to_owned(),
+                Some(lsp_types::CompletionItemKind::CONSTANT),
+                Some("4e18".to_owned()),
+            ),
+        ];
+
+        // after a member name with no value yet and while typing the value
+        assert_eq!(completions("value "), constants);
+        // the typed value already rebinds the member
+        let mut rebound = constants.clone();
+        rebound[1].2 = Some("1".to_owned());
+        assert_eq!(completions("value 1"), rebound);
+
+        // on the member name
+        let labels: Vec<_> = completions("value").into_iter().map(|v| v.0).collect();
+        assert_eq!(labels, vec!["exp", "value"]);
+        // a new member name after a complete pair and the new name of a rename
+        assert!(completions("value 1 ")
+            .iter()
+            .all(|v| v.1 != Some(lsp_types::CompletionItemKind::CONSTANT)));
+        assert!(completions("'value ")
+            .iter()
+            .all(|v| v.1 != Some(lsp_types::CompletionItemKind::CONSTANT)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_completion_or_hover_in_comments() -> anyhow::Result<()> {
+        let text = "---\n#a 1\n#exp\n_: a /* a */;";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+        let uri = Url::parse("file:///example.rain")?;
+
+        // at the end of / on the alias "a" in the comment vs in the expression
+        let in_comment = Position::new(3, 9);
+        let in_expression = Position::new(3, 4);
+        assert!(get_completion(
+            &rain_document,
+            &uri,
+            in_comment,
+            MarkupKind::PlainText,
+            None
+        )
+        .is_none());
+        assert!(get_completion(
+            &rain_document,
+            &uri,
+            in_expression,
+            MarkupKind::PlainText,
+            None
+        )
+        .is_some());
+        assert!(get_hover(
+            &rain_document,
+            in_comment,
+            MarkupKind::PlainText,
+            &HoverOptions::default(),
+        )
+        .is_none());
+        assert!(get_hover(
+            &rain_document,
+            Position::new(3, 3),
+            MarkupKind::PlainText,
+            &HoverOptions::default(),
+        )
+        .is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trailing_dot_namespace_completion() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (bar_hash, _) = store.set_dotrain("---\n#baz 1\n#qux 2", "bar.rain", false)?;
+        let bar_hash = alloy_primitives::hex::encode_prefixed(bar_hash);
+        let (foo_hash, _) = store.set_dotrain(
+            &format!("---\n@bar {bar_hash}\n#value 3"),
+            "foo.rain",
+            false,
+        )?;
+        let foo_hash = alloy_primitives::hex::encode_prefixed(foo_hash);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let uri = Url::parse("file:///example.rain")?;
+        let labels = |expression: &str, suffix: &str| {
+            let text = format!("---\n@foo {foo_hash}\n#exp\n_: {expression}");
+            let rain_document = RainDocument::create(
+                format!("{text}{suffix}"),
+                Some(meta_store.clone()),
+                None,
+                None,
+            );
+            let position = text.position_at(text.len());
+            let mut labels =
+                get_completion(&rain_document, &uri, position, MarkupKind::PlainText, None)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|v| {
+                        v.detail
+                            .as_ref()
+                            .is_some_and(|d| d.starts_with("namespace") || d.contains("binding"))
+                    })
+                    .map(|v| v.label)
+                    .collect::<Vec<_>>();
+            labels.sort();
+            labels
+        };
+
+        for suffix in ["", ";", " 1;", "\n"] {
+            assert_eq!(labels("foo.", suffix), vec!["bar", "value"], "{suffix:?}");
+            assert_eq!(labels("foo.bar.", suffix), vec!["baz", "qux"], "{suffix:?}");
+        }
+        for expression in [".foo.", "add(foo.", "add(1 foo.", "_ _: foo."] {
+            assert_eq!(
+                labels(expression, ""),
+                vec!["bar", "value"],
+                "{expression:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_many_async() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (lib_hash, _) = store.set_dotrain("---\n#value 1", "file:///lib.rain", false)?;
+        let lib_hash = alloy_primitives::hex::encode_prefixed(&lib_hash);
+        let missing_hash = format!("0x{}", "ab".repeat(32));
+        let lang_services = RainLanguageServices::new(&LanguageServiceParams {
+            meta_store: Some(Arc::new(RwLock::new(store))),
+            ..Default::default()
+        });
+
+        let text_documents: Vec<TextDocumentItem> = [
+            format!("---\n@lib {lib_hash}\n#exp\n_: lib.value;"),
+            format!("---\n@lib {lib_hash}\n@missing {missing_hash}\n#exp\n_: lib.value;"),
+            "---\n#exp\n_: 1;".to_owned(),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| TextDocumentItem {
+            uri: Url::parse(&format!("file:///doc{i}.rain")).unwrap(),
+            text,
+            version: 0,
+            language_id: "rainlang".to_owned(),
+        })
+        .collect();
+
+        // with no subgraphs to search in nothing is searched, so the whole future resolves on
+        // first poll
+        let result = futures::FutureExt::now_or_never(
+            lang_services.validate_many_async(&text_documents, true),
+        )
+        .unwrap();
+        let expected: Vec<_> = text_documents
+            .iter()
+            .map(|v| (v.uri.clone(), lang_services.do_validate(v, true, None)))
+            .collect();
+        assert_eq!(result, expected);
+        assert!(result[0].1.is_empty());
+        assert_eq!(result[1].1.len(), 1);
+        assert!(result[2].1.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_known_words() -> anyhow::Result<()> {
+        use dotrain::rain_metadata::types::authoring::v1::AuthoringMetaItem;
+        let known_words = AuthoringMeta(vec![AuthoringMetaItem {
+            word: "add".to_owned(),
+            operand_parser_offset: 0,
+            description: "adds numbers".to_owned(),
+        }]);
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: "---\n#exp\n_: add(1 2);".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let lang_services = RainLanguageServices::new(&LanguageServiceParams {
+            known_words: Some(known_words.clone()),
+            ..Default::default()
+        });
+
+        let rain_document = lang_services.new_rain_document(&text_document, None);
+        assert_eq!(rain_document.known_words(), &Some(known_words));
+
+        let hover = lang_services.do_hover(
+            &text_document,
+            Position::new(2, 4),
+            Some(MarkupKind::PlainText),
+            None,
+        );
+        let Some(Hover {
+            contents: lsp_types::HoverContents::Markup(content),
+            ..
+        }) = hover
+        else {
+            panic!("expected markup hover");
+        };
+        assert!(content.value.contains("adds numbers"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_of() -> anyhow::Result<()> {
+        let text = "---\n#a\n_: 1;\n\n#b\n_: add(1 2);\n";
+        let rain_document = RainDocument::create(text.to_owned(), None, None, None);
+
+        // line boundaries, document start and end round trip
+        for offset in [0, 3, 4, 6, 7, 13, 14, 15, text.len() - 1, text.len()] {
+            assert_eq!(
+                rain_document.offset_at(&rain_document.position_at(offset)),
+                offset
+            );
+        }
+
+        let range = rain_document.range_of(rain_document.bindings()[1].content_position);
+        assert_eq!(range, Range::new(Position::new(5, 0), Position::new(5, 12)));
+        assert_eq!(
+            rain_document.offset_at(&range.end),
+            rain_document.bindings()[1].content_position[1]
+        );
+        assert_eq!(
+            text.range_of([4, 6]),
+            Range::new(Position::new(1, 0), Position::new(1, 2))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_streaming() -> anyhow::Result<()> {
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: "---\n@lib 0x1234\n#exp\na: x,\nb: 1,\n_: add(a 2;".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let lang_services = RainLanguageServices::new(&LanguageServiceParams {
+            unused_aliases: Some(true),
+            ..Default::default()
+        });
+
+        let mut streamed = vec![];
+        lang_services.validate_streaming(&text_document, true, None, |v| streamed.push(v));
+        let code = |v: &Diagnostic| match &v.code {
+            Some(lsp_types::NumberOrString::Number(code)) => *code,
+            _ => panic!("expected a number code"),
+        };
+        assert_eq!(
+            streamed.iter().map(code).collect::<Vec<_>>(),
+            vec![
+                ErrorCode::ExpectedClosingParen.to_i32(),
+                ErrorCode::UndefinedWord.to_i32(),
+                ErrorCode::UnusedAlias.to_i32(),
+                ErrorCode::UndefinedImport.to_i32(),
+            ]
+        );
+
+        // same set of diagnostics as do_validate
+        let mut expected = lang_services.do_validate(&text_document, true, None);
+        let key = |v: &Diagnostic| (code(v), v.range.start, v.range.end);
+        expected.sort_by_key(key);
+        streamed.sort_by_key(key);
+        assert_eq!(streamed, expected);
+
+        // an invalid rebind fails the full parse, so the syntax only problems are not streamed
+        let rebinds = Some(vec![Rebind("exp".to_owned(), "1".to_owned())]);
+        let mut streamed = vec![];
+        lang_services
+            .validate_streaming(&text_document, true, rebinds.clone(), |v| streamed.push(v));
+        assert!(streamed
+            .iter()
+            .any(|v| code(v) == ErrorCode::InvalidSuppliedRebindings.to_i32()));
+        let mut expected = lang_services.do_validate(&text_document, true, rebinds);
+        expected.sort_by_key(key);
+        streamed.sort_by_key(key);
+        assert_eq!(streamed, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_pragmas_params() -> anyhow::Result<()> {
+        let text_document = TextDocumentItem {
+            uri: Url::parse("file:///example.rain")?,
+            text: "---\nsome-pragma 0x1234\n#exp\n_: 1;".to_string(),
+            version: 0,
+            language_id: "rainlang".to_string(),
+        };
+        let mut params = LanguageServiceParams {
+            ..Default::default()
+        };
+        let expected_range = lsp_types::Range::new(Position::new(1, 0), Position::new(1, 18));
+
+        // errors by default
+        let result = RainLanguageServices::new(&params).do_validate(&text_document, false, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].range, expected_range);
+        assert_eq!(
+            result[0].severity,
+            Some(lsp_types::DiagnosticSeverity::ERROR)
+        );
+        assert_eq!(result[0].message, "undefined pragma: some-pragma");
+
+        params.unknown_pragmas = Some(UnknownPragmaPolicy::Warn);
+        let result = RainLanguageServices::new(&params).do_validate(&text_document, false, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].range, expected_range);
+        assert_eq!(
+            result[0].severity,
+            Some(lsp_types::DiagnosticSeverity::WARNING)
+        );
+        assert_eq!(result[0].message, "unknown pragma: some-pragma, ignored");
+
+        params.unknown_pragmas = Some(UnknownPragmaPolicy::Ignore);
+        assert!(RainLanguageServices::new(&params)
+            .do_validate(&text_document, false, None)
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_completion_filtering() -> anyhow::Result<()> {
+        let mut store = Store::new();
+        let (constants_hash, _) =
+            store.set_dotrain("---\n#baz 1\n#qux 2", "constants.rain", false)?;
+        let constants_hash = alloy_primitives::hex::encode_prefixed(constants_hash);
+        let (exps_hash, _) = store.set_dotrain("---\n#inner\n_: 1;", "exps.rain", false)?;
+        let exps_hash = alloy_primitives::hex::encode_prefixed(exps_hash);
+        let (foo_hash, _) = store.set_dotrain(
+            &format!(
+                "---\n@constants {constants_hash}\n@exps {exps_hash}\n#value 3\n#elided ! some msg\n#exp\n_: 1;\n#quote 'exp"
+            ),
+            "foo.rain",
+            false,
+        )?;
+        let foo_hash = alloy_primitives::hex::encode_prefixed(foo_hash);
+        let meta_store = Arc::new(RwLock::new(store));
+
+        let uri = Url::parse("file:///example.rain")?;
+        let labels = |expression: &str| {
+            let text = format!("---\n@foo {foo_hash}\n#main\n_: {expression}");
+            let rain_document =
+                RainDocument::create(text.clone(), Some(meta_store.clone()), None, None);
+            let position = text.position_at(text.len());
+            let mut labels =
+                get_completion(&rain_document, &uri, position, MarkupKind::PlainText, None)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|v| {
+                        v.detail
+                            .as_ref()
+                            .is_some_and(|d| d.starts_with("namespace") || d.contains("binding"))
+                    })
+                    .map(|v| v.label)
+                    .collect::<Vec<_>>();
+            labels.sort();
+            labels
+        };
+
+        // only expression and quote bindings and the namespaces containing them
+        for expression in ["'foo.", "call<'foo."] {
+            assert_eq!(
+                labels(expression),
+                vec!["exp", "exps", "quote"],
+                "{expression:?}"
+            );
+        }
+        assert_eq!(labels("'foo.exps."), vec!["inner"]);
+        assert!(labels("'foo.constants.").is_empty());
+
+        // not filtered outside of quotes
+        assert_eq!(
+            labels("foo."),
+            vec!["constants", "elided", "exp", "exps", "quote", "value"]
+        );
+
+        Ok(())
+    }
 }
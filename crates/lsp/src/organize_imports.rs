@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+use super::PositionAt;
+use dotrain::RainDocument;
+use lsp_types::{TextEdit, Url, WorkspaceEdit};
+
+/// Provides the workspace edit that organizes the given RainDocument's imports, i.e. sorts them,
+/// removes the repeated ones and drops the unused ones, see [RainDocument::organize_imports],
+/// None if there are no imports or they are already organized
+pub fn get_organize_imports(rain_document: &RainDocument, uri: &Url) -> Option<WorkspaceEdit> {
+    let (range, new_text) = rain_document.organize_imports()?;
+    if rain_document.span_text(range)? == new_text {
+        return None;
+    }
+    Some(WorkspaceEdit {
+        changes: Some(HashMap::from([(
+            uri.clone(),
+            vec![TextEdit {
+                range: rain_document.range_of(range),
+                new_text,
+            }],
+        )])),
+        document_changes: None,
+        change_annotations: None,
+    })
+}
@@ -0,0 +1,83 @@
+use serde_json::{json, Value};
+use super::PositionAt;
+use lsp_types::PositionEncodingKind;
+use dotrain::{error::ErrorCode, types::ast::Problem};
+
+/// the SARIF version that is emitted
+const SARIF_VERSION: &str = "2.1.0";
+
+/// the SARIF 2.1.0 json schema
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Converts the given problems of the text at the given uri to a [SARIF](https://sarifweb.azurewebsites.net/)
+/// 2.1.0 log with a single run, so they can be consumed by CI tools such as GitHub code scanning
+///
+/// Each problem's [ErrorCode] name is used as its rule id, hints are reported with `note` level,
+/// other warnings with `warning` level and all others with `error` level, regions are 1-based lines and columns counted in UTF-16 code units
+/// as SARIF defaults to
+pub fn diagnostics_to_sarif(problems: &[Problem], uri: &str, text: &str) -> Value {
+    let mut codes: Vec<ErrorCode> = vec![];
+    for problem in problems {
+        if !codes.contains(&problem.code) {
+            codes.push(problem.code);
+        }
+    }
+    let rules: Vec<Value> = codes
+        .iter()
+        .map(|code| {
+            let description = code.describe();
+            json!({
+                "id": description.name,
+                "name": description.name,
+                "shortDescription": { "text": description.template },
+                "properties": { "code": description.code },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = problems
+        .iter()
+        .map(|problem| {
+            let start = text.position_at_encoded(problem.position[0], &PositionEncodingKind::UTF16);
+            let end = text.position_at_encoded(problem.position[1], &PositionEncodingKind::UTF16);
+            let level = if problem.code.is_hint() {
+                "note"
+            } else if problem.code.is_warning() {
+                "warning"
+            } else {
+                "error"
+            };
+            json!({
+                "ruleId": format!("{:?}", problem.code),
+                "ruleIndex": codes.iter().position(|v| *v == problem.code),
+                "level": level,
+                "message": { "text": problem.msg },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                        "region": {
+                            "startLine": start.line + 1,
+                            "startColumn": start.character + 1,
+                            "endLine": end.line + 1,
+                            "endColumn": end.character + 1,
+                        },
+                    },
+                }],
+            })
+        })
+        .collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "dotrain",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
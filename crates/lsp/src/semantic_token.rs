@@ -1,6 +1,6 @@
 use std::collections::BTreeSet;
 use super::{OffsetAt, PositionAt};
-use lsp_types::{SemanticTokensPartialResult, Position, SemanticToken};
+use lsp_types::{SemanticTokensPartialResult, Position, Range, SemanticToken};
 use dotrain::{error::ErrorCode, types::ast::BindingItem, RainDocument};
 
 #[derive(Eq, PartialEq, Copy, Clone, PartialOrd, Ord, Debug)]
@@ -16,44 +16,75 @@ pub fn get_semantic_token(
     token_types_index: u32,
     token_modifiers_len: usize,
 ) -> SemanticTokensPartialResult {
+    encode(
+        &get_ranges(rain_document, None),
+        token_types_index,
+        token_modifiers_len,
+    )
+}
+
+/// Provides semantic tokens for RainDocument's elided fragments that intersect the given range,
+/// only the bindings intersecting the range are processed, so it is well suited for providing
+/// the tokens of the visible range of large documents
+/// token_modifiers bit are set with provided token_modifiers_len ((2 ^ len) - 1) such as a length of 3 equals to 00000111
+pub fn get_semantic_token_range(
+    rain_document: &RainDocument,
+    range: Range,
+    token_types_index: u32,
+    token_modifiers_len: usize,
+) -> SemanticTokensPartialResult {
+    let ranges = get_ranges(
+        rain_document,
+        Some([
+            rain_document.offset_at(&range.start),
+            rain_document.offset_at(&range.end),
+        ]),
+    );
+    let ranges: BTreeSet<OrdRange> = ranges
+        .into_iter()
+        .filter(|v| v.start < range.end && v.end > range.start)
+        .collect();
+    encode(&ranges, token_types_index, token_modifiers_len)
+}
+
+/// collects the ranges of the elided fragments of the bindings that intersect the given offsets,
+/// or of all bindings if not specified
+fn get_ranges(rain_document: &RainDocument, offsets: Option<[usize; 2]>) -> BTreeSet<OrdRange> {
     let mut ranges: BTreeSet<OrdRange> = BTreeSet::new();
     for binding in rain_document.bindings() {
+        if let Some([start, end]) = offsets {
+            if binding.position[0] > end || binding.position[1] < start {
+                continue;
+            }
+        }
         match &binding.item {
             BindingItem::Exp(_) => {
                 binding.problems.iter().for_each(|p| {
                     if p.code == ErrorCode::ElidedBinding {
                         ranges.insert(OrdRange {
-                            start: rain_document.text().position_at(p.position[0]),
-                            end: rain_document.text().position_at(p.position[1]),
+                            start: rain_document.position_at(p.position[0]),
+                            end: rain_document.position_at(p.position[1]),
                         });
                     }
                 });
             }
             BindingItem::Elided(_) => {
-                let start = rain_document
-                    .text()
-                    .position_at(binding.content_position[0] + 1);
-                let end = rain_document
-                    .text()
-                    .position_at(binding.content_position[1]);
+                let start = rain_document.position_at(binding.content_position[0] + 1);
+                let end = rain_document.position_at(binding.content_position[1]);
                 if start.line == end.line {
                     ranges.insert(OrdRange { start, end });
                 } else {
                     ranges.insert(OrdRange {
                         start,
-                        end: rain_document.text().position_at(
-                            rain_document
-                                .text()
-                                .offset_at(&Position::new(start.line + 1, 0))
-                                - 1,
+                        end: rain_document.position_at(
+                            rain_document.offset_at(&Position::new(start.line + 1, 0)) - 1,
                         ),
                     });
                     for i in start.line + 1..end.line {
                         ranges.insert(OrdRange {
                             start: Position::new(i, 0),
-                            end: rain_document.text().position_at(
-                                rain_document.text().offset_at(&Position::new(i + 1, 0)) - 1,
-                            ),
+                            end: rain_document
+                                .position_at(rain_document.offset_at(&Position::new(i + 1, 0)) - 1),
                         });
                     }
                     ranges.insert(OrdRange {
@@ -68,7 +99,15 @@ pub fn get_semantic_token(
             _ => {}
         }
     }
+    ranges
+}
 
+/// encodes the given ranges into semantic tokens
+fn encode(
+    ranges: &BTreeSet<OrdRange>,
+    token_types_index: u32,
+    token_modifiers_len: usize,
+) -> SemanticTokensPartialResult {
     let mut last_char = 0u32;
     let mut last_line = 0u32;
     let token_modifiers_bitset = 2u32.pow(8u32.min(token_modifiers_len as u32)) - 1;